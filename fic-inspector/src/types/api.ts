@@ -16,19 +16,31 @@ export interface FieldInfo {
   name: string;
   offset: number;
   length: number;
-  field_type: 'String' | 'Integer' | 'Float' | 'Date' | 'Memo' | 'Binary' | 'Unknown';
+  field_type: 'String' | 'Unicode' | 'Integer' | 'Unsigned' | 'Boolean' | 'Float' | 'Currency' | 'Numeric' | 'Date' | 'Time' | 'DateTime' | 'Duration' | 'Memo' | 'Binary' | 'Unknown';
+  encoding?: string;
+  scale?: number;
 }
 
 export interface Record {
   id: number;
   fields: Record<string, FieldValue>;
-  memo_data: Record<string, string>;
+  memo_data: Record<string, MemoValue>;
 }
 
+export type MemoValue =
+  | { type: 'text'; value: string }
+  | { type: 'binary'; mime_type: string; size: number };
+
 export type FieldValue = 
   | { type: 'string'; value: string }
   | { type: 'integer'; value: number }
   | { type: 'float'; value: number }
+  | { type: 'boolean'; value: boolean }
+  | { type: 'decimal'; value: string } // décimal exact
+  | { type: 'date'; value: string } // ISO-8601
+  | { type: 'time'; value: string } // ISO-8601
+  | { type: 'datetime'; value: string } // ISO-8601
+  | { type: 'duration'; value: string } // ISO-8601
   | { type: 'binary'; value: string } // hex string
   | { type: 'null'; value: null };
 
@@ -36,7 +48,7 @@ export interface QueryResult {
   records: Record[];
   total: number;
   offset: number;
-  limit: number;
+  limit: number | null; // null : sans limite
 }
 
 export interface QueryFilters {
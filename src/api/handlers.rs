@@ -6,16 +6,22 @@
  * 
  * - health : Vérification de santé du serveur
  * - activity : Historique d'activité (bases de données et DSN)
+ * - tables/:table/schema : Lecture et édition du schéma d'une table
+ * - tables/:table/records/:id/memos/:field : Contenu brut d'un mémo
+ * - analysis : Analyse du projet (fichiers, clés, liaisons)
  * 
  * Liens avec d'autres modules :
  * - Les endpoints SQL et ODBC sont gérés par src/sql/server.rs
  */
 
 use crate::api::server::AppState;
+use crate::core::wdd::Analysis;
+use crate::core::{FieldInfo, TableSchema};
 use crate::logger::{get_logger, LogLevel};
 use axum::{
     extract::{Path, Query, State},
-    response::Json,
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -601,3 +607,203 @@ pub async fn get_migration_status(
         Json(None)
     }
 }
+
+/// Requête d'enregistrement du schéma d'une table
+#[derive(Deserialize)]
+pub struct SaveSchemaRequest {
+    /// Rubriques de la table (offsets byte de flags inclus)
+    pub fields: Vec<FieldInfo>,
+}
+
+/// Réponse avec le schéma d'une table
+#[derive(Serialize)]
+pub struct TableSchemaResponse {
+    /// Succès de l'opération
+    pub success: bool,
+    /// Schéma effectif de la table
+    pub schema: Option<TableSchema>,
+    /// Fichier de schéma utilisé (None : schéma détecté dans le .fic)
+    pub override_path: Option<String>,
+    /// Message d'erreur éventuel
+    pub error: Option<String>,
+}
+
+type SchemaResult = Result<Json<TableSchemaResponse>, (StatusCode, Json<TableSchemaResponse>)>;
+
+/// Réponse d'erreur des endpoints de schéma (table inconnue : 404)
+fn schema_error(state: &AppState, table: &str, status: StatusCode, error: anyhow::Error) -> (StatusCode, Json<TableSchemaResponse>) {
+    let status = if state.engine.list_tables().iter().any(|t| t == table) {
+        status
+    } else {
+        StatusCode::NOT_FOUND
+    };
+    get_logger().log_with_source(LogLevel::Error, format!("Schéma de {}: {:#}", table, error), Some("API".to_string()));
+    (status, Json(TableSchemaResponse {
+        success: false,
+        schema: None,
+        override_path: None,
+        error: Some(format!("{:#}", error)),
+    }))
+}
+
+/// Schéma effectif et fichier de schéma d'une table
+fn schema_response(state: &AppState, table: &str) -> anyhow::Result<TableSchemaResponse> {
+    let schema = state.engine.get_schema(table)?;
+    let override_path = state.engine.schema_override(table)?
+        .map(|(path, _)| path.to_string_lossy().to_string());
+    Ok(TableSchemaResponse {
+        success: true,
+        schema: Some(schema),
+        override_path,
+        error: None,
+    })
+}
+
+/**
+ * Handler GET /tables/:table/schema - Schéma effectif d'une table.
+ * 
+ * @param state - État de l'application (injecté par Axum)
+ * @param table - Nom de la table
+ * @returns SchemaResult - Schéma et fichier de schéma éventuel ; 404 si la
+ *   table est inconnue, 400 si le fichier de schéma est invalide
+ * 
+ * Effets de bord :
+ * - Lit le fichier .fic et le fichier de schéma
+ */
+pub async fn get_table_schema(
+    State(state): State<AppState>,
+    Path(table): Path<String>,
+) -> SchemaResult {
+    schema_response(&state, &table)
+        .map(Json)
+        .map_err(|e| schema_error(&state, &table, StatusCode::BAD_REQUEST, e))
+}
+
+/**
+ * Handler PUT /tables/:table/schema - Enregistre le schéma d'une table
+ * dans son fichier `<table>.schema.toml/.json`.
+ * 
+ * @param state - État de l'application (injecté par Axum)
+ * @param table - Nom de la table
+ * @param request - Rubriques à enregistrer
+ * @returns SchemaResult - Nouveau schéma effectif ; 400 si les rubriques sont
+ *   invalides ou si le serveur est en lecture seule
+ * 
+ * Effets de bord :
+ * - Crée ou remplace le fichier de schéma
+ */
+pub async fn save_table_schema(
+    State(state): State<AppState>,
+    Path(table): Path<String>,
+    Json(request): Json<SaveSchemaRequest>,
+) -> SchemaResult {
+    state.engine.save_schema_override(&table, request.fields)
+        .and_then(|_| schema_response(&state, &table))
+        .map(Json)
+        .map_err(|e| schema_error(&state, &table, StatusCode::BAD_REQUEST, e))
+}
+
+/**
+ * Handler DELETE /tables/:table/schema - Supprime le fichier de schéma
+ * (retour au schéma détecté dans le .fic).
+ * 
+ * @param state - État de l'application (injecté par Axum)
+ * @param table - Nom de la table
+ * @returns SchemaResult - Schéma effectif après suppression
+ * 
+ * Effets de bord :
+ * - Supprime le fichier de schéma
+ */
+pub async fn delete_table_schema(
+    State(state): State<AppState>,
+    Path(table): Path<String>,
+) -> SchemaResult {
+    state.engine.delete_schema_override(&table)
+        .and_then(|_| schema_response(&state, &table))
+        .map(Json)
+        .map_err(|e| schema_error(&state, &table, StatusCode::BAD_REQUEST, e))
+}
+
+/// Réponse avec l'analyse du projet
+#[derive(Serialize)]
+pub struct AnalysisResponse {
+    /// Succès de l'opération (false si le projet n'a pas d'analyse)
+    pub success: bool,
+    /// Analyse chargée lors du dernier scan
+    pub analysis: Option<Analysis>,
+    /// Message d'erreur éventuel
+    pub error: Option<String>,
+}
+
+/**
+ * Handler GET /analysis - Analyse du projet.
+ * 
+ * Retourne les fichiers décrits (schémas, clés) et les liaisons entre
+ * fichiers, utilisées comme clés étrangères.
+ * 
+ * @param state - État de l'application (injecté par Axum)
+ * @returns Result<Json<AnalysisResponse>, ...> - Analyse, 404 si aucune
+ *   analyse n'a été trouvée
+ * 
+ * Effets de bord : Aucun
+ */
+pub async fn get_analysis(
+    State(state): State<AppState>,
+) -> Result<Json<AnalysisResponse>, (StatusCode, Json<AnalysisResponse>)> {
+    match state.engine.analysis() {
+        Some(analysis) => Ok(Json(AnalysisResponse {
+            success: true,
+            analysis: Some(analysis.as_ref().clone()),
+            error: None,
+        })),
+        None => Err((StatusCode::NOT_FOUND, Json(AnalysisResponse {
+            success: false,
+            analysis: None,
+            error: Some("Aucune analyse (.wdd) chargée".to_string()),
+        }))),
+    }
+}
+
+/// Réponse d'erreur de l'endpoint des mémos
+#[derive(Serialize)]
+pub struct MemoErrorResponse {
+    /// Toujours false
+    pub success: bool,
+    /// Message d'erreur
+    pub error: String,
+}
+
+/**
+ * Handler GET /tables/:table/records/:id/memos/:field - Contenu brut d'un
+ * mémo (photo, document ou texte), servi avec son type MIME.
+ * 
+ * @param state - État de l'application (injecté par Axum)
+ * @param table - Nom de la table
+ * @param id - Identifiant de l'enregistrement
+ * @param field - Nom de la rubrique mémo
+ * @returns Result<Response, ...> - Octets du mémo ; 404 si la table ou le
+ *   mémo n'existe pas, 400 pour une rubrique invalide
+ * 
+ * Effets de bord :
+ * - Lit les fichiers .fic et .mmo
+ */
+pub async fn get_memo(
+    State(state): State<AppState>,
+    Path((table, id, field)): Path<(String, u32, String)>,
+) -> Result<Response, (StatusCode, Json<MemoErrorResponse>)> {
+    let error = |status: StatusCode, message: String| (status, Json(MemoErrorResponse {
+        success: false,
+        error: message,
+    }));
+    if !state.engine.list_tables().contains(&table) {
+        return Err(error(StatusCode::NOT_FOUND, format!("Table '{}' non trouvée", table)));
+    }
+    match state.engine.read_memo(&table, id, &field) {
+        Ok(Some(memo)) => Ok(([(header::CONTENT_TYPE, memo.mime_type)], memo.data).into_response()),
+        Ok(None) => Err(error(StatusCode::NOT_FOUND, format!("Aucun mémo {} pour l'enregistrement {}", field, id))),
+        Err(e) => {
+            get_logger().log_with_source(LogLevel::Error, format!("Mémo {}.{}: {:#}", table, field, e), Some("API".to_string()));
+            Err(error(StatusCode::BAD_REQUEST, format!("{:#}", e)))
+        }
+    }
+}
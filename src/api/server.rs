@@ -7,6 +7,8 @@
  * 
  * Endpoints exposés :
  * - GET /health : Vérification de santé du serveur
+ * - GET/PUT/DELETE /tables/:table/schema : Schéma d'une table (fichier de schéma)
+ * - GET /analysis : Analyse du projet (schémas, clés, liaisons)
  * - POST /sql : Exécution de requêtes SQL
  * - POST /odbc/tables : Liste des tables ODBC
  * - POST /odbc/relations : Relations entre tables ODBC
@@ -47,6 +49,8 @@ pub struct AppState {
     pub engine: Arc<StorageEngine>,
     /// Gestionnaire d'activité
     pub tracker: Arc<ActivityTracker>,
+    /// Transactions SQL ouvertes par les sessions (BEGIN)
+    pub sql_sessions: Arc<sql_server::SqlSessions>,
 }
 
 /**
@@ -77,10 +81,15 @@ pub async fn start_server(engine: Arc<StorageEngine>, host: &str, port: u16) ->
             .context("Impossible de créer le gestionnaire d'activité")?
     );
 
+    // Annuler en tâche de fond les transactions SQL abandonnées, pour libérer leurs tables
+    let sql_sessions = Arc::new(sql_server::SqlSessions::new());
+    tokio::spawn(sql_server::reap_sessions(Arc::downgrade(&sql_sessions)));
+
     // Créer l'état global de l'application
     let app_state = AppState {
         engine: engine.clone(),
         tracker,
+        sql_sessions,
     };
 
     let app = Router::new()
@@ -88,6 +97,11 @@ pub async fn start_server(engine: Arc<StorageEngine>, host: &str, port: u16) ->
         .route("/activity", get(handlers::get_activity))
         .route("/logs", get(handlers::get_logs))
         .route("/scan", post(handlers::scan_directory))
+        .route("/tables/:table/schema", get(handlers::get_table_schema))
+        .route("/tables/:table/schema", put(handlers::save_table_schema))
+        .route("/tables/:table/schema", delete(handlers::delete_table_schema))
+        .route("/tables/:table/records/:id/memos/:field", get(handlers::get_memo))
+        .route("/analysis", get(handlers::get_analysis))
         .route("/sql", post(sql_server::execute_sql))
         .route("/odbc/tables", post(sql_server::get_odbc_tables))
         .route("/odbc/relations", post(sql_server::get_odbc_relations))
@@ -128,6 +142,10 @@ pub async fn start_server(engine: Arc<StorageEngine>, host: &str, port: u16) ->
         "GET  /activity - Historique d'activité",
         "GET  /logs - Logs de l'application",
         "POST /scan - Scanner un dossier et lister les fichiers .fic",
+        "GET  /tables/:table/schema - Schéma d'une table",
+        "PUT  /tables/:table/schema - Enregistrer le schéma d'une table",
+        "DELETE /tables/:table/schema - Revenir au schéma détecté",
+        "GET  /analysis - Analyse du projet (schémas, clés, liaisons)",
         "POST /sql - Exécuter des requêtes SQL",
         "POST /odbc/tables - Liste des tables ODBC",
         "POST /odbc/relations - Relations entre tables ODBC",
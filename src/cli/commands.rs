@@ -7,6 +7,7 @@
  * - scan_tables : Détecte et liste les tables HFSQL dans un dossier
  * - export_table : Exporte les données d'une table vers JSON ou CSV
  * - debug_file : Affiche des informations de debug sur un fichier
+ * - infer_schema : Déduit le schéma d'un fichier .fic sans description
  * 
  * Liens avec d'autres modules :
  * - Utilise src/storage/engine.rs pour accéder aux données
@@ -77,13 +78,7 @@ pub async fn export_table(
 ) -> Result<()> {
     info!("Export de la table '{}' au format {}", table, format);
 
-    let filters = QueryFilters {
-        limit: None,
-        offset: None,
-        field_filters: std::collections::HashMap::new(),
-    };
-
-    let result = engine.select(&table, filters)?;
+    let result = engine.select(&table, QueryFilters::default())?;
 
     match format.as_str() {
         "json" => {
@@ -135,15 +130,19 @@ pub async fn export_table(
  * - "raw" : Affiche un dump hexadécimal complet du header
  * - "records" : Affiche les premiers enregistrements
  * 
+ * Pour un .mmo, "header" et "blocks" parcourent tous les blocs et les
+ * confrontent aux pointeurs mémo du .fic de même nom (voir debug_mmo).
+ * 
  * @param file - Chemin du fichier à analyser
- * @param dump - Type de dump à effectuer (header, hex, raw, records)
+ * @param dump - Type de dump à effectuer (header, hex, raw, records, blocks)
+ * @param json - Sortie JSON (fichiers .mmo)
  * @returns Result<()> - Succès si l'analyse s'est bien déroulée, erreur sinon
  * 
  * Effets de bord :
  * - Lit le fichier depuis le système de fichiers
  * - Affiche des informations sur stdout
  */
-pub async fn debug_file(file: PathBuf, dump: String) -> Result<()> {
+pub async fn debug_file(file: PathBuf, dump: String, json: bool) -> Result<()> {
     if let Some(ext) = file.extension() {
         let ext_lower = ext.to_string_lossy().to_lowercase();
         match ext_lower.as_str() {
@@ -188,16 +187,26 @@ pub async fn debug_file(file: PathBuf, dump: String) -> Result<()> {
                     }
                 }
             }
-            "mmo" => {
-                let _mmo = crate::core::MmoFile::open(&file)?;
-                println!("Fichier .mmo ouvert: {:?}", file);
-                // TODO: Dump du contenu
-            }
+            "mmo" => match dump.as_str() {
+                "header" | "blocks" => debug_mmo(&file, json)?,
+                _ => anyhow::bail!("Type de dump inconnu pour un .mmo: {}. Options: header, blocks", dump),
+            },
             "ndx" => {
-                let ndx = crate::core::NdxFile::open(&file)?;
-                println!("Entrées dans l'index: {}", ndx.entries().len());
-                for entry in ndx.entries().iter().take(10) {
-                    println!("  Key: {:?}, Record ID: {}", entry.key, entry.record_id);
+                let mut ndx = crate::core::NdxFile::open(&file)?;
+                println!("Taille de page: {} bytes", ndx.page_size());
+                println!("Clés dans l'index: {}", ndx.keys().len());
+                for key in ndx.keys().to_vec() {
+                    println!(
+                        "\nClé {}: {} entrées, longueur {}, racine page {}{}",
+                        key.number, key.entry_count, key.key_length, key.root_page,
+                        if key.unique { ", unique" } else { "" }
+                    );
+                    for component in &key.components {
+                        println!("  Rubrique: offset {}, longueur {}, type {:?}", component.offset, component.length, component.key_type);
+                    }
+                    for entry in ndx.range(key.number, std::ops::Bound::Unbounded, std::ops::Bound::Unbounded, Some(10))? {
+                        println!("  Key: {:?}, Record ID: {}", entry.key, entry.record_id);
+                    }
                 }
             }
             _ => {
@@ -211,3 +220,151 @@ pub async fn debug_file(file: PathBuf, dump: String) -> Result<()> {
     Ok(())
 }
 
+/**
+ * Parcourt les blocs d'un fichier .mmo et affiche, pour chacun, son offset,
+ * sa longueur, son type, l'encodage probable et un aperçu.
+ * 
+ * Si un .fic de même nom existe, les pointeurs mémo de ses enregistrements
+ * actifs sont comparés aux blocs : mémos orphelins (référencés par aucun
+ * enregistrement) et pointeurs invalides sont signalés. Les rubriques mémo
+ * viennent du fichier de schéma, sinon de la description du .fic, sinon de
+ * l'inférence du schéma.
+ * 
+ * @param file - Chemin du fichier .mmo
+ * @param json - Affiche le rapport en JSON
+ * @returns Result<()> - Succès si le parcours s'est bien déroulé, erreur sinon
+ * 
+ * Effets de bord :
+ * - Lit les fichiers .mmo et .fic
+ * - Affiche le rapport sur stdout
+ */
+fn debug_mmo(file: &std::path::Path, json: bool) -> Result<()> {
+    use crate::core::mmo::{BlockType, MemoReference};
+    use crate::core::{schema_file, FicFile, MmoFile};
+
+    let mut mmo = MmoFile::open(file)?;
+    let fic_path = ["fic", "FIC"]
+        .iter()
+        .map(|ext| file.with_extension(ext))
+        .find(|path| path.exists());
+    let references = match &fic_path {
+        Some(fic_path) => {
+            let mut fic = FicFile::open(fic_path)?;
+            let name = file.file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let fields = match schema_file::find_schema_file(&name, fic_path, None) {
+                Some(path) => schema_file::read_schema_file(&path)?,
+                None if fic.has_descriptor() => fic.analyze_schema(),
+                None => crate::core::infer::infer_schema(&name, &mut fic, Some(&mut mmo), crate::core::infer::DEFAULT_SAMPLE_SIZE)?
+                    .schema
+                    .fields,
+            };
+            let references: Vec<MemoReference> = fic.read_all_records()?
+                .iter()
+                .flat_map(|record| {
+                    FicFile::memo_pointers(&record.data, &fields)
+                        .into_iter()
+                        .map(|pointer| MemoReference { record: record.id, field: pointer.field, offset: pointer.offset })
+                })
+                .collect();
+            Some(references)
+        }
+        None => None,
+    };
+    let report = mmo.inspect(references.as_deref())?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    match report.block_size {
+        Some(block_size) => println!("Fichier: {:?} (blocs de {} bytes, {} blocs)", file, block_size, report.blocks.len()),
+        None => println!("Fichier: {:?} (ancien format, {} blocs)", file, report.blocks.len()),
+    }
+    println!("{:>10}  {:<12} {:>8} {:>10} {:>8}  {:<24} Aperçu", "Offset", "Type", "Utilisés", "Longueur", "Suivant", "Encodage");
+    for block in &report.blocks {
+        let description = match (&block.encoding, &block.mime_type) {
+            (Some(encoding), _) => encoding.clone(),
+            (None, Some(mime)) => mime.clone(),
+            (None, None) => String::new(),
+        };
+        println!(
+            "{:>10}  {:<12} {:>8} {:>10} {:>8}  {:<24} {}",
+            block.offset,
+            format!("{:?}", block.block_type),
+            block.used,
+            block.length.map(|l| l.to_string()).unwrap_or_default(),
+            block.next.map(|n| n.to_string()).unwrap_or_default(),
+            description,
+            block.error.as_ref().map(|e| format!("ERREUR: {}", e)).or_else(|| block.preview.clone()).unwrap_or_default(),
+        );
+    }
+
+    let memos = report.blocks.iter().filter(|b| matches!(b.block_type, BlockType::Text | BlockType::Binary)).count();
+    println!("\nMémos: {}", memos);
+    if !report.references_checked {
+        println!("Aucun .fic de même nom : références non vérifiées");
+        return Ok(());
+    }
+    println!("Pointeurs des enregistrements: {}", report.reference_count);
+    println!("Mémos orphelins: {}", report.orphaned.len());
+    for offset in &report.orphaned {
+        println!("  offset {}", offset);
+    }
+    println!("Pointeurs invalides: {}", report.dangling.len());
+    for reference in &report.dangling {
+        println!("  enregistrement {}, rubrique {} -> offset {}", reference.record, reference.field, reference.offset);
+    }
+    Ok(())
+}
+
+/**
+ * Déduit et affiche le schéma d'un fichier .fic.
+ * 
+ * Analyse un échantillon d'enregistrements (voir src/core/infer.rs). Le
+ * fichier .mmo de même nom, s'il existe, sert à reconnaître les pointeurs
+ * mémo.
+ * 
+ * @param file - Chemin du fichier .fic
+ * @param sample - Nombre maximal d'enregistrements analysés
+ * @param json - Affiche le résultat en JSON plutôt qu'en tableau
+ * @returns Result<()> - Succès si l'analyse s'est bien déroulée, erreur sinon
+ * 
+ * Effets de bord :
+ * - Lit les fichiers .fic et .mmo
+ * - Affiche le schéma sur stdout
+ */
+pub async fn infer_schema(file: PathBuf, sample: usize, json: bool) -> Result<()> {
+    let mut fic = crate::core::FicFile::open(&file)?;
+    let mut mmo = ["mmo", "MMO"]
+        .iter()
+        .map(|ext| file.with_extension(ext))
+        .find(|path| path.exists())
+        .map(crate::core::MmoFile::open)
+        .transpose()?;
+    let name = file.file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let inferred = crate::core::infer::infer_schema(&name, &mut fic, mmo.as_mut(), sample)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&inferred)?);
+        return Ok(());
+    }
+
+    if fic.has_descriptor() {
+        println!("Note: le fichier contient une description des rubriques (schéma inféré pour comparaison)");
+    }
+    println!("Table: {} ({} bytes par enregistrement, {} enregistrements analysés)",
+             inferred.schema.name, inferred.schema.record_length, inferred.sampled);
+    println!("{:<10} {:>7} {:>7}  {:<8} {:>9}", "Champ", "Offset", "Taille", "Type", "Confiance");
+    for (field, confidence) in inferred.schema.fields.iter().zip(&inferred.confidence) {
+        println!("{:<10} {:>7} {:>7}  {:<8} {:>8.0}%",
+                 field.name, field.offset, field.length, format!("{:?}", field.field_type), confidence * 100.0);
+    }
+
+    Ok(())
+}
@@ -73,9 +73,23 @@ pub enum Commands {
     Debug {
         /// Chemin du fichier à analyser
         file: PathBuf,
-        /// Type de dump (header, hex, raw, records)
+        /// Type de dump (header, hex, raw, records ; header ou blocks pour un .mmo)
         #[arg(short = 't', long, default_value = "header")]
         dump: String,
+        /// Sortie JSON (fichiers .mmo)
+        #[arg(long)]
+        json: bool,
+    },
+    /// Déduit le schéma d'un fichier .fic à partir de ses enregistrements
+    InferSchema {
+        /// Chemin du fichier .fic à analyser
+        file: PathBuf,
+        /// Nombre maximal d'enregistrements analysés
+        #[arg(short, long, default_value_t = crate::core::infer::DEFAULT_SAMPLE_SIZE)]
+        sample: usize,
+        /// Sortie JSON (schéma et confiances)
+        #[arg(long)]
+        json: bool,
     },
 }
 
@@ -84,7 +98,7 @@ impl Cli {
      * Exécute la commande CLI sélectionnée par l'utilisateur.
      * 
      * Charge la configuration, détermine le dossier de données, puis
-     * exécute la commande appropriée (scan, export, serve, debug).
+     * exécute la commande appropriée (scan, export, serve, debug, infer-schema).
      * 
      * @returns Result<()> - Succès si l'exécution s'est bien déroulée, erreur sinon
      * 
@@ -112,19 +126,24 @@ impl Cli {
                 commands::scan_tables(path).await
             }
             Commands::Export { table, format, output } => {
-                let engine = crate::storage::StorageEngine::new_with_parallel(&data_dir, true, settings.storage.parallel)?;
+                // Configuration [storage] complète (encodages, schémas, analyse), sans écriture
+                let storage = crate::config::StorageSettings { read_only: true, ..settings.storage.clone() };
+                let engine = crate::storage::StorageEngine::from_settings(&data_dir, &storage)?;
                 engine.scan_tables()?;
                 commands::export_table(engine, table, format, output).await
             }
             Commands::Serve { port, host } => {
                 let engine = std::sync::Arc::new(
-                    crate::storage::StorageEngine::new_with_parallel(&data_dir, settings.storage.read_only, settings.storage.parallel)?
+                    crate::storage::StorageEngine::from_settings(&data_dir, &settings.storage)?
                 );
                 engine.scan_tables()?;
                 crate::api::start_server(engine, &host, port).await
             }
-            Commands::Debug { file, dump } => {
-                commands::debug_file(file, dump).await
+            Commands::Debug { file, dump, json } => {
+                commands::debug_file(file, dump, json).await
+            }
+            Commands::InferSchema { file, sample, json } => {
+                commands::infer_schema(file, sample, json).await
             }
         }
     }
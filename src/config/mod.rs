@@ -17,5 +17,5 @@
 
 pub mod settings;
 
-pub use settings::Settings;
+pub use settings::{Settings, StorageSettings};
 
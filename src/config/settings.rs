@@ -17,6 +17,7 @@
 
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 /// Configuration principale de l'application
@@ -48,11 +49,31 @@ pub struct ApiSettings {
 pub struct StorageSettings {
     /// Active le mode lecture seule (désactive les modifications)
     pub read_only: bool,
-    /// Active l'écriture (redondant avec read_only, à supprimer si non utilisé)
+    /// Autorise les écritures (INSERT/UPDATE/DELETE) ; sans effet si read_only est actif
     pub enable_write: bool,
     /// Active le multi-threading pour la lecture parallèle (améliore les performances)
     #[serde(default = "default_parallel")]
     pub parallel: bool,
+    /// Projette les .fic en mémoire pour les parcours ; à réserver aux
+    /// fichiers qu'aucune autre application ne modifie (une troncature
+    /// pendant un parcours fait échouer le serveur)
+    #[serde(default)]
+    pub mmap: bool,
+    /// Dossier des fichiers de schéma `<table>.schema.toml/.json`, consulté
+    /// avant le dossier du .fic
+    #[serde(default)]
+    pub schema_dir: Option<PathBuf>,
+    /// Analyse du projet (.wdd au format de ce moteur, voir core::wdd) ;
+    /// sinon cherchée dans le dossier de données et ses sous-dossiers *.ana
+    #[serde(default)]
+    pub analysis: Option<PathBuf>,
+    /// Encodage des chaînes des tables (ex. "windows-1250", "cp850") ;
+    /// Windows-1252 si absent
+    #[serde(default)]
+    pub encoding: Option<String>,
+    /// Encodage par table (nom de table -> libellé), prioritaire sur `encoding`
+    #[serde(default)]
+    pub table_encodings: HashMap<String, String>,
 }
 
 fn default_parallel() -> bool {
@@ -84,6 +105,11 @@ impl Default for Settings {
                 read_only: false,
                 enable_write: true,
                 parallel: true,
+                mmap: false,
+                schema_dir: None,
+                analysis: None,
+                encoding: None,
+                table_encodings: HashMap::new(),
             },
             logging: LoggingSettings {
                 level: "info".to_string(),
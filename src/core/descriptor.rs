@@ -0,0 +1,238 @@
+/**
+ * Lecture du bloc de description des rubriques d'un fichier .fic.
+ *
+ * Un fichier .fic peut embarquer la description de ses rubriques (nom, type,
+ * position, taille) entre le header fixe et les enregistrements. Ce fichier
+ * décode ce bloc en FieldInfo, ce qui donne les vraies colonnes de la table
+ * au lieu du découpage par défaut de FicFile::analyze_schema.
+ *
+ * Ce bloc "RUB\0" est un format propre à ce moteur : il ne reproduit pas la
+ * description des rubriques des fichiers écrits par WinDev, dont
+ * l'organisation n'est pas décodée ici. Pour ces fichiers, le schéma vient
+ * d'un fichier de schéma (schema_dir) ou du découpage par défaut.
+ *
+ * Structure du bloc (little-endian, à la fin du header fixe) :
+ * - 0x00 : signature "RUB\0"
+ * - 0x04 : longueur totale du bloc en bytes (u32, signature comprise) ;
+ *   les enregistrements commencent juste après le bloc
+ * - 0x08 : nombre de rubriques (u16)
+ * - 0x0A : réservé (u16)
+ * - Puis, pour chaque rubrique :
+ *   - longueur du nom (u8), nom (Windows-1252)
+ *   - type (u8, codes TYPE_*)
+ *   - offset dans l'enregistrement (u32, byte de flags inclus)
+ *   - taille en bytes (u32)
+ *   - attributs (u16 ; octet bas = nombre de décimales d'un numérique,
+ *     réservé pour les autres types)
+ *
+ * Un fichier sans signature n'a pas de description : le schéma par défaut
+ * est utilisé.
+ *
+ * Liens avec d'autres modules :
+ * - Utilisé par src/core/fic.rs à l'ouverture des fichiers
+ * - Le format des rubriques est partagé avec src/core/wdd.rs
+ */
+
+use crate::core::schema_file::validate_fields;
+use crate::core::{FieldInfo, FieldType};
+use anyhow::{Context, Result};
+use byteorder::{LittleEndian, ReadBytesExt};
+use encoding_rs::WINDOWS_1252;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+/// Signature du bloc de description des rubriques
+pub const DESCRIPTOR_SIGNATURE: &[u8; 4] = b"RUB\0";
+/// Taille de l'en-tête du bloc (signature, longueur, nombre de rubriques)
+const DESCRIPTOR_HEADER_SIZE: usize = 12;
+/// Taille maximale acceptée pour un bloc (protection contre les fichiers corrompus)
+const MAX_DESCRIPTOR_SIZE: u32 = 1 << 20;
+
+/// Texte (chaîne de taille fixe)
+pub const TYPE_TEXT: u8 = 1;
+/// Entier signé
+pub const TYPE_INTEGER: u8 = 2;
+/// Entier non signé
+pub const TYPE_UNSIGNED: u8 = 3;
+/// Réel (f32 / f64)
+pub const TYPE_REAL: u8 = 4;
+/// Date (AAAAMMJJ)
+pub const TYPE_DATE: u8 = 5;
+/// Heure (HHMMSSCC)
+pub const TYPE_TIME: u8 = 6;
+/// Date et heure
+pub const TYPE_DATETIME: u8 = 7;
+/// Durée
+pub const TYPE_DURATION: u8 = 8;
+/// Monétaire
+pub const TYPE_CURRENCY: u8 = 9;
+/// Booléen
+pub const TYPE_BOOLEAN: u8 = 10;
+/// Mémo texte
+pub const TYPE_TEXT_MEMO: u8 = 11;
+/// Mémo binaire
+pub const TYPE_BINARY_MEMO: u8 = 12;
+/// Binaire de taille fixe
+pub const TYPE_BINARY: u8 = 13;
+/// Numérique (décimal exact)
+pub const TYPE_NUMERIC: u8 = 14;
+
+/// Bloc de description décodé
+#[derive(Debug, Clone)]
+pub struct Descriptor {
+    /// Longueur totale du bloc en bytes
+    pub length: u32,
+    /// Rubriques, dans l'ordre du bloc
+    pub fields: Vec<FieldInfo>,
+}
+
+/**
+ * Convertit un code de type de rubrique en FieldType.
+ *
+ * @param code - Code TYPE_* lu dans le bloc
+ * @returns FieldType - Type correspondant (Unknown pour un code inconnu)
+ */
+pub fn field_type_from_code(code: u8) -> FieldType {
+    match code {
+        TYPE_TEXT => FieldType::String,
+        TYPE_INTEGER => FieldType::Integer,
+        TYPE_UNSIGNED => FieldType::Unsigned,
+        TYPE_BOOLEAN => FieldType::Boolean,
+        TYPE_REAL => FieldType::Float,
+        TYPE_CURRENCY => FieldType::Currency,
+        TYPE_NUMERIC => FieldType::Numeric,
+        TYPE_DATE => FieldType::Date,
+        TYPE_TIME => FieldType::Time,
+        TYPE_DATETIME => FieldType::DateTime,
+        TYPE_DURATION => FieldType::Duration,
+        TYPE_TEXT_MEMO | TYPE_BINARY_MEMO => FieldType::Memo,
+        TYPE_BINARY => FieldType::Binary,
+        _ => FieldType::Unknown,
+    }
+}
+
+/**
+ * Lit le bloc de description des rubriques s'il est présent.
+ *
+ * @param reader - Reader sur le fichier .fic
+ * @param offset - Position du bloc (fin du header fixe)
+ * @param record_length - Longueur d'un enregistrement (validation des rubriques)
+ * @returns Result<Option<Descriptor>> - Bloc décodé, None sans signature,
+ *   erreur si le bloc est présent mais incohérent
+ *
+ * Effets de bord :
+ * - Lit depuis le reader (position modifiée)
+ */
+pub fn read_descriptor<R: Read + Seek>(reader: &mut R, offset: u64, record_length: u32) -> Result<Option<Descriptor>> {
+    reader.seek(SeekFrom::Start(offset))?;
+    let mut signature = [0u8; 4];
+    if reader.read_exact(&mut signature).is_err() || &signature != DESCRIPTOR_SIGNATURE {
+        return Ok(None);
+    }
+
+    let length = reader.read_u32::<LittleEndian>()?;
+    if (length as usize) < DESCRIPTOR_HEADER_SIZE || length > MAX_DESCRIPTOR_SIZE {
+        anyhow::bail!("Longueur du bloc de rubriques invalide: {}", length);
+    }
+    let mut block = vec![0u8; length as usize - 8];
+    reader.read_exact(&mut block)
+        .context("Bloc de rubriques tronqué")?;
+
+    let mut cursor = Cursor::new(block.as_slice());
+    let count = cursor.read_u16::<LittleEndian>()?;
+    let _reserved = cursor.read_u16::<LittleEndian>()?;
+
+    let fields = (0..count)
+        .map(|index| read_item(&mut cursor, index))
+        .collect::<Result<Vec<FieldInfo>>>()?;
+    validate_fields(&fields, record_length)?;
+
+    Ok(Some(Descriptor { length, fields }))
+}
+
+/**
+ * Lit la description d'une rubrique (format commun au bloc des .fic et à
+ * l'analyse .wdd, voir src/core/wdd.rs).
+ *
+ * @param reader - Reader positionné sur la rubrique
+ * @param index - Numéro de la rubrique (messages d'erreur)
+ * @returns Result<FieldInfo> - Rubrique lue, erreur si tronquée ou sans nom
+ *
+ * Effets de bord :
+ * - Avance le reader
+ */
+pub(crate) fn read_item<R: Read>(reader: &mut R, index: u16) -> Result<FieldInfo> {
+    let name_length = reader.read_u8()
+        .with_context(|| format!("Rubrique {} tronquée", index))?;
+    let mut name_bytes = vec![0u8; name_length as usize];
+    reader.read_exact(&mut name_bytes)
+        .with_context(|| format!("Nom de la rubrique {} tronqué", index))?;
+    let (name, _, _) = WINDOWS_1252.decode(&name_bytes);
+    let name = name.trim().to_string();
+
+    let type_code = reader.read_u8()?;
+    let offset = reader.read_u32::<LittleEndian>()?;
+    let length = reader.read_u32::<LittleEndian>()?;
+    let attributes = reader.read_u16::<LittleEndian>()
+        .with_context(|| format!("Rubrique {} ({}) tronquée", index, name))?;
+
+    if name.is_empty() {
+        anyhow::bail!("Rubrique {} sans nom", index);
+    }
+    Ok(FieldInfo {
+        name,
+        offset,
+        length,
+        field_type: field_type_from_code(type_code),
+        encoding: None,
+        scale: (type_code == TYPE_NUMERIC).then_some((attributes & 0xFF) as u32),
+    })
+}
+
+/// Encode la description d'une rubrique (fichiers de test)
+#[cfg(test)]
+pub(crate) fn encode_item(out: &mut Vec<u8>, name: &str, code: u8, offset: u32, length: u32) {
+    out.push(name.len() as u8);
+    out.extend_from_slice(name.as_bytes());
+    out.push(code);
+    out.extend_from_slice(&offset.to_le_bytes());
+    out.extend_from_slice(&length.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+}
+
+/// Encode un bloc de description (fichiers de test)
+#[cfg(test)]
+pub(crate) fn encode_descriptor(items: &[(&str, u8, u32, u32)]) -> Vec<u8> {
+    let mut body = Vec::new();
+    body.extend_from_slice(&(items.len() as u16).to_le_bytes());
+    body.extend_from_slice(&0u16.to_le_bytes());
+    for (name, code, offset, length) in items {
+        encode_item(&mut body, name, *code, *offset, *length);
+    }
+    let mut block = DESCRIPTOR_SIGNATURE.to_vec();
+    block.extend_from_slice(&((body.len() + 8) as u32).to_le_bytes());
+    block.extend_from_slice(&body);
+    block
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_descriptor() -> Result<()> {
+        let block = encode_descriptor(&[("CODE", TYPE_TEXT, 1, 8), ("AGE", TYPE_INTEGER, 9, 2), ("NOTES", TYPE_TEXT_MEMO, 11, 4)]);
+        let descriptor = read_descriptor(&mut Cursor::new(block.clone()), 0, 15)?.unwrap();
+        assert_eq!(descriptor.length as usize, block.len());
+        let names: Vec<&str> = descriptor.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["CODE", "AGE", "NOTES"]);
+        assert!(matches!(descriptor.fields[2].field_type, FieldType::Memo));
+
+        // Sans signature : pas de description
+        assert!(read_descriptor(&mut Cursor::new(vec![0u8; 32]), 0, 15)?.is_none());
+        // Rubrique dépassant l'enregistrement
+        assert!(read_descriptor(&mut Cursor::new(block.clone()), 0, 12).is_err());
+        // Bloc tronqué
+        assert!(read_descriptor(&mut Cursor::new(block[..block.len() - 3].to_vec()), 0, 15).is_err());
+        Ok(())
+    }
+}
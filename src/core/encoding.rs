@@ -0,0 +1,183 @@
+/**
+ * Encodages des chaînes HFSQL.
+ *
+ * Les rubriques texte des fichiers Classic sont en Windows-1252 par défaut,
+ * mais certains sites utilisent une autre page de code (CP850 des anciennes
+ * applications DOS, Windows-1250 pour le polonais, Windows-1253 pour le
+ * grec…) et les rubriques Unicode sont stockées en UTF-16LE.
+ *
+ * TextEncoding regroupe ces cas : les pages de code connues d'encoding_rs
+ * (libellés WHATWG), CP850 (table ci-dessous, absente d'encoding_rs) et
+ * UTF-16LE, qu'encoding_rs sait décoder mais pas encoder.
+ *
+ * Liens avec d'autres modules :
+ * - Utilisé par FieldInfo::text_encoding (src/core/mod.rs)
+ * - Utilisé par src/storage/engine.rs (chaînes et mémos) et src/core/mmo.rs
+ */
+
+use encoding_rs::{Encoding, UTF_16LE, WINDOWS_1252};
+
+/// Caractères des bytes 0x80 à 0xFF en CP850 (IBM 850, Europe de l'Ouest DOS)
+const CP850_HIGH: [char; 128] = [
+    '\u{00C7}', '\u{00FC}', '\u{00E9}', '\u{00E2}', '\u{00E4}', '\u{00E0}', '\u{00E5}', '\u{00E7}',
+    '\u{00EA}', '\u{00EB}', '\u{00E8}', '\u{00EF}', '\u{00EE}', '\u{00EC}', '\u{00C4}', '\u{00C5}',
+    '\u{00C9}', '\u{00E6}', '\u{00C6}', '\u{00F4}', '\u{00F6}', '\u{00F2}', '\u{00FB}', '\u{00F9}',
+    '\u{00FF}', '\u{00D6}', '\u{00DC}', '\u{00F8}', '\u{00A3}', '\u{00D8}', '\u{00D7}', '\u{0192}',
+    '\u{00E1}', '\u{00ED}', '\u{00F3}', '\u{00FA}', '\u{00F1}', '\u{00D1}', '\u{00AA}', '\u{00BA}',
+    '\u{00BF}', '\u{00AE}', '\u{00AC}', '\u{00BD}', '\u{00BC}', '\u{00A1}', '\u{00AB}', '\u{00BB}',
+    '\u{2591}', '\u{2592}', '\u{2593}', '\u{2502}', '\u{2524}', '\u{00C1}', '\u{00C2}', '\u{00C0}',
+    '\u{00A9}', '\u{2563}', '\u{2551}', '\u{2557}', '\u{255D}', '\u{00A2}', '\u{00A5}', '\u{2510}',
+    '\u{2514}', '\u{2534}', '\u{252C}', '\u{251C}', '\u{2500}', '\u{253C}', '\u{00E3}', '\u{00C3}',
+    '\u{255A}', '\u{2554}', '\u{2569}', '\u{2566}', '\u{2560}', '\u{2550}', '\u{256C}', '\u{00A4}',
+    '\u{00F0}', '\u{00D0}', '\u{00CA}', '\u{00CB}', '\u{00C8}', '\u{0131}', '\u{00CD}', '\u{00CE}',
+    '\u{00CF}', '\u{2518}', '\u{250C}', '\u{2588}', '\u{2584}', '\u{00A6}', '\u{00CC}', '\u{2580}',
+    '\u{00D3}', '\u{00DF}', '\u{00D4}', '\u{00D2}', '\u{00F5}', '\u{00D5}', '\u{00B5}', '\u{00FE}',
+    '\u{00DE}', '\u{00DA}', '\u{00DB}', '\u{00D9}', '\u{00FD}', '\u{00DD}', '\u{00AF}', '\u{00B4}',
+    '\u{00AD}', '\u{00B1}', '\u{2017}', '\u{00BE}', '\u{00B6}', '\u{00A7}', '\u{00F7}', '\u{00B8}',
+    '\u{00B0}', '\u{00A8}', '\u{00B7}', '\u{00B9}', '\u{00B3}', '\u{00B2}', '\u{25A0}', '\u{00A0}',
+];
+
+/// Encodage des chaînes d'une rubrique
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextEncoding {
+    /// Page de code gérée par encoding_rs
+    Standard(&'static Encoding),
+    /// CP850 (IBM 850)
+    Cp850,
+    /// UTF-16 little-endian (rubriques Unicode)
+    Utf16Le,
+}
+
+impl Default for TextEncoding {
+    fn default() -> Self {
+        TextEncoding::Standard(WINDOWS_1252)
+    }
+}
+
+impl TextEncoding {
+    /**
+     * Retrouve un encodage à partir de son libellé.
+     *
+     * @param label - Libellé WHATWG (`windows-1250`, `utf-8`…), `cp850` /
+     *   `ibm850` ou `utf-16le`
+     * @returns Option<TextEncoding> - None pour un libellé inconnu ou un
+     *   encodage qui ne peut pas être écrit
+     */
+    pub fn for_label(label: &str) -> Option<Self> {
+        match label.trim().to_ascii_lowercase().as_str() {
+            "cp850" | "ibm850" | "850" => return Some(TextEncoding::Cp850),
+            "utf-16le" | "utf-16" | "unicode" => return Some(TextEncoding::Utf16Le),
+            _ => {}
+        }
+        let encoding = Encoding::for_label(label.trim().as_bytes())?;
+        (encoding.output_encoding() == encoding).then_some(TextEncoding::Standard(encoding))
+    }
+
+    /// Nom de l'encodage (pour les messages)
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextEncoding::Standard(encoding) => encoding.name(),
+            TextEncoding::Cp850 => "IBM850",
+            TextEncoding::Utf16Le => "UTF-16LE",
+        }
+    }
+
+    /// Indique si les caractères ASCII sont codés sur un byte à l'identique
+    pub fn is_ascii_compatible(&self) -> bool {
+        match self {
+            TextEncoding::Standard(encoding) => encoding.is_ascii_compatible(),
+            TextEncoding::Cp850 => true,
+            TextEncoding::Utf16Le => false,
+        }
+    }
+
+    /**
+     * Retourne la partie d'une zone de taille fixe avant son terminateur
+     * (byte nul, ou unité UTF-16 nulle).
+     *
+     * @param bytes - Zone du champ
+     * @returns &[u8] - Contenu utile
+     */
+    pub fn until_terminator<'a>(&self, bytes: &'a [u8]) -> &'a [u8] {
+        let end = match self {
+            TextEncoding::Utf16Le => bytes
+                .chunks_exact(2)
+                .position(|unit| unit == [0, 0])
+                .map_or(bytes.len() & !1, |i| i * 2),
+            _ => bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len()),
+        };
+        &bytes[..end]
+    }
+
+    /**
+     * Décode des bytes.
+     *
+     * @param bytes - Texte encodé
+     * @returns (String, bool) - Texte et indicateur de séquences invalides
+     *   (remplacées par U+FFFD)
+     */
+    pub fn decode(&self, bytes: &[u8]) -> (String, bool) {
+        match self {
+            TextEncoding::Standard(encoding) => {
+                let (text, had_errors) = encoding.decode_without_bom_handling(bytes);
+                (text.into_owned(), had_errors)
+            }
+            TextEncoding::Cp850 => {
+                let text = bytes
+                    .iter()
+                    .map(|&b| if b < 0x80 { b as char } else { CP850_HIGH[(b - 0x80) as usize] })
+                    .collect();
+                (text, false)
+            }
+            TextEncoding::Utf16Le => {
+                let (text, had_errors) = UTF_16LE.decode_without_bom_handling(bytes);
+                (text.into_owned(), had_errors)
+            }
+        }
+    }
+
+    /**
+     * Encode un texte.
+     *
+     * @param text - Texte à encoder
+     * @returns Option<Vec<u8>> - None si un caractère n'est pas représentable
+     */
+    pub fn encode(&self, text: &str) -> Option<Vec<u8>> {
+        match self {
+            TextEncoding::Standard(encoding) => {
+                let (bytes, _, had_errors) = encoding.encode(text);
+                (!had_errors).then(|| bytes.into_owned())
+            }
+            TextEncoding::Cp850 => text
+                .chars()
+                .map(|c| match c {
+                    '\0'..='\u{7F}' => Some(c as u8),
+                    _ => CP850_HIGH.iter().position(|&h| h == c).map(|i| 0x80 + i as u8),
+                })
+                .collect(),
+            TextEncoding::Utf16Le => Some(text.encode_utf16().flat_map(u16::to_le_bytes).collect()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encodings_round_trip() {
+        let cp850 = TextEncoding::for_label("IBM850").unwrap();
+        assert_eq!(cp850.decode(&[0x82, 0x87, 0x9C]).0, "éç£");
+        assert_eq!(cp850.encode("Ñandú").unwrap(), vec![0xA5, b'a', b'n', b'd', 0xA3]);
+        assert!(cp850.encode("€").is_none());
+
+        let polish = TextEncoding::for_label("windows-1250").unwrap();
+        assert_eq!(polish.decode(&polish.encode("Łódź").unwrap()).0, "Łódź");
+
+        let unicode = TextEncoding::for_label("utf-16le").unwrap();
+        let mut field = unicode.encode("Σωκράτης").unwrap();
+        field.resize(24, 0);
+        assert_eq!(unicode.decode(unicode.until_terminator(&field)).0, "Σωκράτης");
+        assert!(TextEncoding::for_label("x-inconnu").is_none());
+    }
+}
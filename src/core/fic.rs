@@ -6,30 +6,69 @@
  * 
  * Structure d'un fichier .fic :
  * - Header : Métadonnées (magic bytes, version, nombre d'enregistrements, etc.)
+ * - Description des rubriques (optionnelle, bloc propre à ce moteur, voir
+ *   src/core/descriptor.rs)
  * - Données : Enregistrements de taille fixe, chacun commençant par un byte de flags
  * 
  * Fonctionnalités :
  * - Lecture du header avec détection automatique du format
  * - Lecture d'enregistrements individuels par index
  * - Lecture de tous les enregistrements actifs
- * - Analyse du schéma (déduction des champs)
+ * - Parcours sans copie des enregistrements (records), via une projection
+ *   du fichier en mémoire : les fichiers de plusieurs Go ne sont jamais
+ *   chargés en entier
+ * - Analyse du schéma (rubriques décrites dans le fichier, sinon déduction)
  * - Extraction des pointeurs mémo vers les fichiers .mmo
+ * - Écriture : ajout, réécriture et marquage de suppression d'enregistrements,
+ *   avec mise à jour des compteurs du header, préparées sous forme de
+ *   PendingWrite pour le journal du stockage
  * 
  * Liens avec d'autres modules :
  * - Utilisé par src/storage/engine.rs pour lire les données
  * - Utilise src/core/FieldInfo pour représenter les champs
  */
 
-use crate::core::FieldInfo;
+use crate::core::descriptor::read_descriptor;
+use crate::core::{FieldInfo, FieldType, PendingWrite};
 use crate::logger::{get_logger, LogLevel};
 use anyhow::{Context, Result};
-use byteorder::{LittleEndian, ReadBytesExt};
+use byteorder::{ByteOrder, LittleEndian};
+use memmap2::Mmap;
 use serde::{Deserialize, Serialize};
-use std::fs::File;
-use std::io::{Read, Seek, SeekFrom};
+use std::borrow::Cow;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::Path;
+use std::sync::OnceLock;
 use rayon::prelude::*;
 
+/// Magic "FIC\0" écrit comme un entier (bytes inversés sur disque)
+const FIC_MAGIC: u32 = 0x46494300;
+/// Seule version du header fixe dont l'organisation est connue en écriture
+const FIC_VERSION: u16 = 1;
+/// Taille du header fixe (suivi de la description des rubriques ou des données)
+pub const FIXED_HEADER_SIZE: u32 = 0x20;
+/// Taille du header d'un fichier Classic (suivi de GUIDs, puis des données)
+const CLASSIC_HEADER_SIZE: u32 = 0x14;
+/// Longueur d'enregistrement maximale acceptée (protège des headers corrompus)
+pub const MAX_RECORD_LENGTH: u32 = 1 << 24;
+/// Offset de la longueur d'un enregistrement dans le header (u32)
+const RECORD_LENGTH_OFFSET: usize = 0x06;
+/// Offset du nombre d'enregistrements dans le header (u32)
+const RECORD_COUNT_OFFSET: u64 = 0x0A;
+/// Offset du nombre d'enregistrements supprimés dans le header (u32)
+const DELETED_COUNT_OFFSET: u64 = 0x0E;
+/// Offset des flags du fichier dans le header (u16)
+const FLAGS_OFFSET: usize = 0x12;
+/// Offset de la taille du header dans le header (u32, 0 = header fixe seul)
+const HEADER_SIZE_OFFSET: usize = 0x14;
+/// Offset du début des données dans le header (u64, 0 = après le header)
+const DATA_OFFSET_OFFSET: usize = 0x18;
+/// Taille des lectures de FicFile::records sans projection en mémoire
+const READ_CHUNK_SIZE: usize = 1 << 20;
+/// Bit du byte de flags marquant un enregistrement supprimé
+const DELETED_FLAG: u8 = 0x01;
+
 /// Header d'un fichier .fic contenant les métadonnées
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FicHeader {
@@ -45,10 +84,23 @@ pub struct FicHeader {
     pub deleted_count: u32,
     /// Flags divers du fichier
     pub flags: u16,
-    /// Taille du header en bytes
+    /// Taille du header en bytes (header fixe et description des rubriques)
     pub header_size: u32,
     /// Offset où commencent les données (après le header)
-    pub data_offset: u32,
+    pub data_offset: u64,
+    /// Organisation du header reconnue à l'ouverture
+    pub layout: HeaderLayout,
+}
+
+/// Organisation du header d'un fichier .fic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HeaderLayout {
+    /// Header de FIXED_HEADER_SIZE bytes dont toutes les valeurs sont
+    /// cohérentes (compteurs u32, taille du header, début des données)
+    Fixed,
+    /// Header Classic (compteurs u16, données après CLASSIC_HEADER_SIZE
+    /// bytes) : lu quand le header fixe n'est pas cohérent
+    Classic,
 }
 
 /// Représente un enregistrement dans un fichier .fic
@@ -60,19 +112,201 @@ pub struct FicRecord {
     pub deleted: bool,
     /// Données brutes de l'enregistrement (sans le byte de flags)
     pub data: Vec<u8>,
-    /// Offsets vers les données mémo dans le fichier .mmo associé
-    pub memo_pointers: Vec<u32>,
+    /// Pointeurs vers les données mémo dans le fichier .mmo associé
+    pub memo_pointers: Vec<MemoPointer>,
+}
+
+impl FicRecord {
+    /// Vue empruntée de l'enregistrement (même forme que FicFile::records)
+    pub fn as_raw(&self) -> RawRecord<'_> {
+        RawRecord {
+            id: self.id,
+            deleted: self.deleted,
+            data: Cow::Borrowed(&self.data),
+        }
+    }
+}
+
+/// Pointeur d'une rubrique mémo vers un bloc du fichier .mmo
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoPointer {
+    /// Nom de la rubrique mémo
+    pub field: String,
+    /// Offset du bloc dans le fichier .mmo
+    pub offset: u32,
+}
+
+/// Enregistrement lu par FicFile::records
+#[derive(Debug, Clone)]
+pub struct RawRecord<'a> {
+    /// Identifiant de l'enregistrement (index 0-based)
+    pub id: u32,
+    /// Indique si l'enregistrement est marqué comme supprimé
+    pub deleted: bool,
+    /// Données brutes (sans le byte de flags), empruntées à la projection du
+    /// fichier ou copiées depuis le tampon de lecture
+    pub data: Cow<'a, [u8]>,
+}
+
+impl RawRecord<'_> {
+    /**
+     * Copie l'enregistrement dans un FicRecord.
+     * 
+     * @param fields - Rubriques décrites dans le fichier (FicFile::descriptor),
+     *   pour extraire les pointeurs mémo
+     * @returns FicRecord - Enregistrement possédant ses données
+     */
+    pub fn to_record(&self, fields: Option<&[FieldInfo]>) -> FicRecord {
+        self.clone().into_record(fields)
+    }
+
+    /**
+     * Convertit l'enregistrement en FicRecord, sans copie si les données
+     * sont déjà possédées (lecture par blocs).
+     * 
+     * @param fields - Rubriques décrites dans le fichier (FicFile::descriptor),
+     *   pour extraire les pointeurs mémo
+     * @returns FicRecord - Enregistrement possédant ses données
+     */
+    pub fn into_record(self, fields: Option<&[FieldInfo]>) -> FicRecord {
+        let data = self.data.into_owned();
+        let memo_pointers = fields
+            .map(|fields| FicFile::memo_pointers(&data, fields))
+            .unwrap_or_default();
+        FicRecord {
+            id: self.id,
+            deleted: self.deleted,
+            data,
+            memo_pointers,
+        }
+    }
+}
+
+/**
+ * Itérateur sur les emplacements d'un fichier .fic (FicFile::records).
+ * 
+ * Retourne tous les emplacements, supprimés compris, dans l'ordre des ids.
+ * S'arrête à la fin du fichier si celui-ci est plus court que ne l'indique
+ * le header ; le dernier emplacement peut alors être incomplet.
+ */
+pub struct Records<'a> {
+    source: Source<'a>,
+    record_length: usize,
+    next: u32,
+    count: u32,
+}
+
+/// Origine des emplacements parcourus par Records
+enum Source<'a> {
+    /// Zone des données projetée en mémoire (à partir de data_offset)
+    Mapped(&'a [u8]),
+    /// Lecture du fichier par blocs de READ_CHUNK_SIZE bytes
+    Chunked(ChunkReader<'a>),
+}
+
+/// Lecture par blocs d'emplacements consécutifs
+struct ChunkReader<'a> {
+    file: &'a File,
+    path: &'a Path,
+    data_offset: u64,
+    /// Emplacements lus (le dernier peut être incomplet en fin de fichier)
+    buffer: Vec<u8>,
+    /// Id du premier emplacement du tampon
+    first: u32,
+}
+
+impl ChunkReader<'_> {
+    /**
+     * Retourne un emplacement, en relisant le fichier s'il n'est pas dans le tampon.
+     * 
+     * @returns Option<&[u8]> - Emplacement (flags compris), None au-delà de la
+     *   fin du fichier ou si la lecture échoue (erreur journalisée)
+     */
+    fn slot(&mut self, id: u32, record_length: usize) -> Option<&[u8]> {
+        let buffered = (id >= self.first)
+            .then(|| (id - self.first) as usize * record_length)
+            .filter(|&start| start < self.buffer.len());
+        let start = match buffered {
+            Some(start) => start,
+            None => {
+                self.fill(id, record_length)?;
+                0
+            }
+        };
+        let slot = self.buffer.get(start..).filter(|slot| !slot.is_empty())?;
+        Some(&slot[..slot.len().min(record_length)])
+    }
+
+    fn fill(&mut self, id: u32, record_length: usize) -> Option<()> {
+        let slots = (READ_CHUNK_SIZE / record_length).max(1);
+        self.buffer.clear();
+        self.first = id;
+        let mut file = self.file;
+        let read = file
+            .seek(SeekFrom::Start(self.data_offset + id as u64 * record_length as u64))
+            .and_then(|_| file.take((slots * record_length) as u64).read_to_end(&mut self.buffer));
+        if let Err(e) = read {
+            get_logger().log_with_source(LogLevel::Error, format!("Lecture interrompue de {:?} à l'enregistrement {}: {}", self.path, id, e), Some("FIC Core".to_string()));
+            return None;
+        }
+        Some(())
+    }
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = RawRecord<'a>;
+
+    fn next(&mut self) -> Option<RawRecord<'a>> {
+        if self.next >= self.count {
+            return None;
+        }
+        let id = self.next;
+        let (deleted, data) = match &mut self.source {
+            Source::Mapped(data) => {
+                let data: &'a [u8] = data;
+                let start = (id as usize).checked_mul(self.record_length)?;
+                let slot = data.get(start..).filter(|slot| !slot.is_empty())?;
+                let slot = &slot[..slot.len().min(self.record_length)];
+                ((slot[0] & DELETED_FLAG) != 0, Cow::Borrowed(&slot[1..]))
+            }
+            Source::Chunked(reader) => {
+                let slot = reader.slot(id, self.record_length)?;
+                ((slot[0] & DELETED_FLAG) != 0, Cow::Owned(slot[1..].to_vec()))
+            }
+        };
+        self.next += 1;
+        Some(RawRecord { id, deleted, data })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some((self.count - self.next) as usize))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<RawRecord<'a>> {
+        // Accès direct : les emplacements sautés ne sont pas lus
+        self.next = u32::try_from(n).ok()
+            .and_then(|n| self.next.checked_add(n))
+            .unwrap_or(self.count)
+            .min(self.count);
+        self.next()
+    }
 }
 
 /// Gestionnaire de fichier .fic permettant la lecture et l'analyse
 pub struct FicFile {
     /// Chemin du fichier
-    #[allow(dead_code)]
     path: std::path::PathBuf,
     /// Header du fichier (lu au moment de l'ouverture)
     header: FicHeader,
     /// Handle du fichier ouvert (Option pour permettre la fermeture explicite)
     file: Option<File>,
+    /// Rubriques décrites dans le fichier (None sans bloc de description)
+    descriptor: Option<Vec<FieldInfo>>,
+    /// Projette le fichier en mémoire dans records (désactivé par défaut, voir set_mmap)
+    use_mmap: bool,
+    /// Projection du fichier en mémoire, créée au premier appel de records
+    /// et invalidée par les écritures
+    mmap: OnceLock<Mmap>,
 }
 
 impl FicFile {
@@ -94,115 +328,232 @@ impl FicFile {
         let mut file = File::open(&path)
             .with_context(|| format!("Impossible d'ouvrir le fichier: {:?}", path))?;
 
-        let header = Self::read_header(&mut file)?;
+        let mut header = Self::read_header(&mut file)?;
+        let descriptor = Self::read_fields(&mut file, &mut header, &path);
 
         Ok(Self {
             path,
             header,
             file: Some(file),
+            descriptor,
+            use_mmap: false,
+            mmap: OnceLock::new(),
+        })
+    }
+
+    /**
+     * Ouvre un fichier .fic en lecture et écriture.
+     * 
+     * @param path - Chemin vers le fichier .fic
+     * @returns Result<FicFile> - Gestionnaire de fichier ou erreur
+     * 
+     * Effets de bord :
+     * - Ouvre le fichier en lecture/écriture
+     * - Lit le header
+     */
+    pub fn open_rw<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("Impossible d'ouvrir le fichier en écriture: {:?}", path))?;
+
+        let mut header = Self::read_header(&mut file)?;
+        let descriptor = Self::read_fields(&mut file, &mut header, &path);
+
+        Ok(Self {
+            path,
+            header,
+            file: Some(file),
+            descriptor,
+            use_mmap: false,
+            mmap: OnceLock::new(),
         })
     }
 
     /**
      * Lit et parse le header d'un fichier .fic.
      * 
-     * Lit les métadonnées depuis le début du fichier et les parse
-     * pour construire un FicHeader. Gère différents formats (PCS, FIC)
-     * et calcule automatiquement la longueur d'enregistrement si nécessaire.
+     * Header fixe de FIXED_HEADER_SIZE bytes (little-endian) :
+     * - 0x00 : magic "PCS\0" ou "FIC\0" (aussi écrit comme l'entier 0x46494300)
+     * - 0x04 : version (u16)
+     * - 0x06 : longueur d'un enregistrement, byte de flags inclus (u32)
+     * - 0x0A : nombre d'enregistrements (u32)
+     * - 0x0E : nombre d'enregistrements supprimés (u32)
+     * - 0x12 : flags du fichier (u16)
+     * - 0x14 : taille du header (u32, 0 = header fixe seul)
+     * - 0x18 : début des données (u64, 0 = juste après le header)
+     * 
+     * Si ces valeurs sont incohérentes avec la taille du fichier, le header
+     * est relu dans l'organisation Classic (read_classic_header) plutôt que
+     * refusé.
      * 
-     * @param reader - Reader positionné au début du fichier
+     * @param reader - Reader sur le fichier
      * @returns Result<FicHeader> - Header parsé ou erreur
      * 
      * Effets de bord :
      * - Lit depuis le reader (position modifiée)
-     * - Peut faire des seeks pour déterminer la taille du fichier
      */
     fn read_header<R: Read + Seek>(reader: &mut R) -> Result<FicHeader> {
+        let file_size = reader.seek(SeekFrom::End(0))?;
         reader.seek(SeekFrom::Start(0))?;
 
-        // Magic bytes: "PCS\0" (4 bytes)
-        let mut magic_bytes = [0u8; 4];
-        reader.read_exact(&mut magic_bytes)?;
-        let magic = u32::from_le_bytes(magic_bytes);
-        
-        // Vérification du magic
-        if &magic_bytes[0..3] != b"PCS" {
-            // Fallback: essayer "FIC" pour compatibilité
-            if &magic_bytes[0..3] != b"FIC" {
-                anyhow::bail!("Magic bytes invalides: {:?} (attendu: PCS ou FIC)", magic_bytes);
+        let mut bytes = Vec::with_capacity(FIXED_HEADER_SIZE as usize);
+        reader.take(FIXED_HEADER_SIZE as u64).read_to_end(&mut bytes)?;
+        if bytes.len() < CLASSIC_HEADER_SIZE as usize {
+            anyhow::bail!("Header tronqué: {} bytes (minimum {})", file_size, CLASSIC_HEADER_SIZE);
+        }
+
+        // Vérification du magic ("FIC" accepté pour compatibilité)
+        if &bytes[0..3] != b"PCS" && &bytes[0..3] != b"FIC" && LittleEndian::read_u32(&bytes) != FIC_MAGIC {
+            anyhow::bail!("Magic bytes invalides: {:?} (attendu: PCS ou FIC)", &bytes[0..4]);
+        }
+
+        let header = match Self::read_fixed_header(&bytes, file_size) {
+            Ok(header) => header,
+            Err(e) => {
+                let header = Self::read_classic_header(&bytes, file_size)
+                    .with_context(|| format!("{:#}", e))?;
+                get_logger().log_with_source(LogLevel::Debug, format!("Header fixe incohérent ({:#}) : header Classic", e), Some("FIC Core".to_string()));
+                header
             }
+        };
+
+        // Calcul en u64 : record_count * record_length dépasse u32 au-delà de 4 Go
+        let expected_size = header.data_offset + header.record_count as u64 * header.record_length as u64;
+        if file_size < expected_size {
+            get_logger().log_with_source(LogLevel::Warn, format!("Fichier tronqué: {} bytes pour {} enregistrements de {} bytes (attendu: {})",
+                     file_size, header.record_count, header.record_length, expected_size), Some("FIC Core".to_string()));
         }
+        Ok(header)
+    }
 
-        let version = reader.read_u16::<LittleEndian>()?;
-        let _padding1 = reader.read_u16::<LittleEndian>()?; // Padding
-        
-        // Record length en u16 (peut-être en unités spéciales)
-        let record_length_u16 = reader.read_u16::<LittleEndian>()?;
-        let record_count = reader.read_u16::<LittleEndian>()?;
-        let _padding2 = reader.read_u16::<LittleEndian>()?; // Padding
-        let deleted_count = reader.read_u16::<LittleEndian>()?;
-        let _padding3 = reader.read_u16::<LittleEndian>()?; // Padding
-        let flags = reader.read_u16::<LittleEndian>()?;
-        
-        // Record length: si c'est 1, peut-être que c'est en pages de 64KB
-        // Mais d'abord, essayons de déterminer la vraie longueur en analysant le fichier
-        let current_pos = reader.seek(SeekFrom::Current(0))?;
-        let file_size = reader.seek(SeekFrom::End(0))?;
-        reader.seek(SeekFrom::Start(current_pos))?;
-        
-        // Calculer la taille du header en cherchant la fin du header
-        // Le header semble se terminer après les flags (offset 0x14 = 20 bytes)
-        // mais peut contenir des données supplémentaires (GUIDs, etc.)
-        // On va chercher un header_size plus réaliste en analysant la structure
-        
-        // Pour l'instant, on suppose que le header fait au moins 20 bytes
-        // mais peut-être plus. On va le calculer dynamiquement si possible.
-        let min_header_size = 0x14; // 20 bytes minimum
-        
-        // Si record_length_u16 == 1, cela pourrait signifier différentes choses:
-        // 1. 65536 bytes (64KB) - mais cela ne correspond pas à la taille du fichier
-        // 2. Une unité différente (peut-être en blocs de 512 bytes?)
-        // 3. La vraie valeur est ailleurs dans le header
-        let record_length = if record_length_u16 == 1 {
-            // Si le fichier est trop petit pour 65536 bytes par enregistrement,
-            // calculons la longueur réelle à partir de la taille du fichier
-            if record_count > 0 {
-                let available_data = file_size.saturating_sub(min_header_size as u64);
-                let calculated_length = (available_data / record_count as u64) as u32;
-                // Si la longueur calculée est raisonnable (entre 100 et 100000 bytes), l'utiliser
-                if calculated_length >= 100 && calculated_length <= 100000 {
-                    get_logger().log_with_source(LogLevel::Warn, format!("record_length=1 détecté, calculé dynamiquement: {} bytes (fichier: {} bytes, {} enregistrements)", 
-                             calculated_length, file_size, record_count), Some("FIC Core".to_string()));
-                    calculated_length
+    /**
+     * Parse le header fixe (voir read_header) en refusant toute valeur
+     * incohérente avec la taille du fichier.
+     * 
+     * @param bytes - Début du fichier (FIXED_HEADER_SIZE bytes attendus)
+     * @param file_size - Taille du fichier
+     * @returns Result<FicHeader> - Header, ou erreur décrivant l'incohérence
+     */
+    fn read_fixed_header(bytes: &[u8], file_size: u64) -> Result<FicHeader> {
+        if bytes.len() < FIXED_HEADER_SIZE as usize {
+            anyhow::bail!("Header tronqué: {} bytes (minimum {})", file_size, FIXED_HEADER_SIZE);
+        }
+        let u16_at = |offset: usize| LittleEndian::read_u16(&bytes[offset..]);
+        let u32_at = |offset: usize| LittleEndian::read_u32(&bytes[offset..]);
+        let u64_at = |offset: usize| LittleEndian::read_u64(&bytes[offset..]);
+
+        let record_length = u32_at(RECORD_LENGTH_OFFSET);
+        let header_size = match u32_at(HEADER_SIZE_OFFSET) {
+            0 => FIXED_HEADER_SIZE,
+            size => size,
+        };
+        let data_offset = match u64_at(DATA_OFFSET_OFFSET) {
+            0 => header_size as u64,
+            offset => offset,
+        };
+
+        if record_length == 0 || record_length > MAX_RECORD_LENGTH {
+            anyhow::bail!("Longueur d'enregistrement invalide: {} (maximum {})", record_length, MAX_RECORD_LENGTH);
+        }
+        if header_size < FIXED_HEADER_SIZE || header_size as u64 > file_size {
+            anyhow::bail!("Taille de header invalide: {} (fichier: {} bytes)", header_size, file_size);
+        }
+        if data_offset < header_size as u64 || data_offset > file_size {
+            anyhow::bail!("Début des données invalide: {} (header: {} bytes, fichier: {} bytes)", data_offset, header_size, file_size);
+        }
+
+        Ok(FicHeader {
+            magic: u32_at(0),
+            version: u16_at(4),
+            record_length,
+            record_count: u32_at(RECORD_COUNT_OFFSET as usize),
+            deleted_count: u32_at(DELETED_COUNT_OFFSET as usize),
+            flags: u16_at(FLAGS_OFFSET),
+            header_size,
+            data_offset,
+            layout: HeaderLayout::Fixed,
+        })
+    }
+
+    /**
+     * Parse un header Classic, tel que relevé sur les fichiers HFSQL Classic :
+     * - 0x00 : magic, 0x04 : version (u16), 0x06 : réservé (u16)
+     * - 0x08 : longueur d'un enregistrement (u16)
+     * - 0x0A : nombre d'enregistrements (u16), 0x0C : réservé (u16)
+     * - 0x0E : nombre d'enregistrements supprimés (u16), 0x10 : réservé (u16)
+     * - 0x12 : flags du fichier (u16)
+     * - À partir de 0x14 : GUIDs ; les données suivent le header
+     * 
+     * Une longueur de 1 désigne une longueur non stockée : elle est déduite
+     * de la taille du fichier quand le résultat est plausible, 65 536 sinon.
+     * 
+     * @param bytes - Début du fichier (au moins CLASSIC_HEADER_SIZE bytes)
+     * @param file_size - Taille du fichier
+     * @returns Result<FicHeader> - Header, ou erreur si la longueur est nulle
+     */
+    fn read_classic_header(bytes: &[u8], file_size: u64) -> Result<FicHeader> {
+        let u16_at = |offset: usize| LittleEndian::read_u16(&bytes[offset..]);
+        let record_count = u16_at(0x0A) as u32;
+        let record_length = match u16_at(0x08) {
+            0 => anyhow::bail!("Longueur d'enregistrement nulle"),
+            1 => {
+                let calculated = file_size.saturating_sub(CLASSIC_HEADER_SIZE as u64)
+                    .checked_div(record_count as u64)
+                    .unwrap_or(0);
+                if (100..=100_000).contains(&calculated) {
+                    get_logger().log_with_source(LogLevel::Warn, format!("record_length=1 détecté, calculé dynamiquement: {} bytes (fichier: {} bytes, {} enregistrements)",
+                             calculated, file_size, record_count), Some("FIC Core".to_string()));
+                    calculated as u32
                 } else {
-                    // Sinon, utiliser 65536 comme valeur par défaut
-                    65536u32
+                    65536
                 }
-            } else {
-                65536u32
             }
-        } else {
-            record_length_u16 as u32
+            length => length as u32,
         };
-        
-        // Pour header_size et data_offset, on doit les chercher ailleurs
-        // ou les calculer. Pour l'instant, on utilise des valeurs par défaut
-        // basées sur l'observation que le header semble commencer à 0x14
-        let header_size = min_header_size; // 20 bytes minimum, mais peut être plus
-        let data_offset = header_size; // Les données commencent après le header de base
 
         Ok(FicHeader {
-            magic,
-            version,
+            magic: LittleEndian::read_u32(bytes),
+            version: u16_at(0x04),
             record_length,
-            record_count: record_count as u32,
-            deleted_count: deleted_count as u32,
-            flags,
-            header_size,
-            data_offset,
+            record_count,
+            deleted_count: u16_at(0x0E) as u32,
+            flags: u16_at(0x12),
+            header_size: CLASSIC_HEADER_SIZE,
+            data_offset: CLASSIC_HEADER_SIZE as u64,
+            layout: HeaderLayout::Classic,
         })
     }
 
+    /**
+     * Lit la description des rubriques placée après le header fixe.
+     * 
+     * Si elle est présente, les enregistrements commencent au plus tôt après
+     * elle (header.data_offset est ajusté). Une description incohérente est
+     * ignorée avec un avertissement : le schéma par défaut est utilisé.
+     * 
+     * @param reader - Reader sur le fichier
+     * @param header - Header lu (header_size et data_offset mis à jour)
+     * @param path - Chemin du fichier (messages)
+     * @returns Option<Vec<FieldInfo>> - Rubriques décrites
+     */
+    fn read_fields<R: Read + Seek>(reader: &mut R, header: &mut FicHeader, path: &Path) -> Option<Vec<FieldInfo>> {
+        match read_descriptor(reader, header.header_size as u64, header.record_length) {
+            Ok(Some(descriptor)) => {
+                header.header_size += descriptor.length;
+                header.data_offset = header.data_offset.max(header.header_size as u64);
+                Some(descriptor.fields)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                get_logger().log_with_source(LogLevel::Warn, format!("Description des rubriques ignorée pour {:?}: {:#}", path, e), Some("FIC Core".to_string()));
+                None
+            }
+        }
+    }
+
     /**
      * Retourne une référence vers le header du fichier.
      * 
@@ -212,6 +563,111 @@ impl FicFile {
         &self.header
     }
 
+    /**
+     * Indique si le fichier contient une description des rubriques lisible.
+     * 
+     * @returns bool - true si analyze_schema retourne les rubriques décrites
+     */
+    pub fn has_descriptor(&self) -> bool {
+        self.descriptor.is_some()
+    }
+
+    /**
+     * Rubriques décrites dans le fichier.
+     * 
+     * @returns Option<&[FieldInfo]> - None sans bloc de description
+     */
+    pub fn descriptor(&self) -> Option<&[FieldInfo]> {
+        self.descriptor.as_deref()
+    }
+
+    /**
+     * Active la projection en mémoire pour records.
+     * 
+     * À réserver aux fichiers que seul ce moteur modifie : une autre
+     * application (une application WinDev sur un fichier en exploitation)
+     * qui tronque le fichier pendant un parcours fait échouer le processus
+     * (SIGBUS). Sans projection, records lit le fichier par blocs.
+     * 
+     * @param enabled - true pour projeter le fichier en mémoire
+     */
+    pub fn set_mmap(&mut self, enabled: bool) {
+        self.use_mmap = enabled;
+        if !enabled {
+            self.mmap.take();
+        }
+    }
+
+    /**
+     * Parcourt les enregistrements sans charger tout le fichier.
+     * 
+     * Le fichier est lu par blocs de READ_CHUNK_SIZE bytes ou, si la
+     * projection est activée (set_mmap), projeté en mémoire : seules les
+     * pages parcourues sont alors chargées par le système et chaque
+     * RawRecord emprunte ses données à la projection. Les enregistrements
+     * supprimés sont inclus (champ deleted).
+     * 
+     * @returns Result<Records> - Itérateur sur les emplacements, dans l'ordre des ids
+     * 
+     * Effets de bord :
+     * - Projette le fichier en mémoire au premier appel (projection activée)
+     * - Déplace la position de lecture du fichier (lecture par blocs)
+     */
+    pub fn records(&self) -> Result<Records<'_>> {
+        let file = self.file.as_ref().context("Fichier non ouvert")?;
+        let source = match self.mapping(file)? {
+            Some(map) => {
+                let start = usize::try_from(self.header.data_offset).map_or(map.len(), |start| start.min(map.len()));
+                Source::Mapped(&map[start..])
+            }
+            None => Source::Chunked(ChunkReader {
+                file,
+                path: &self.path,
+                data_offset: self.header.data_offset,
+                buffer: Vec::new(),
+                first: 0,
+            }),
+        };
+        Ok(Records {
+            source,
+            record_length: self.header.record_length.max(1) as usize,
+            next: 0,
+            count: self.header.record_count,
+        })
+    }
+
+    /**
+     * Retourne la projection du fichier, créée au premier appel.
+     * 
+     * @returns Result<Option<&Mmap>> - None si la projection est désactivée
+     *   ou si le fichier change de taille pendant la projection (fichier en
+     *   cours d'écriture par une autre application : lecture par blocs)
+     */
+    fn mapping(&self, file: &File) -> Result<Option<&Mmap>> {
+        if !self.use_mmap {
+            return Ok(None);
+        }
+        if let Some(map) = self.mmap.get() {
+            return Ok(Some(map));
+        }
+        let size = file.metadata()?.len();
+        // SAFETY : la projection n'est activée (set_mmap) que pour des
+        // fichiers que seul ce moteur modifie ; il le fait sous son verrou
+        // d'écriture, jamais pendant une lecture, et les écritures passant
+        // par ce FicFile invalident la projection (apply_writes).
+        // Limite : si une autre application tronque le fichier pendant un
+        // parcours, l'accès aux pages disparues provoque un SIGBUS. Le
+        // contrôle de taille ci-dessous ne détecte qu'une écriture en cours
+        // au moment de la projection.
+        let map = unsafe { Mmap::map(file) }
+            .with_context(|| format!("Impossible de projeter le fichier en mémoire: {:?}", self.path))?;
+        if map.len() as u64 != size || file.metadata()?.len() != size {
+            get_logger().log_with_source(LogLevel::Warn, format!("Taille de {:?} modifiée pendant la projection : lecture par blocs", self.path), Some("FIC Core".to_string()));
+            return Ok(None);
+        }
+        Ok(Some(self.mmap.get_or_init(|| map)))
+    }
+
     /**
      * Lit un enregistrement spécifique par son index.
      * 
@@ -227,14 +683,14 @@ impl FicFile {
      * - Peut retourner une erreur si l'index est hors limites
      */
     pub fn read_record(&mut self, index: u32) -> Result<FicRecord> {
-        let file = self.file.as_mut()
-            .context("Fichier non ouvert")?;
-
         if index >= self.header.record_count {
             anyhow::bail!("Index {} hors limites (max: {})", index, self.header.record_count);
         }
 
-        let offset = self.header.data_offset as u64 + (index * self.header.record_length) as u64;
+        // Offset en u64 : index * record_length dépasse u32 au-delà de 4 Go
+        let offset = self.slot_offset(index);
+        let file = self.file.as_mut()
+            .context("Fichier non ouvert")?;
         
         // Vérifier que l'offset est dans les limites du fichier
         let current_pos = file.seek(SeekFrom::Current(0))
@@ -254,7 +710,7 @@ impl FicFile {
 
         // Le premier byte contient le flag de suppression
         let flags_byte = record_buffer[0];
-        let deleted = (flags_byte & 0x01) != 0;
+        let deleted = (flags_byte & DELETED_FLAG) != 0;
 
         // Le reste sont les données (on prend ce qui a été lu, au cas où le fichier serait tronqué)
         let data = if bytes_read > 1 {
@@ -263,8 +719,10 @@ impl FicFile {
             Vec::new()
         };
 
-        // Extraction des pointeurs mémo (si présents dans les premiers bytes)
-        let memo_pointers = Self::extract_memo_pointers(&data);
+        // Extraction des pointeurs mémo (rubriques mémo de la description)
+        let memo_pointers = self.descriptor.as_deref()
+            .map(|fields| Self::memo_pointers(&data, fields))
+            .unwrap_or_default();
 
         Ok(FicRecord {
             id: index,
@@ -275,28 +733,254 @@ impl FicFile {
     }
 
     /**
-     * Extrait les pointeurs mémo depuis les données brutes d'un enregistrement.
+     * Offset d'un emplacement d'enregistrement dans le fichier.
+     */
+    fn slot_offset(&self, index: u32) -> u64 {
+        self.header.data_offset + index as u64 * self.header.record_length as u64
+    }
+
+    /**
+     * Lit l'emplacement complet d'un enregistrement (byte de flags inclus).
      * 
-     * Analyse les premiers bytes des données pour détecter les pointeurs
-     * vers le fichier .mmo. Cette fonction est une implémentation simplifiée
-     * et peut nécessiter des ajustements selon le format réel HFSQL.
+     * Contrairement à read_record, retourne exactement record_length bytes,
+     * complétés par des zéros si le fichier est tronqué.
      * 
-     * @param data - Données brutes de l'enregistrement
-     * @returns Vec<u32> - Liste des offsets vers le fichier .mmo
+     * @param index - Index de l'enregistrement (0-based)
+     * @returns Result<Vec<u8>> - Contenu brut de l'emplacement
      * 
-     * Effets de bord : Aucun
+     * Effets de bord :
+     * - Lit depuis le fichier (position modifiée)
      */
-    fn extract_memo_pointers(data: &[u8]) -> Vec<u32> {
-        // Hypothèse: les pointeurs mémo sont dans les 4 premiers bytes
-        // À adapter selon le format réel HFSQL
-        let mut pointers = Vec::new();
-        if data.len() >= 4 {
-            let ptr = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
-            if ptr != 0 && ptr < 0xFFFFFFFF {
-                pointers.push(ptr);
+    pub fn read_slot(&mut self, index: u32) -> Result<Vec<u8>> {
+        if index >= self.header.record_count {
+            anyhow::bail!("Index {} hors limites (max: {})", index, self.header.record_count);
+        }
+        let offset = self.slot_offset(index);
+        let file = self.file.as_mut().context("Fichier non ouvert")?;
+        file.seek(SeekFrom::Start(offset))?;
+
+        let mut slot = vec![0u8; self.header.record_length as usize];
+        let mut read = 0;
+        while read < slot.len() {
+            let n = file.read(&mut slot[read..])?;
+            if n == 0 {
+                break;
             }
+            read += n;
+        }
+        Ok(slot)
+    }
+
+    /**
+     * Prépare l'ajout d'un enregistrement à la fin du fichier.
+     * 
+     * Les données précèdent la mise à jour du nombre d'enregistrements dans
+     * le header. Le header en mémoire est mis à jour, de sorte que plusieurs
+     * préparations successives s'enchaînent.
+     * 
+     * @param slot - Contenu complet de l'emplacement (byte de flags inclus,
+     *   record_length bytes)
+     * @returns Result<(u32, Vec<PendingWrite>)> - Index du nouvel
+     *   enregistrement et écritures à appliquer
+     * 
+     * Effets de bord :
+     * - Met à jour le header en mémoire (pas le fichier)
+     */
+    pub fn plan_append(&mut self, slot: &[u8]) -> Result<(u32, Vec<PendingWrite>)> {
+        self.check_writable()?;
+        self.check_slot_length(slot)?;
+        let index = self.header.record_count;
+        let record_count = index.checked_add(1)
+            .with_context(|| format!("Le header ne permet pas plus de {} enregistrements", u32::MAX))?;
+        let mut writes = vec![PendingWrite { offset: self.slot_offset(index), data: slot.to_vec() }];
+
+        self.header.record_count = record_count;
+        writes.extend(self.count_writes());
+        Ok((index, writes))
+    }
+
+    /**
+     * Prépare la réécriture d'un enregistrement existant.
+     * 
+     * @param index - Index de l'enregistrement (0-based)
+     * @param slot - Contenu complet de l'emplacement (byte de flags inclus)
+     * @returns Result<Vec<PendingWrite>> - Écritures à appliquer
+     */
+    pub fn plan_write(&self, index: u32, slot: &[u8]) -> Result<Vec<PendingWrite>> {
+        self.check_writable()?;
+        self.check_slot_length(slot)?;
+        if index >= self.header.record_count {
+            anyhow::bail!("Index {} hors limites (max: {})", index, self.header.record_count);
+        }
+        Ok(vec![PendingWrite { offset: self.slot_offset(index), data: slot.to_vec() }])
+    }
+
+    /**
+     * Prépare le marquage d'un enregistrement comme supprimé.
+     * 
+     * Positionne le bit de suppression du byte de flags et incrémente le
+     * nombre d'enregistrements supprimés du header. Les données restent
+     * en place, comme sous HFSQL.
+     * 
+     * @param index - Index de l'enregistrement (0-based)
+     * @param slot - Contenu actuel de l'emplacement (read_slot, ou version
+     *   modifiée non encore écrite)
+     * @returns Result<Vec<PendingWrite>> - Écritures à appliquer
+     * 
+     * Effets de bord :
+     * - Met à jour le header en mémoire (pas le fichier)
+     */
+    pub fn plan_delete(&mut self, index: u32, slot: &[u8]) -> Result<Vec<PendingWrite>> {
+        self.check_writable()?;
+        self.check_slot_length(slot)?;
+        if index >= self.header.record_count {
+            anyhow::bail!("Index {} hors limites (max: {})", index, self.header.record_count);
+        }
+        if slot[0] & DELETED_FLAG != 0 {
+            anyhow::bail!("L'enregistrement {} est déjà supprimé", index);
+        }
+        let deleted_count = self.header.deleted_count.checked_add(1)
+            .context("Nombre d'enregistrements supprimés trop grand pour le header")?;
+        let mut writes = vec![PendingWrite { offset: self.slot_offset(index), data: vec![slot[0] | DELETED_FLAG] }];
+
+        self.header.deleted_count = deleted_count;
+        writes.extend(self.count_writes());
+        Ok(writes)
+    }
+
+    /**
+     * Ajoute un enregistrement à la fin du fichier (sans journal).
+     * 
+     * @param slot - Contenu complet de l'emplacement (byte de flags inclus)
+     * @returns Result<u32> - Index du nouvel enregistrement
+     * 
+     * Effets de bord :
+     * - Écrit à la fin des données et met à jour le header
+     */
+    pub fn append_record(&mut self, slot: &[u8]) -> Result<u32> {
+        let (index, writes) = self.plan_append(slot)?;
+        self.apply_writes(&writes)?;
+        Ok(index)
+    }
+
+    /**
+     * Réécrit un enregistrement existant (sans journal).
+     * 
+     * @param index - Index de l'enregistrement (0-based)
+     * @param slot - Contenu complet de l'emplacement (byte de flags inclus)
+     * 
+     * Effets de bord :
+     * - Écrit dans le fichier à l'emplacement de l'enregistrement
+     */
+    pub fn write_record(&mut self, index: u32, slot: &[u8]) -> Result<()> {
+        let writes = self.plan_write(index, slot)?;
+        self.apply_writes(&writes)
+    }
+
+    /**
+     * Marque un enregistrement comme supprimé (sans journal).
+     * 
+     * @param index - Index de l'enregistrement (0-based)
+     * 
+     * Effets de bord :
+     * - Écrit le byte de flags et le header
+     */
+    pub fn mark_deleted(&mut self, index: u32) -> Result<()> {
+        let slot = self.read_slot(index)?;
+        let writes = self.plan_delete(index, &slot)?;
+        self.apply_writes(&writes)
+    }
+
+    /**
+     * Force l'écriture des modifications sur le disque.
+     * 
+     * Effets de bord :
+     * - Appelle fsync sur le fichier
+     */
+    pub fn sync(&mut self) -> Result<()> {
+        let file = self.file.as_mut().context("Fichier non ouvert")?;
+        file.flush()?;
+        file.sync_data()?;
+        Ok(())
+    }
+
+    /**
+     * Indique si le header a été reconnu avec certitude : header fixe
+     * cohérent et version FIC_VERSION. Seuls ces fichiers sont modifiés,
+     * les offsets des compteurs des autres organisations n'étant pas connus.
+     * 
+     * @returns bool - true si les écritures sont autorisées
+     */
+    pub fn is_writable(&self) -> bool {
+        self.header.layout == HeaderLayout::Fixed && self.header.version == FIC_VERSION
+    }
+
+    /// Refuse toute écriture sur un fichier dont le header n'est pas reconnu
+    fn check_writable(&self) -> Result<()> {
+        if !self.is_writable() {
+            anyhow::bail!(
+                "Header .fic non reconnu ({:?}, version {}) : écriture refusée",
+                self.header.layout,
+                self.header.version
+            );
         }
-        pointers
+        Ok(())
+    }
+
+    /// Vérifie qu'un emplacement fait exactement record_length bytes
+    fn check_slot_length(&self, slot: &[u8]) -> Result<()> {
+        if slot.len() != self.header.record_length as usize {
+            anyhow::bail!(
+                "Taille d'enregistrement invalide: {} bytes (attendu: {})",
+                slot.len(),
+                self.header.record_length
+            );
+        }
+        Ok(())
+    }
+
+    /// Écritures de record_count et deleted_count dans le header (u32)
+    fn count_writes(&self) -> Vec<PendingWrite> {
+        vec![
+            PendingWrite { offset: RECORD_COUNT_OFFSET, data: self.header.record_count.to_le_bytes().to_vec() },
+            PendingWrite { offset: DELETED_COUNT_OFFSET, data: self.header.deleted_count.to_le_bytes().to_vec() },
+        ]
+    }
+
+    /// Applique directement des écritures préparées (fichier ouvert avec open_rw)
+    fn apply_writes(&mut self, writes: &[PendingWrite]) -> Result<()> {
+        self.mmap.take();
+        let file = self.file.as_mut().context("Fichier non ouvert")?;
+        for write in writes {
+            file.seek(SeekFrom::Start(write.offset))?;
+            file.write_all(&write.data)?;
+        }
+        Ok(())
+    }
+
+    /**
+     * Extrait les pointeurs mémo depuis les données brutes d'un enregistrement.
+     * 
+     * Chaque rubrique mémo du schéma contient, à son offset, le pointeur
+     * (u32 little-endian) vers son bloc dans le fichier .mmo. Les pointeurs
+     * 0 (pas de mémo) et 0xFFFFFFFF (non initialisé) sont ignorés.
+     * 
+     * @param data - Données brutes de l'enregistrement (sans le byte de flags)
+     * @param fields - Rubriques de la table (offsets byte de flags inclus)
+     * @returns Vec<MemoPointer> - Pointeurs des rubriques mémo renseignées
+     * 
+     * Effets de bord : Aucun
+     */
+    pub fn memo_pointers(data: &[u8], fields: &[FieldInfo]) -> Vec<MemoPointer> {
+        fields.iter()
+            .filter(|field| matches!(field.field_type, FieldType::Memo) && field.length >= 4 && field.offset >= 1)
+            .filter_map(|field| {
+                let start = field.offset as usize - 1;
+                let bytes = data.get(start..start + 4)?;
+                let offset = u32::from_le_bytes(bytes.try_into().ok()?);
+                (offset != 0 && offset != u32::MAX).then(|| MemoPointer { field: field.name.clone(), offset })
+            })
+            .collect()
     }
 
     /**
@@ -334,7 +1018,7 @@ impl FicFile {
         file.seek(SeekFrom::Start(0))
             .context("Impossible de revenir au début du fichier")?;
         
-        let expected_size = self.header.data_offset as u64 + (self.header.record_count as u64 * self.header.record_length as u64);
+        let expected_size = self.header.data_offset + self.header.record_count as u64 * self.header.record_length as u64;
         if file_size < expected_size {
             get_logger().log_with_source(LogLevel::Warn, format!("Taille du fichier ({}) inférieure à la taille attendue ({})", file_size, expected_size), Some("FIC Core".to_string()));
             get_logger().log_with_source(LogLevel::Warn, format!("  Header: data_offset={}, record_count={}, record_length={}", 
@@ -369,8 +1053,11 @@ impl FicFile {
     /**
      * Version parallèle de read_all_records (pour gros fichiers).
      * 
-     * Lit le fichier en mémoire puis parse les enregistrements en parallèle
-     * pour améliorer les performances sur les fichiers volumineux.
+     * Parcourt les emplacements (records : projection du fichier si
+     * storage.mmap est activé, sinon lecture par blocs) puis convertit les
+     * enregistrements actifs en parallèle. Les données lues par blocs sont
+     * déplacées dans les FicRecord, seules celles de la projection sont
+     * copiées. Pour ne pas tout garder en mémoire, préférer records.
      * 
      * @returns Result<Vec<FicRecord>> - Liste des enregistrements actifs ou erreur
      */
@@ -384,92 +1071,23 @@ impl FicFile {
         file.seek(SeekFrom::Start(0))
             .context("Impossible de revenir au début du fichier")?;
         
-        let expected_size = self.header.data_offset as u64 + (self.header.record_count as u64 * self.header.record_length as u64);
+        let expected_size = self.header.data_offset + self.header.record_count as u64 * self.header.record_length as u64;
         if file_size < expected_size {
             get_logger().log_with_source(LogLevel::Warn, format!("Taille du fichier ({}) inférieure à la taille attendue ({})", file_size, expected_size), Some("FIC Core".to_string()));
             get_logger().log_with_source(LogLevel::Warn, format!("  Header: data_offset={}, record_count={}, record_length={}", 
                      self.header.data_offset, self.header.record_count, self.header.record_length), Some("FIC Core".to_string()));
         }
 
-        // Lire tout le fichier en mémoire
-        file.seek(SeekFrom::Start(self.header.data_offset as u64))
-            .context("Impossible de se positionner au début des données")?;
-        
-        let data_size = (self.header.record_count as u64 * self.header.record_length as u64) as usize;
-        let mut file_data = vec![0u8; data_size];
-        file.read_exact(&mut file_data)
-            .context("Impossible de lire toutes les données du fichier")?;
-
-        // Extraire les informations du header pour le parsing parallèle
-        let record_length = self.header.record_length as usize;
-        let record_count = self.header.record_count as usize;
-
-        // Parser les enregistrements en parallèle
-        let records: Vec<Result<FicRecord>> = (0..record_count)
-            .into_par_iter()
-            .map(|i| {
-                let offset = i * record_length;
-                if offset + record_length > file_data.len() {
-                    return Err(anyhow::anyhow!("Enregistrement {} hors limites", i));
-                }
-
-                let record_data = &file_data[offset..offset + record_length];
-                
-                // Le premier byte contient le flag de suppression
-                if record_data.is_empty() {
-                    return Err(anyhow::anyhow!("Enregistrement {} vide", i));
-                }
-
-                let flags_byte = record_data[0];
-                let deleted = (flags_byte & 0x01) != 0;
-
-                // Le reste sont les données
-                let data = if record_data.len() > 1 {
-                    record_data[1..].to_vec()
-                } else {
-                    Vec::new()
-                };
-
-                // Extraction des pointeurs mémo
-                let memo_pointers = Self::extract_memo_pointers(&data);
-
-                Ok(FicRecord {
-                    id: i as u32,
-                    deleted,
-                    data,
-                    memo_pointers,
-                })
-            })
+        // Parser les enregistrements en parallèle une fois lus (projection
+        // ou lecture par blocs)
+        let descriptor = self.descriptor.as_deref();
+        let slots: Vec<RawRecord> = self.records()?
+            .filter(|raw| !raw.deleted)
             .collect();
-
-        // Collecter les résultats et gérer les erreurs
-        let mut valid_records = Vec::new();
-        let mut first_error = None;
-
-        for (i, result) in records.into_iter().enumerate() {
-            match result {
-                Ok(record) => {
-                    if !record.deleted {
-                        valid_records.push(record);
-                    }
-                }
-                Err(e) => {
-                    get_logger().log_with_source(LogLevel::Error, format!("Erreur lors de la lecture de l'enregistrement {}: {}", i, e), Some("FIC Core".to_string()));
-                    if i == 0 {
-                        first_error = Some(e);
-                    } else {
-                        get_logger().log_with_source(LogLevel::Warn, format!("Arrêt de la lecture après {} enregistrements valides", valid_records.len()), Some("FIC Core".to_string()));
-                        break;
-                    }
-                }
-            }
-        }
-
-        if let Some(err) = first_error {
-            return Err(err).with_context(|| "Impossible de lire le premier enregistrement (index 0)");
-        }
-
-        Ok(valid_records)
+        Ok(slots
+            .into_par_iter()
+            .map(|raw| raw.into_record(descriptor))
+            .collect())
     }
 
     /**
@@ -528,7 +1146,11 @@ impl FicFile {
      * Effets de bord : Aucun
      */
     pub fn analyze_schema(&self) -> Vec<FieldInfo> {
-        // Analyse basique: on suppose des champs fixes
+        if let Some(fields) = &self.descriptor {
+            return fields.clone();
+        }
+
+        // Sans description : on suppose des champs fixes
         // À améliorer avec une analyse plus poussée
         let mut fields = Vec::new();
         let mut offset = 0;
@@ -539,6 +1161,8 @@ impl FicFile {
             offset,
             length: 4,
             field_type: crate::core::FieldType::Integer,
+            encoding: None,
+            scale: None,
         });
         offset += 4;
 
@@ -547,7 +1171,9 @@ impl FicFile {
             name: "flags".to_string(),
             offset,
             length: 1,
-            field_type: crate::core::FieldType::Integer,
+            field_type: crate::core::FieldType::Unsigned,
+            encoding: None,
+            scale: None,
         });
         offset += 1;
 
@@ -558,6 +1184,8 @@ impl FicFile {
                 offset,
                 length: self.header.record_length - offset,
                 field_type: crate::core::FieldType::Binary,
+                encoding: None,
+                scale: None,
             });
         }
 
@@ -565,6 +1193,17 @@ impl FicFile {
     }
 }
 
+/// Encode un header fixe, tailles et offsets par défaut (fichiers de test)
+#[cfg(test)]
+pub(crate) fn encode_header(record_length: u32, record_count: u32) -> Vec<u8> {
+    let mut bytes = b"PCS\0".to_vec();
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // Version
+    bytes.extend_from_slice(&record_length.to_le_bytes());
+    bytes.extend_from_slice(&record_count.to_le_bytes());
+    bytes.resize(FIXED_HEADER_SIZE as usize, 0);
+    bytes
+}
+
 impl Drop for FicFile {
     fn drop(&mut self) {
         // Fermeture automatique du fichier
@@ -644,4 +1283,208 @@ mod tests {
         
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Fichier .fic au format de header lu par read_header
+    fn create_writable_fic_file(record_length: u16, records: &[&[u8]]) -> Result<NamedTempFile> {
+        let mut file = NamedTempFile::new()?;
+        let mut bytes = encode_header(record_length as u32, records.len() as u32);
+        for record in records {
+            let mut slot = vec![0u8; record_length as usize];
+            slot[1..1 + record.len()].copy_from_slice(record);
+            bytes.extend_from_slice(&slot);
+        }
+        file.write_all(&bytes)?;
+        file.flush()?;
+        Ok(file)
+    }
+
+    #[test]
+    fn test_append_write_and_delete_records() -> Result<()> {
+        let test_file = create_writable_fic_file(8, &[b"abc", b"def"])?;
+
+        let mut fic = FicFile::open_rw(test_file.path())?;
+        let mut slot = vec![0u8; 8];
+        slot[1..4].copy_from_slice(b"ghi");
+        assert_eq!(fic.append_record(&slot)?, 2);
+        slot[1..4].copy_from_slice(b"DEF");
+        fic.write_record(1, &slot)?;
+        fic.mark_deleted(0)?;
+        assert!(fic.mark_deleted(0).is_err());
+        assert!(fic.append_record(&[0u8; 3]).is_err());
+        drop(fic);
+
+        // Les compteurs du header sont relus depuis le disque
+        let mut fic = FicFile::open(test_file.path())?;
+        assert_eq!(fic.header().record_count, 3);
+        assert_eq!(fic.header().deleted_count, 1);
+        assert!(fic.read_record(0)?.deleted);
+        let records = fic.read_all_records()?;
+        let data: Vec<&[u8]> = records.iter().map(|r| &r.data[..3]).collect();
+        assert_eq!(data, vec![&b"DEF"[..], &b"ghi"[..]]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_records_iterator() -> Result<()> {
+        let test_file = create_writable_fic_file(8, &[b"abc", b"def"])?;
+
+        let mut fic = FicFile::open_rw(test_file.path())?;
+        assert_eq!(fic.records()?.count(), 2);
+        fic.mark_deleted(0)?;
+        let mut slot = vec![0u8; 8];
+        slot[1..4].copy_from_slice(b"ghi");
+        fic.append_record(&slot)?;
+
+        // Les écritures invalident la projection
+        let expected = fic.read_all_records()?;
+        let raw: Vec<RawRecord> = fic.records()?.collect();
+        let flags: Vec<(u32, bool)> = raw.iter().map(|r| (r.id, r.deleted)).collect();
+        assert_eq!(flags, vec![(0, true), (1, false), (2, false)]);
+        assert_eq!(raw[1].data.len(), 7);
+        let copies: Vec<Vec<u8>> = raw.iter().filter(|r| !r.deleted).map(|r| r.to_record(None).data).collect();
+        assert_eq!(copies, expected.into_iter().map(|r| r.data).collect::<Vec<_>>());
+        Ok(())
+    }
+
+    #[test]
+    fn test_records_chunked_and_mapped() -> Result<()> {
+        // Plusieurs blocs de lecture, dernier emplacement incomplet
+        const RECORD_LENGTH: u32 = 400_000;
+        let mut bytes = encode_header(RECORD_LENGTH, 8);
+        for i in 0..7u8 {
+            let mut slot = vec![0u8; RECORD_LENGTH as usize];
+            slot[0] = if i == 3 { DELETED_FLAG } else { 0 };
+            slot[1] = i;
+            bytes.extend_from_slice(&slot);
+        }
+        bytes.extend_from_slice(&[0, 7, 7]);
+        let mut file = NamedTempFile::new()?;
+        file.write_all(&bytes)?;
+        file.flush()?;
+
+        let mut fic = FicFile::open(file.path())?;
+        let read = |fic: &FicFile| -> Result<Vec<(u32, bool, u8, usize)>> {
+            Ok(fic.records()?.map(|r| (r.id, r.deleted, r.data[0], r.data.len())).collect())
+        };
+        let chunked = read(&fic)?;
+        assert_eq!(chunked.len(), 8);
+        assert!(chunked[3].1);
+        assert_eq!(chunked[7], (7, false, 7, 2));
+        assert_eq!(fic.records()?.nth(5).map(|r| r.data[0]), Some(5));
+
+        fic.set_mmap(true);
+        assert_eq!(read(&fic)?, chunked);
+        assert!(matches!(fic.records()?.next().map(|r| r.data), Some(Cow::Borrowed(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_large_sparse_file() -> Result<()> {
+        // Plus de 65 535 enregistrements de plus de 64 Ko : 5,6 Go, au-delà
+        // de u32 (fichier creux, aucune donnée écrite au milieu)
+        const RECORD_LENGTH: u32 = 70_000;
+        const RECORD_COUNT: u32 = 80_000;
+        const DATA_OFFSET: u64 = 64;
+        let last = RECORD_COUNT - 1;
+        let size = DATA_OFFSET + RECORD_COUNT as u64 * RECORD_LENGTH as u64;
+
+        let mut file = NamedTempFile::new()?;
+        let mut header = encode_header(RECORD_LENGTH, RECORD_COUNT);
+        header[0x18..0x20].copy_from_slice(&DATA_OFFSET.to_le_bytes());
+        file.write_all(&header)?;
+        file.as_file().set_len(size)?;
+        file.seek(SeekFrom::Start(DATA_OFFSET + last as u64 * RECORD_LENGTH as u64))?;
+        file.write_all(b"\0LAST")?;
+        file.flush()?;
+
+        let mut fic = FicFile::open(file.path())?;
+        assert_eq!(fic.header().record_count, RECORD_COUNT);
+        assert_eq!(fic.header().record_length, RECORD_LENGTH);
+        assert_eq!(fic.header().data_offset, DATA_OFFSET);
+        let raw = fic.records()?.nth(last as usize).expect("dernier enregistrement");
+        assert_eq!((raw.id, &raw.data[..4], raw.data.len()), (last, &b"LAST"[..], RECORD_LENGTH as usize - 1));
+        assert_eq!(&fic.read_record(last)?.data[..4], b"LAST");
+        assert_eq!(&fic.read_slot(last)?[..5], b"\0LAST");
+        assert!(fic.records()?.nth(RECORD_COUNT as usize).is_none());
+
+        // Début des données au-delà de la fin du fichier
+        header[0x18..0x20].copy_from_slice(&(size + 1).to_le_bytes());
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(&header)?;
+        file.flush()?;
+        assert_eq!(FicFile::open(file.path())?.header().layout, HeaderLayout::Classic);
+        Ok(())
+    }
+
+    #[test]
+    fn test_classic_header() -> Result<()> {
+        // Compteurs u16, longueur à 0x08, enregistrements dès 0x14
+        let mut bytes = b"PCS\0".to_vec();
+        bytes.extend_from_slice(&[0x0A, 0x00, 0xFF, 0xFF]); // version, réservé
+        bytes.extend_from_slice(&12u16.to_le_bytes());
+        bytes.extend_from_slice(&[2, 0, 0, 0, 1, 0, 0, 0, 0x04, 0x00]);
+        assert_eq!(bytes.len(), CLASSIC_HEADER_SIZE as usize);
+        for (flags, text) in [(0u8, b"CLIENT-0001"), (DELETED_FLAG, b"CLIENT-0002")] {
+            bytes.push(flags);
+            bytes.extend_from_slice(text);
+        }
+        let mut file = NamedTempFile::new()?;
+        file.write_all(&bytes)?;
+        file.flush()?;
+
+        let mut fic = FicFile::open(file.path())?;
+        let header = fic.header().clone();
+        assert_eq!(header.layout, HeaderLayout::Classic);
+        assert_eq!((header.version, header.record_length, header.record_count, header.deleted_count), (10, 12, 2, 1));
+        assert_eq!(header.data_offset, CLASSIC_HEADER_SIZE as u64);
+        assert_eq!(fic.read_record(0)?.data, b"CLIENT-0001");
+        assert!(fic.read_record(1)?.deleted);
+
+        // Compteurs à des offsets non confirmés : aucune écriture
+        let mut fic = FicFile::open_rw(file.path())?;
+        assert!(!fic.is_writable());
+        assert!(fic.append_record(&[0u8; 12]).is_err());
+        assert!(fic.mark_deleted(0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_version_is_read_only() -> Result<()> {
+        let mut bytes = encode_header(4, 1);
+        bytes[4..6].copy_from_slice(&2u16.to_le_bytes());
+        bytes.extend_from_slice(&[0, 1, 2, 3]);
+        let mut file = NamedTempFile::new()?;
+        file.write_all(&bytes)?;
+        file.flush()?;
+
+        let mut fic = FicFile::open_rw(file.path())?;
+        assert_eq!(fic.header().layout, HeaderLayout::Fixed);
+        assert!(fic.write_record(0, &[0, 9, 9, 9]).is_err());
+        assert_eq!(std::fs::read(file.path())?, bytes);
+        Ok(())
+    }
+
+    #[test]
+    fn test_counts_beyond_u16() -> Result<()> {
+        // 70 000 enregistrements de 4 bytes (fichier creux)
+        const RECORD_COUNT: u32 = 70_000;
+        let mut file = NamedTempFile::new()?;
+        file.write_all(&encode_header(4, RECORD_COUNT))?;
+        file.as_file().set_len(FIXED_HEADER_SIZE as u64 + RECORD_COUNT as u64 * 4)?;
+        file.flush()?;
+
+        let mut fic = FicFile::open_rw(file.path())?;
+        assert_eq!(fic.append_record(&[0, b'N', b'E', b'W'])?, RECORD_COUNT);
+        fic.mark_deleted(RECORD_COUNT)?;
+        fic.mark_deleted(0)?;
+        drop(fic);
+
+        let mut fic = FicFile::open(file.path())?;
+        assert_eq!(fic.header().record_count, RECORD_COUNT + 1);
+        assert_eq!(fic.header().deleted_count, 2);
+        let appended = fic.read_record(RECORD_COUNT)?;
+        assert!(appended.deleted);
+        assert_eq!(appended.data, b"NEW");
+        Ok(())
+    }
+}
@@ -0,0 +1,455 @@
+/**
+ * Inférence heuristique du schéma d'un fichier .fic.
+ *
+ * Utilisée quand le fichier n'a pas de description des rubriques lisible
+ * (fichier endommagé ou format inhabituel). Un échantillon d'enregistrements
+ * est analysé position par position :
+ * - les réels f64 sont repérés en premier (octet d'exposant plausible) ;
+ * - les zones de texte (CP1252, complétées par des zéros ou des espaces) sont
+ *   découpées aux transitions remplissage -> texte et chiffres -> lettres ;
+ * - le reste est découpé en entiers little-endian selon l'entropie des
+ *   octets : un octet variable qui suit un octet constant commence un champ
+ *   (octet de poids faible), les octets constants qui suivent sont ses
+ *   octets de poids fort.
+ *
+ * Chaque champ reçoit un type deviné (chaîne, entier, réel, date AAAAMMJJ,
+ * pointeur mémo) et un score de confiance entre 0 et 1. Le résultat reste
+ * indicatif : les rubriques ne sont pas nommées (field_1, field_2...) et deux
+ * entiers adjacents dont tous les octets varient sont confondus.
+ *
+ * Liens avec d'autres modules :
+ * - S'appuie sur FicFile::records (src/core/fic.rs)
+ * - Vérifie les pointeurs mémo supposés avec MmoFile (src/core/mmo.rs)
+ * - Exposé par la commande CLI `infer-schema` (src/cli/commands.rs)
+ */
+
+use crate::core::{FicFile, FieldInfo, FieldType, MmoFile, TableSchema};
+use anyhow::Result;
+use serde::Serialize;
+use std::borrow::Cow;
+
+/// Nombre d'enregistrements échantillonnés par défaut
+pub const DEFAULT_SAMPLE_SIZE: usize = 1000;
+/// Part minimale des enregistrements devant confirmer une hypothèse
+const MAJORITY: f32 = 0.9;
+/// Entropie normalisée sous laquelle une position est considérée constante
+const LOW_ENTROPY: f32 = 0.25;
+
+/// Schéma déduit d'un échantillon d'enregistrements
+#[derive(Debug, Clone, Serialize)]
+pub struct InferredSchema {
+    /// Schéma proposé (offsets byte de flags inclus, comme FieldInfo)
+    pub schema: TableSchema,
+    /// Confiance par champ (0.0 à 1.0), dans l'ordre de schema.fields
+    pub confidence: Vec<f32>,
+    /// Nombre d'enregistrements analysés
+    pub sampled: usize,
+}
+
+/**
+ * Déduit le schéma d'une table à partir de ses enregistrements actifs.
+ *
+ * @param name - Nom de la table (reporté dans le schéma)
+ * @param fic - Fichier .fic à analyser
+ * @param mmo - Fichier .mmo associé, pour reconnaître les pointeurs mémo
+ * @param sample_size - Nombre maximal d'enregistrements analysés (répartis
+ *   sur tout le fichier)
+ * @returns Result<InferredSchema> - Schéma et confiances, erreur si le
+ *   fichier n'a aucun enregistrement actif
+ *
+ * Effets de bord :
+ * - Lit le fichier .fic (et quelques bytes du .mmo)
+ */
+pub fn infer_schema(name: &str, fic: &mut FicFile, mmo: Option<&mut MmoFile>, sample_size: usize) -> Result<InferredSchema> {
+    let record_length = fic.header().record_length;
+    // Seul l'échantillon est gardé : les gros fichiers ne sont jamais
+    // chargés entièrement en mémoire
+    let active = fic.records()?.filter(|r| !r.deleted).count();
+    if active == 0 {
+        anyhow::bail!("Aucun enregistrement actif : impossible d'inférer le schéma de {}", name);
+    }
+
+    let step = active.div_ceil(sample_size.max(1));
+    let sample: Vec<Cow<[u8]>> = fic.records()?
+        .filter(|r| !r.deleted)
+        .step_by(step)
+        .map(|r| r.data)
+        .collect();
+    let width = sample.iter().map(|r| r.len()).min().unwrap_or(0);
+    let rows: Vec<&[u8]> = sample.iter().map(|r| &r[..width]).collect();
+
+    let mut inference = Inference::new(rows, mmo);
+    let mut found = inference.run();
+    found.sort_by_key(|f| f.start);
+
+    let fields: Vec<FieldInfo> = found
+        .iter()
+        .enumerate()
+        .map(|(i, f)| FieldInfo {
+            name: format!("field_{}", i + 1),
+            // Le byte 0 de l'enregistrement est le byte de flags
+            offset: f.start as u32 + 1,
+            length: f.length as u32,
+            field_type: f.field_type.clone(),
+            encoding: None,
+            scale: None,
+        })
+        .collect();
+
+    Ok(InferredSchema {
+        confidence: found.iter().map(|f| f.confidence).collect(),
+        sampled: inference.rows.len(),
+        schema: TableSchema {
+            name: name.to_string(),
+            record_length,
+            field_count: fields.len() as u32,
+            fields,
+        },
+    })
+}
+
+/// Champ détecté (positions relatives aux données, sans le byte de flags)
+struct Candidate {
+    start: usize,
+    length: usize,
+    field_type: FieldType,
+    confidence: f32,
+}
+
+/// État de l'analyse d'un échantillon
+struct Inference<'a> {
+    rows: Vec<&'a [u8]>,
+    mmo: Option<&'a mut MmoFile>,
+    /// Entropie normalisée de chaque position
+    entropy: Vec<f32>,
+    /// Positions déjà attribuées à un champ
+    claimed: Vec<bool>,
+}
+
+impl<'a> Inference<'a> {
+    fn new(rows: Vec<&'a [u8]>, mmo: Option<&'a mut MmoFile>) -> Self {
+        let width = rows.first().map(|r| r.len()).unwrap_or(0);
+        let entropy = (0..width).map(|p| position_entropy(&rows, p)).collect();
+        Self { rows, mmo, entropy, claimed: vec![false; width] }
+    }
+
+    fn width(&self) -> usize {
+        self.claimed.len()
+    }
+
+    fn run(&mut self) -> Vec<Candidate> {
+        let mut found = Vec::new();
+        self.find_doubles(&mut found);
+        self.find_memos(&mut found);
+        self.find_texts(&mut found);
+        self.find_integers(&mut found);
+        found
+    }
+
+    fn claim(&mut self, found: &mut Vec<Candidate>, start: usize, length: usize, field_type: FieldType, confidence: f32) {
+        self.claimed[start..start + length].iter_mut().for_each(|c| *c = true);
+        found.push(Candidate { start, length, field_type, confidence });
+    }
+
+    /// Part des enregistrements vérifiant un prédicat
+    fn ratio(&self, predicate: impl Fn(&[u8]) -> bool) -> f32 {
+        self.rows.iter().filter(|r| predicate(r)).count() as f32 / self.rows.len() as f32
+    }
+
+    /// Réels f64 : au moins la moitié des valeurs non nulles, et presque
+    /// toutes dans une plage plausible
+    fn find_doubles(&mut self, found: &mut Vec<Candidate>) {
+        let mut p = 0;
+        while p + 8 <= self.width() {
+            let (mut non_zero, mut plausible) = (0usize, 0usize);
+            for row in &self.rows {
+                let bytes: [u8; 8] = row[p..p + 8].try_into().unwrap();
+                if bytes == [0u8; 8] {
+                    continue;
+                }
+                non_zero += 1;
+                let value = f64::from_le_bytes(bytes);
+                if value.is_normal() && (1e-6..=1e15).contains(&value.abs()) {
+                    plausible += 1;
+                }
+            }
+            if non_zero * 2 >= self.rows.len() && plausible as f32 >= MAJORITY * non_zero as f32 {
+                self.claim(found, p, 8, FieldType::Float, plausible as f32 / non_zero as f32);
+                p += 8;
+            } else {
+                p += 1;
+            }
+        }
+    }
+
+    /// Pointeurs mémo : 4 bytes désignant, pour la plupart des lignes, un
+    /// mémo valide du .mmo (avant les textes, qui absorbent les zéros des
+    /// pointeurs alignés sur les blocs)
+    fn find_memos(&mut self, found: &mut Vec<Candidate>) {
+        if self.mmo.is_none() {
+            return;
+        }
+        let mut p = 0;
+        while p + 4 <= self.width() {
+            if !self.claimed[p..p + 4].contains(&true) && self.is_memo(p) {
+                let confidence = self.memo_ratio(p);
+                self.claim(found, p, 4, FieldType::Memo, confidence);
+                p += 4;
+            } else {
+                p += 1;
+            }
+        }
+    }
+
+    /// Zones de texte, suivies de leur remplissage
+    fn find_texts(&mut self, found: &mut Vec<Candidate>) {
+        let width = self.width();
+        let is_text = |s: &Self, p: usize| {
+            !s.claimed[p]
+                && s.rows.iter().all(|r| r[p] == 0 || is_text_byte(r[p]))
+                && s.rows.iter().any(|r| !is_padding(r[p]))
+        };
+        let is_zero = |s: &Self, p: usize| !s.claimed[p] && s.rows.iter().all(|r| r[p] == 0);
+
+        let mut p = 0;
+        while p < width {
+            if !is_text(self, p) {
+                p += 1;
+                continue;
+            }
+            let mut end = p + 1;
+            while end < width && (is_text(self, end) || is_zero(self, end)) && !self.text_boundary(end) {
+                end += 1;
+            }
+
+            let content = self.rows.iter().map(|r| content_length(&r[p..end])).max().unwrap_or(0);
+            // Un seul octet significatif suivi de zéros : entier little-endian
+            if content <= 1 && end - p > 1 {
+                p = end;
+                continue;
+            }
+            let (field_type, confidence) = self.text_type(p, end, content);
+            self.claim(found, p, end - p, field_type, confidence);
+            p = end;
+        }
+    }
+
+    /// Début d'un nouveau champ texte à la position p
+    fn text_boundary(&self, p: usize) -> bool {
+        if self.ratio(|r| is_padding(r[p - 1]) && !is_padding(r[p])) >= MAJORITY {
+            return true;
+        }
+        // Chiffres puis lettres (ex. date suivie d'un nom)
+        let full = |q: usize| self.ratio(|r| !is_padding(r[q])) >= MAJORITY;
+        full(p - 1)
+            && full(p)
+            && self.rows.iter().all(|r| is_padding(r[p - 1]) || r[p - 1].is_ascii_digit())
+            && self.rows.iter().all(|r| is_padding(r[p]) || !r[p].is_ascii_digit())
+    }
+
+    /// Type d'une zone de texte : date AAAAMMJJ ou chaîne
+    fn text_type(&self, start: usize, end: usize, content: usize) -> (FieldType, f32) {
+        let values: Vec<&[u8]> = self.rows.iter()
+            .map(|r| &r[start..start + content_length(&r[start..end])])
+            .filter(|v| !v.is_empty())
+            .collect();
+        let dates = values.iter().filter(|v| is_date(v)).count();
+        if dates > 0 && dates as f32 >= MAJORITY * values.len() as f32 {
+            return (FieldType::Date, dates as f32 / values.len() as f32);
+        }
+
+        // Texte sans zéro au milieu de la valeur
+        let clean = self.ratio(|r| {
+            let value = &r[start..start + content_length(&r[start..end])];
+            !value.contains(&0)
+        });
+        let confidence = 0.6 + 0.4 * clean;
+        (FieldType::String, if content == 1 { confidence * 0.8 } else { confidence })
+    }
+
+    /// Entiers dans les positions restantes
+    fn find_integers(&mut self, found: &mut Vec<Candidate>) {
+        let width = self.width();
+        let mut p = 0;
+        while p < width {
+            if self.claimed[p] {
+                p += 1;
+                continue;
+            }
+            // Un octet variable après un octet constant commence un champ
+            let mut end = p + 1;
+            while end < width
+                && !self.claimed[end]
+                && !(self.entropy[end] >= LOW_ENTROPY && self.entropy[end - 1] < LOW_ENTROPY)
+            {
+                end += 1;
+            }
+            self.integer_span(found, p, end - p);
+            p = end;
+        }
+    }
+
+    fn integer_span(&mut self, found: &mut Vec<Candidate>, start: usize, length: usize) {
+        let constant = self.rows.iter().all(|r| r[start..start + length] == self.rows[0][start..start + length]);
+        match length {
+            1 if constant => self.claim(found, start, 1, FieldType::Unsigned, 0.3),
+            2 | 4 | 8 if constant => self.claim(found, start, length, FieldType::Integer, 0.3),
+            _ if constant || length > 8 => self.claim(found, start, length, FieldType::Binary, 0.2),
+            1 => self.claim(found, start, 1, FieldType::Unsigned, 0.6),
+            2 | 4 | 8 => {
+                // Octet de poids fort nul ou d'extension de signe
+                let top = start + length - 1;
+                let confidence = 0.4 + 0.5 * self.ratio(|r| r[top] == 0x00 || r[top] == 0xFF);
+                self.claim(found, start, length, FieldType::Integer, confidence);
+            }
+            _ => {
+                // Largeur non standard : découpage en entiers plus petits
+                let mut offset = start;
+                for width in [4, 2, 1] {
+                    while offset + width <= start + length {
+                        let field_type = if width == 1 { FieldType::Unsigned } else { FieldType::Integer };
+                        self.claim(found, offset, width, field_type, 0.4);
+                        offset += width;
+                    }
+                }
+            }
+        }
+    }
+
+    fn is_memo(&mut self, start: usize) -> bool {
+        self.mmo.is_some() && self.memo_ratio(start) >= MAJORITY
+    }
+
+    /// Part des pointeurs non nuls désignant un bloc du .mmo
+    fn memo_ratio(&mut self, start: usize) -> f32 {
+        let Some(mmo) = self.mmo.as_mut() else {
+            return 0.0;
+        };
+        let pointers: Vec<u32> = self.rows.iter()
+            .map(|r| u32::from_le_bytes(r[start..start + 4].try_into().unwrap()))
+            .filter(|&p| p != 0 && p != u32::MAX)
+            .collect();
+        if pointers.is_empty() {
+            return 0.0;
+        }
+        let valid = pointers.iter().filter(|&&p| mmo.has_block(p)).count();
+        valid as f32 / pointers.len() as f32
+    }
+}
+
+/// Entropie de Shannon des valeurs d'une position, normalisée entre 0 et 1
+fn position_entropy(rows: &[&[u8]], p: usize) -> f32 {
+    let mut counts = [0usize; 256];
+    for row in rows {
+        counts[row[p] as usize] += 1;
+    }
+    let total = rows.len() as f32;
+    let max = total.min(256.0).log2();
+    if max <= 0.0 {
+        return 0.0;
+    }
+    let entropy: f32 = counts.iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let probability = c as f32 / total;
+            -probability * probability.log2()
+        })
+        .sum();
+    entropy / max
+}
+
+/// Caractère imprimable en Windows-1252
+fn is_text_byte(b: u8) -> bool {
+    (0x20..=0x7E).contains(&b) || b >= 0xA0
+}
+
+/// Remplissage d'une chaîne (zéro ou espace)
+fn is_padding(b: u8) -> bool {
+    b == 0 || b == b' '
+}
+
+/// Longueur de la valeur sans le remplissage final
+fn content_length(bytes: &[u8]) -> usize {
+    bytes.iter().rposition(|&b| !is_padding(b)).map(|i| i + 1).unwrap_or(0)
+}
+
+/// Date AAAAMMJJ plausible
+fn is_date(value: &[u8]) -> bool {
+    if value.len() != 8 || !value.iter().all(u8::is_ascii_digit) {
+        return false;
+    }
+    let number = |range: std::ops::Range<usize>| {
+        value[range].iter().fold(0u32, |n, &b| n * 10 + (b - b'0') as u32)
+    };
+    (1800..=2200).contains(&number(0..4)) && (1..=12).contains(&number(4..6)) && (1..=31).contains(&number(6..8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::MemoKind;
+    use crate::core::fic::encode_header;
+
+    #[test]
+    fn test_infer_schema() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut mmo = MmoFile::open_rw(dir.path().join("client.mmo"))?;
+        let notes = [
+            mmo.append_block(b"Client fidele", MemoKind::Text)?,
+            mmo.append_block(b"Relancer", MemoKind::Text)?,
+            0,
+            mmo.append_block(b"A rappeler", MemoKind::Text)?,
+            0,
+        ];
+
+        // CODE (6), AGE (i32), PRIX (f64), DATE (AAAAMMJJ), NOTES (mémo)
+        let rows = [
+            ("C001", 18i32, 12.5f64, "20240115"),
+            ("C002", 25, 99.99, "20231231"),
+            ("C003", 42, 1500.0, "19990704"),
+            ("C004", 67, 0.5, "20100228"),
+            ("C005", 80, 42.0, "20240301"),
+        ];
+        let mut bytes = encode_header(31, rows.len() as u32);
+        for (i, (code, age, prix, date)) in rows.iter().enumerate() {
+            bytes.push(0);
+            let mut code_bytes = code.as_bytes().to_vec();
+            code_bytes.resize(6, 0);
+            bytes.extend_from_slice(&code_bytes);
+            bytes.extend_from_slice(&age.to_le_bytes());
+            bytes.extend_from_slice(&prix.to_le_bytes());
+            bytes.extend_from_slice(date.as_bytes());
+            bytes.extend_from_slice(&notes[i].to_le_bytes());
+        }
+        let path = dir.path().join("client.fic");
+        std::fs::write(&path, bytes)?;
+
+        let mut fic = FicFile::open(&path)?;
+        let inferred = infer_schema("client", &mut fic, Some(&mut mmo), DEFAULT_SAMPLE_SIZE)?;
+        assert_eq!(inferred.sampled, 5);
+        let layout: Vec<(u32, u32, String)> = inferred.schema.fields.iter()
+            .map(|f| (f.offset, f.length, format!("{:?}", f.field_type)))
+            .collect();
+        assert_eq!(layout, vec![
+            (1, 6, "String".to_string()),
+            (7, 4, "Integer".to_string()),
+            (11, 8, "Float".to_string()),
+            (19, 8, "Date".to_string()),
+            (27, 4, "Memo".to_string()),
+        ]);
+        assert!(inferred.confidence.iter().all(|c| (0.0..=1.0).contains(c)));
+        assert!(inferred.confidence[0] > 0.9);
+
+        // Sans .mmo, le pointeur reste un entier (son byte de poids faible,
+        // nul pour un pointeur aligné sur les blocs, prolonge la date)
+        let inferred = infer_schema("client", &mut fic, None, DEFAULT_SAMPLE_SIZE)?;
+        let pointer: Vec<&FieldType> = inferred.schema.fields.iter()
+            .filter(|f| f.offset >= 28)
+            .map(|f| &f.field_type)
+            .collect();
+        assert!(!pointer.is_empty());
+        assert!(pointer.iter().all(|t| matches!(t, FieldType::Integer | FieldType::Unsigned)));
+        assert_eq!(infer_schema("client", &mut fic, None, 2)?.sampled, 2);
+        Ok(())
+    }
+}
@@ -5,42 +5,188 @@
  * qui contiennent les données de type mémo (texte long, blobs) référencées
  * par les enregistrements dans les fichiers .fic.
  * 
- * Structure d'un fichier .mmo :
- * - Blocs de données de taille variable
- * - Chaque bloc commence par sa longueur (4 bytes)
- * - Les données peuvent être du texte (Windows-1252 ou UTF-8) ou binaires
+ * Structure d'un fichier .mmo (valeurs little-endian) :
+ * - Header (bloc 0) : signature "MMO\0", version (u16) à 0x04, taille des
+ *   blocs (u32) à 0x08 ; le reste du bloc est réservé
+ * - Blocs de taille fixe, alignés sur la taille de bloc. Chaque bloc
+ *   commence par un en-tête de 16 bytes :
+ *   - 0x00 : type (1 = début d'un mémo texte, 2 = début d'un mémo binaire,
+ *     3 = bloc de continuation, 0 = bloc libre)
+ *   - 0x04 : nombre de bytes de données utilisés dans ce bloc (u32)
+ *   - 0x08 : offset du bloc suivant de la chaîne, 0 pour le dernier (u32)
+ *   - 0x0C : longueur totale du mémo (u32, premier bloc uniquement)
+ * - Un mémo plus grand qu'un bloc est réparti sur une chaîne de blocs ; le
+ *   pointeur stocké dans l'enregistrement est l'offset du premier bloc
+ * 
+ * Cette organisation est celle de ce moteur : elle ne reproduit pas les
+ * .mmo écrits par WinDev et n'a été vérifiée que sur des fichiers produits
+ * ici. Les fichiers écrits par les versions précédentes de ce moteur (4
+ * bytes nuls puis des blocs « longueur (4 bytes) + données ») restent
+ * lisibles ; tout autre fichier sans signature (mémos WinDev notamment) est
+ * lu de la même façon, au mieux, mais jamais modifié.
  * 
  * Fonctionnalités :
- * - Lecture de blocs mémo par offset
- * - Décodage automatique en texte (Windows-1252 puis UTF-8)
+ * - Lecture de mémos par offset, en suivant les chaînes de blocs ; chaque
+ *   bloc est validé (alignement, type, longueurs) avant d'être lu, pour
+ *   qu'un pointeur erroné ne provoque pas d'allocation démesurée
+ * - Décodage automatique en texte (UTF-8, sinon Windows-1252 ou l'encodage
+ *   de la rubrique)
+ * - Distinction texte / binaire, avec le type MIME des images et documents
+ *   (JPEG, PNG, GIF, PDF) reconnu à leur signature
  * - Lecture de données brutes
+ * - Ajout de mémos en fin de fichier (écriture des mémos)
+ * - Parcours de tous les blocs (commande `debug`), avec repérage des mémos
+ *   orphelins et des pointeurs invalides
  * 
  * Liens avec d'autres modules :
  * - Utilisé par src/storage/engine.rs pour lire les données mémo
  * - Les offsets sont fournis par les enregistrements FicRecord
  */
 
+use crate::core::{PendingWrite, TextEncoding};
 use anyhow::{Context, Result};
 use byteorder::{LittleEndian, ReadBytesExt};
-use encoding_rs::WINDOWS_1252;
 use serde::{Deserialize, Serialize};
-use std::fs::File;
-use std::io::{Read, Seek, SeekFrom};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::Path;
 
-/// Représente un bloc mémo dans un fichier .mmo
+/// Signature d'un fichier .mmo
+const MMO_MAGIC: &[u8; 4] = b"MMO\0";
+/// Version du format écrite dans le header
+const MMO_VERSION: u16 = 1;
+/// Taille de bloc des fichiers créés
+pub const DEFAULT_BLOCK_SIZE: u32 = 512;
+/// Taille de l'en-tête d'un bloc
+const BLOCK_HEADER_SIZE: u32 = 16;
+/// Taille de bloc maximale acceptée à la lecture
+const MAX_BLOCK_SIZE: u32 = 1 << 20;
+
+/// Type du premier bloc d'un mémo texte
+const BLOCK_TEXT: u8 = 1;
+/// Type du premier bloc d'un mémo binaire
+const BLOCK_BINARY: u8 = 2;
+/// Type d'un bloc de continuation
+const BLOCK_CONTINUATION: u8 = 3;
+
+/// Type d'un mémo, noté dans son premier bloc
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MemoKind {
+    /// Texte (encodage de la rubrique)
+    Text,
+    /// Données binaires (image, document)
+    Binary,
+}
+
+/// Représente un mémo lu dans un fichier .mmo
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MmoBlock {
-    /// Offset du bloc dans le fichier
+    /// Offset du premier bloc dans le fichier
     pub offset: u32,
-    /// Longueur du bloc en bytes
+    /// Longueur du mémo en bytes
     pub length: u32,
-    /// Données brutes du bloc
+    /// Type du mémo noté dans le premier bloc (None pour l'ancien format)
+    pub kind: Option<MemoKind>,
+    /// Nombre de blocs de la chaîne
+    pub blocks: u32,
+    /// Données brutes du mémo
     pub data: Vec<u8>,
     /// Texte décodé (si le bloc contient du texte)
     pub text: Option<String>,
 }
 
+/// Type d'un bloc rencontré lors du parcours d'un fichier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockType {
+    /// Header du fichier (bloc 0)
+    Header,
+    /// Début d'un mémo texte
+    Text,
+    /// Début d'un mémo binaire
+    Binary,
+    /// Suite d'un mémo
+    Continuation,
+    /// Bloc libre
+    Free,
+    /// Bloc illisible
+    Unknown,
+}
+
+/// Bloc décrit par MmoFile::walk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockInfo {
+    /// Offset du bloc dans le fichier
+    pub offset: u64,
+    /// Type du bloc
+    pub block_type: BlockType,
+    /// Bytes de données utilisés dans le bloc
+    pub used: u32,
+    /// Offset du bloc suivant de la chaîne
+    pub next: Option<u32>,
+    /// Longueur totale du mémo (premier bloc)
+    pub length: Option<u32>,
+    /// Nombre de blocs du mémo (premier bloc)
+    pub blocks: Option<u32>,
+    /// Encodage probable d'un mémo texte (ascii, utf-8, windows-1252, utf-16le)
+    pub encoding: Option<String>,
+    /// Type MIME d'un mémo binaire
+    pub mime_type: Option<String>,
+    /// Début du texte, ou premiers bytes en hexadécimal pour un binaire
+    pub preview: Option<String>,
+    /// Erreur de lecture du bloc ou de sa chaîne
+    pub error: Option<String>,
+}
+
+/// Pointeur mémo d'un enregistrement du .fic
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoReference {
+    /// Identifiant de l'enregistrement
+    pub record: u32,
+    /// Rubrique mémo
+    pub field: String,
+    /// Offset pointé dans le .mmo
+    pub offset: u32,
+}
+
+/// Résultat de MmoFile::inspect
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MmoReport {
+    /// Taille des blocs (None pour l'ancien format ou un fichier vide)
+    pub block_size: Option<u32>,
+    /// Tous les blocs du fichier, dans l'ordre
+    pub blocks: Vec<BlockInfo>,
+    /// Indique si les pointeurs des enregistrements ont été vérifiés
+    pub references_checked: bool,
+    /// Nombre de pointeurs vérifiés
+    pub reference_count: usize,
+    /// Offsets des mémos qu'aucun enregistrement ne référence
+    pub orphaned: Vec<u64>,
+    /// Pointeurs d'enregistrements ne désignant pas un mémo valide
+    pub dangling: Vec<MemoReference>,
+}
+
+/// Organisation d'un fichier .mmo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Layout {
+    /// Header puis blocs chaînés de taille fixe
+    Blocks { block_size: u32 },
+    /// Ancien format de ce moteur : longueur (4 bytes) + données
+    Legacy,
+    /// Ni signature ni ancien format (fichier d'une autre application) :
+    /// mémos lus au mieux comme dans l'ancien format, aucune écriture
+    Unknown,
+}
+
+/// En-tête d'un bloc
+#[derive(Debug, Clone, Copy)]
+struct BlockHeader {
+    kind: u8,
+    used: u32,
+    next: u32,
+    total: u32,
+}
+
 /// Gestionnaire de fichier .mmo permettant la lecture des blocs mémo
 pub struct MmoFile {
     /// Chemin du fichier
@@ -48,6 +194,10 @@ pub struct MmoFile {
     path: std::path::PathBuf,
     /// Handle du fichier ouvert
     file: Option<File>,
+    /// Organisation du fichier (None : fichier vide, format choisi à la première écriture)
+    layout: Option<Layout>,
+    /// Fin du fichier en tenant compte des ajouts préparés (plan_append)
+    planned_end: Option<u64>,
 }
 
 impl MmoFile {
@@ -55,66 +205,464 @@ impl MmoFile {
      * Ouvre un fichier .mmo en lecture.
      * 
      * @param path - Chemin vers le fichier .mmo
-     * @returns Result<MmoFile> - Gestionnaire de fichier ou erreur
+     * @returns Result<MmoFile> - Gestionnaire de fichier ou erreur (header invalide)
      * 
      * Effets de bord :
-     * - Ouvre le fichier en lecture
+     * - Ouvre le fichier en lecture et lit son header
      */
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
         let path = path.as_ref().to_path_buf();
-        let file = File::open(&path)
+        let mut file = File::open(&path)
             .with_context(|| format!("Impossible d'ouvrir le fichier: {:?}", path))?;
+        let layout = Self::read_layout(&mut file)
+            .with_context(|| format!("Header .mmo invalide: {:?}", path))?;
 
         Ok(Self {
             path,
             file: Some(file),
+            layout,
+            planned_end: None,
         })
     }
 
     /**
-     * Lit un bloc mémo à l'offset spécifié.
+     * Ouvre un fichier .mmo en lecture et écriture (créé s'il n'existe pas).
+     * 
+     * @param path - Chemin vers le fichier .mmo
+     * @returns Result<MmoFile> - Gestionnaire de fichier ou erreur
+     * 
+     * Effets de bord :
+     * - Ouvre (ou crée) le fichier en lecture/écriture et lit son header
+     */
+    pub fn open_rw<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .with_context(|| format!("Impossible d'ouvrir le fichier en écriture: {:?}", path))?;
+        let layout = Self::read_layout(&mut file)
+            .with_context(|| format!("Header .mmo invalide: {:?}", path))?;
+
+        Ok(Self {
+            path,
+            file: Some(file),
+            layout,
+            planned_end: None,
+        })
+    }
+
+    /**
+     * Détermine l'organisation d'un fichier d'après son header.
+     * 
+     * @param file - Fichier ouvert
+     * @returns Result<Option<Layout>> - None pour un fichier vide
+     * 
+     * Effets de bord :
+     * - Lit le début du fichier
+     */
+    fn read_layout(file: &mut File) -> Result<Option<Layout>> {
+        let size = file.metadata()?.len();
+        if size == 0 {
+            return Ok(None);
+        }
+        let mut header = [0u8; 12];
+        file.seek(SeekFrom::Start(0))?;
+        let read = file.read(&mut header)?;
+        if read < header.len() || &header[..4] != MMO_MAGIC {
+            // L'ancien format commence par 4 bytes nuls ; tout autre contenu
+            // (mémos écrits par WinDev notamment) n'est lu qu'au mieux
+            return Ok(Some(if read >= 4 && header[..4] == [0; 4] { Layout::Legacy } else { Layout::Unknown }));
+        }
+        let block_size = u32::from_le_bytes(header[8..12].try_into().unwrap());
+        if !block_size.is_power_of_two() || !(2 * BLOCK_HEADER_SIZE..=MAX_BLOCK_SIZE).contains(&block_size) {
+            anyhow::bail!("Taille de bloc invalide: {}", block_size);
+        }
+        Ok(Some(Layout::Blocks { block_size }))
+    }
+
+    /**
+     * Prépare l'ajout d'un mémo à la fin du fichier.
+     * 
+     * Un fichier vide reçoit d'abord son header. Le mémo est découpé en une
+     * chaîne de blocs consécutifs. Les blocs remplacés ne sont pas récupérés :
+     * ils restent orphelins dans le fichier. Plusieurs préparations
+     * successives s'enchaînent en fin de fichier.
+     * 
+     * @param data - Contenu du mémo
+     * @param kind - Type du mémo (noté dans le premier bloc)
+     * @returns Result<(u32, Vec<PendingWrite>)> - Offset du premier bloc, à
+     *   stocker dans l'enregistrement, et écritures à appliquer
+     */
+    pub fn plan_append(&mut self, data: &[u8], kind: MemoKind) -> Result<(u32, Vec<PendingWrite>)> {
+        let mut end = match self.planned_end {
+            Some(end) => end,
+            None => self.file.as_ref()
+                .context("Fichier non ouvert")?
+                .metadata()?
+                .len(),
+        };
+        let mut writes = Vec::new();
+        let layout = match self.layout {
+            Some(layout) => layout,
+            None => {
+                let mut header = vec![0u8; DEFAULT_BLOCK_SIZE as usize];
+                header[..4].copy_from_slice(MMO_MAGIC);
+                header[4..6].copy_from_slice(&MMO_VERSION.to_le_bytes());
+                header[8..12].copy_from_slice(&DEFAULT_BLOCK_SIZE.to_le_bytes());
+                writes.push(PendingWrite { offset: 0, data: header });
+                end = DEFAULT_BLOCK_SIZE as u64;
+                Layout::Blocks { block_size: DEFAULT_BLOCK_SIZE }
+            }
+        };
+        let length = u32::try_from(data.len())
+            .context("Mémo trop volumineux")?;
+
+        let (offset, block) = match layout {
+            Layout::Unknown => anyhow::bail!("Format .mmo non reconnu, écriture refusée: {:?}", self.path),
+            Layout::Legacy => {
+                let mut block = Vec::with_capacity(4 + data.len());
+                block.extend_from_slice(&length.to_le_bytes());
+                block.extend_from_slice(data);
+                (end, block)
+            }
+            Layout::Blocks { block_size } => {
+                let start = end.div_ceil(block_size as u64) * block_size as u64;
+                let capacity = (block_size - BLOCK_HEADER_SIZE) as usize;
+                let chunks: Vec<&[u8]> = if data.is_empty() { vec![data] } else { data.chunks(capacity).collect() };
+                u32::try_from(start + (chunks.len() as u64 - 1) * block_size as u64)
+                    .context("Fichier .mmo trop volumineux pour des pointeurs 32 bits")?;
+
+                let mut blocks = vec![0u8; chunks.len() * block_size as usize];
+                for (i, (chunk, block)) in chunks.iter().zip(blocks.chunks_mut(block_size as usize)).enumerate() {
+                    let is_last = i + 1 == chunks.len();
+                    let next = if is_last { 0 } else { (start + (i as u64 + 1) * block_size as u64) as u32 };
+                    block[0] = match (i, kind) {
+                        (0, MemoKind::Text) => BLOCK_TEXT,
+                        (0, MemoKind::Binary) => BLOCK_BINARY,
+                        _ => BLOCK_CONTINUATION,
+                    };
+                    block[4..8].copy_from_slice(&(chunk.len() as u32).to_le_bytes());
+                    block[8..12].copy_from_slice(&next.to_le_bytes());
+                    if i == 0 {
+                        block[12..16].copy_from_slice(&length.to_le_bytes());
+                    }
+                    block[BLOCK_HEADER_SIZE as usize..BLOCK_HEADER_SIZE as usize + chunk.len()].copy_from_slice(chunk);
+                }
+                (start, blocks)
+            }
+        };
+        let offset = u32::try_from(offset)
+            .context("Fichier .mmo trop volumineux pour des pointeurs 32 bits")?;
+
+        self.planned_end = Some(offset as u64 + block.len() as u64);
+        self.layout = Some(layout);
+        writes.push(PendingWrite { offset: offset as u64, data: block });
+        Ok((offset, writes))
+    }
+
+    /**
+     * Ajoute un mémo à la fin du fichier (sans journal).
+     * 
+     * @param data - Contenu du mémo
+     * @param kind - Type du mémo
+     * @returns Result<u32> - Offset du premier bloc, à stocker dans l'enregistrement
+     * 
+     * Effets de bord :
+     * - Écrit à la fin du fichier
+     */
+    pub fn append_block(&mut self, data: &[u8], kind: MemoKind) -> Result<u32> {
+        let (offset, writes) = self.plan_append(data, kind)?;
+        let file = self.file.as_mut()
+            .context("Fichier non ouvert")?;
+        for write in writes {
+            file.seek(SeekFrom::Start(write.offset))?;
+            file.write_all(&write.data)?;
+        }
+        Ok(offset)
+    }
+
+    /**
+     * Lit un mémo à l'offset spécifié.
      * 
-     * Positionne le curseur à l'offset donné, lit la longueur du bloc,
-     * puis lit les données. Tente automatiquement de décoder en texte
-     * (Windows-1252 puis UTF-8).
+     * Suit la chaîne de blocs à partir du premier bloc, après avoir vérifié
+     * chaque bloc (alignement, type, longueurs cohérentes avec la taille du
+     * fichier). Tente automatiquement de décoder en texte (UTF-8 puis
+     * Windows-1252), sauf pour un mémo binaire.
      * 
-     * @param offset - Offset du bloc dans le fichier (en bytes)
-     * @returns Result<MmoBlock> - Bloc mémo lu ou erreur
+     * @param offset - Offset du premier bloc dans le fichier (en bytes)
+     * @returns Result<MmoBlock> - Mémo lu, ou erreur si le pointeur ne désigne
+     *   pas un mémo valide
      * 
      * Effets de bord :
      * - Lit depuis le fichier (position modifiée)
      */
     pub fn read_block(&mut self, offset: u32) -> Result<MmoBlock> {
+        let layout = self.layout;
         let file = self.file.as_mut()
             .context("Fichier non ouvert")?;
+        let size = file.metadata()?.len();
 
-        file.seek(SeekFrom::Start(offset as u64))?;
-
-        // Structure hypothétique d'un bloc mémo:
-        // - 4 bytes: longueur du bloc
-        // - N bytes: données
-        let length = file.read_u32::<LittleEndian>()?;
-        
-        let mut data = vec![0u8; length as usize];
-        file.read_exact(&mut data)?;
-
-        // Tentative de décodage en texte (UTF-8 ou Windows-1252)
-        let text = if let Ok(utf8_str) = std::str::from_utf8(&data) {
-            Some(utf8_str.to_string())
-        } else {
-            // Essayer Windows-1252 (CP1252) pour les fichiers français avec accents
-            let (decoded, _, _) = WINDOWS_1252.decode(&data);
-            Some(decoded.into_owned())
+        let (data, kind, blocks) = match layout {
+            None => anyhow::bail!("Fichier .mmo vide : aucun mémo à l'offset {}", offset),
+            // Format non reconnu : lecture au mieux « longueur + données »
+            Some(Layout::Legacy | Layout::Unknown) => {
+                let length = Self::legacy_length(file, offset, size)
+                    .with_context(|| format!("Aucun mémo valide à l'offset {}", offset))?;
+                let mut data = vec![0u8; length as usize];
+                file.read_exact(&mut data)?;
+                (data, None, 1)
+            }
+            Some(Layout::Blocks { block_size }) => {
+                let (data, kind, blocks) = Self::read_chain(file, offset, block_size, size)?;
+                (data, Some(kind), blocks)
+            }
         };
 
+        // Texte décodé, sauf pour un mémo noté binaire ou au contenu binaire
+        let is_text = kind != Some(MemoKind::Binary) && binary_mime_type(&data, TextEncoding::default()).is_none();
+        let text = is_text.then(|| decode_text(&data, TextEncoding::default()));
+
         Ok(MmoBlock {
             offset,
-            length,
+            length: data.len() as u32,
+            kind,
+            blocks,
             data,
             text,
         })
     }
 
+    /**
+     * Lit la longueur d'un bloc de l'ancien format et vérifie qu'il tient
+     * dans le fichier (curseur laissé au début des données).
+     */
+    fn legacy_length(file: &mut File, offset: u32, size: u64) -> Result<u32> {
+        if offset == 0 || offset as u64 + 4 > size {
+            anyhow::bail!("Pointeur hors du fichier");
+        }
+        file.seek(SeekFrom::Start(offset as u64))?;
+        let length = file.read_u32::<LittleEndian>()?;
+        if offset as u64 + 4 + length as u64 > size {
+            anyhow::bail!("Longueur {} au-delà de la fin du fichier", length);
+        }
+        Ok(length)
+    }
+
+    /// Lit et vérifie l'en-tête du bloc à l'offset donné
+    fn read_block_header(file: &mut File, offset: u64, block_size: u32, size: u64) -> Result<BlockHeader> {
+        if offset < block_size as u64 || !offset.is_multiple_of(block_size as u64) || offset + block_size as u64 > size {
+            anyhow::bail!("Offset de bloc invalide: {} (blocs de {} bytes, fichier de {} bytes)", offset, block_size, size);
+        }
+        let mut bytes = [0u8; BLOCK_HEADER_SIZE as usize];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut bytes)?;
+        let header = BlockHeader {
+            kind: bytes[0],
+            used: u32::from_le_bytes(bytes[4..8].try_into().unwrap()),
+            next: u32::from_le_bytes(bytes[8..12].try_into().unwrap()),
+            total: u32::from_le_bytes(bytes[12..16].try_into().unwrap()),
+        };
+        if header.used > block_size - BLOCK_HEADER_SIZE {
+            anyhow::bail!("Bloc {} : {} bytes utilisés pour des blocs de {} bytes", offset, header.used, block_size);
+        }
+        Ok(header)
+    }
+
+    /**
+     * Lit un mémo en suivant sa chaîne de blocs.
+     * 
+     * La longueur totale annoncée est bornée par la taille du fichier avant
+     * toute allocation ; la chaîne est bornée par le nombre de blocs du
+     * fichier (protection contre les boucles).
+     * 
+     * @returns Result<(Vec<u8>, MemoKind, u32)> - Données, type et nombre de blocs
+     */
+    fn read_chain(file: &mut File, offset: u32, block_size: u32, size: u64) -> Result<(Vec<u8>, MemoKind, u32)> {
+        let first = Self::read_block_header(file, offset as u64, block_size, size)
+            .with_context(|| format!("Aucun mémo valide à l'offset {}", offset))?;
+        let kind = match first.kind {
+            BLOCK_TEXT => MemoKind::Text,
+            BLOCK_BINARY => MemoKind::Binary,
+            other => anyhow::bail!("Le bloc {} n'est pas le début d'un mémo (type {})", offset, other),
+        };
+        let capacity = (block_size - BLOCK_HEADER_SIZE) as u64;
+        let max_blocks = size / block_size as u64;
+        if first.total as u64 > max_blocks * capacity {
+            anyhow::bail!("Mémo {} : longueur {} incohérente avec la taille du fichier", offset, first.total);
+        }
+
+        let mut data = Vec::with_capacity(first.total as usize);
+        let mut header = first;
+        let mut block_offset = offset as u64;
+        let mut blocks = 1u32;
+        loop {
+            if data.len() as u64 + header.used as u64 > first.total as u64 {
+                anyhow::bail!("Mémo {} : la chaîne de blocs dépasse la longueur annoncée ({})", offset, first.total);
+            }
+            let start = data.len();
+            data.resize(start + header.used as usize, 0);
+            file.seek(SeekFrom::Start(block_offset + BLOCK_HEADER_SIZE as u64))?;
+            file.read_exact(&mut data[start..])?;
+
+            if header.next == 0 {
+                break;
+            }
+            if blocks as u64 >= max_blocks {
+                anyhow::bail!("Mémo {} : chaîne de blocs circulaire", offset);
+            }
+            block_offset = header.next as u64;
+            header = Self::read_block_header(file, block_offset, block_size, size)?;
+            if header.kind != BLOCK_CONTINUATION {
+                anyhow::bail!("Mémo {} : le bloc {} n'est pas un bloc de continuation", offset, block_offset);
+            }
+            blocks += 1;
+        }
+        if data.len() != first.total as usize {
+            anyhow::bail!("Mémo {} incomplet : {} bytes lus sur {}", offset, data.len(), first.total);
+        }
+        Ok((data, kind, blocks))
+    }
+
+    /// Taille des blocs (None pour l'ancien format ou un fichier vide)
+    pub fn block_size(&self) -> Option<u32> {
+        match self.layout {
+            Some(Layout::Blocks { block_size }) => Some(block_size),
+            _ => None,
+        }
+    }
+
+    /**
+     * Parcourt tous les blocs du fichier.
+     * 
+     * Chaque début de mémo est lu en entier (chaîne de blocs) pour en
+     * déduire la longueur, l'encodage probable et un aperçu. Une erreur sur
+     * un bloc est notée dans le bloc sans interrompre le parcours, sauf dans
+     * l'ancien format où une longueur invalide empêche de trouver la suite.
+     * 
+     * @returns Result<Vec<BlockInfo>> - Blocs dans l'ordre du fichier
+     * 
+     * Effets de bord :
+     * - Lit tout le fichier
+     */
+    pub fn walk(&mut self) -> Result<Vec<BlockInfo>> {
+        let layout = self.layout;
+        let file = self.file.as_mut()
+            .context("Fichier non ouvert")?;
+        let size = file.metadata()?.len();
+        let mut blocks = Vec::new();
+
+        match layout {
+            None => {}
+            Some(Layout::Unknown) => anyhow::bail!("Format .mmo non reconnu: {:?}", self.path),
+            Some(Layout::Legacy) => {
+                let mut offset = 4u64;
+                while offset + 4 <= size {
+                    let mut info = BlockInfo::new(offset, BlockType::Unknown);
+                    match u32::try_from(offset).map_err(anyhow::Error::from).and_then(|o| Self::legacy_length(file, o, size)) {
+                        Ok(length) => {
+                            let mut data = vec![0u8; length as usize];
+                            file.read_exact(&mut data)?;
+                            info.used = length;
+                            info.length = Some(length);
+                            info.blocks = Some(1);
+                            info.describe(&data, None);
+                            blocks.push(info);
+                            offset += 4 + length as u64;
+                        }
+                        Err(e) => {
+                            info.error = Some(format!("{:#}", e));
+                            blocks.push(info);
+                            break;
+                        }
+                    }
+                }
+            }
+            Some(Layout::Blocks { block_size }) => {
+                blocks.push(BlockInfo::new(0, BlockType::Header));
+                let mut offset = block_size as u64;
+                while offset < size {
+                    let mut info = BlockInfo::new(offset, BlockType::Unknown);
+                    match Self::read_block_header(file, offset, block_size, size) {
+                        Ok(header) => {
+                            info.used = header.used;
+                            info.next = (header.next != 0).then_some(header.next);
+                            info.block_type = match header.kind {
+                                0 => BlockType::Free,
+                                BLOCK_TEXT => BlockType::Text,
+                                BLOCK_BINARY => BlockType::Binary,
+                                BLOCK_CONTINUATION => BlockType::Continuation,
+                                other => {
+                                    info.error = Some(format!("Type de bloc inconnu: {}", other));
+                                    BlockType::Unknown
+                                }
+                            };
+                            if matches!(info.block_type, BlockType::Text | BlockType::Binary) {
+                                info.length = Some(header.total);
+                                match u32::try_from(offset).map_err(anyhow::Error::from)
+                                    .and_then(|o| Self::read_chain(file, o, block_size, size))
+                                {
+                                    Ok((data, kind, count)) => {
+                                        info.blocks = Some(count);
+                                        info.describe(&data, Some(kind));
+                                    }
+                                    Err(e) => info.error = Some(format!("{:#}", e)),
+                                }
+                            }
+                        }
+                        Err(e) => info.error = Some(format!("{:#}", e)),
+                    }
+                    blocks.push(info);
+                    offset += block_size as u64;
+                }
+            }
+        }
+        Ok(blocks)
+    }
+
+    /**
+     * Parcourt le fichier et le confronte aux pointeurs des enregistrements.
+     * 
+     * @param references - Pointeurs mémo des enregistrements du .fic (None :
+     *   pas de vérification des références)
+     * @returns Result<MmoReport> - Blocs, mémos orphelins (non référencés) et
+     *   pointeurs invalides
+     * 
+     * Effets de bord :
+     * - Lit tout le fichier
+     */
+    pub fn inspect(&mut self, references: Option<&[MemoReference]>) -> Result<MmoReport> {
+        let blocks = self.walk()?;
+        let (orphaned, dangling) = match references {
+            Some(references) => {
+                let referenced: std::collections::HashSet<u64> = references.iter()
+                    .map(|r| r.offset as u64)
+                    .collect();
+                let orphaned = blocks.iter()
+                    .filter(|b| matches!(b.block_type, BlockType::Text | BlockType::Binary) && !referenced.contains(&b.offset))
+                    .map(|b| b.offset)
+                    .collect();
+                let dangling = references.iter()
+                    .filter(|r| !self.has_block(r.offset))
+                    .cloned()
+                    .collect();
+                (orphaned, dangling)
+            }
+            None => (Vec::new(), Vec::new()),
+        };
+
+        Ok(MmoReport {
+            block_size: self.block_size(),
+            blocks,
+            references_checked: references.is_some(),
+            reference_count: references.map_or(0, |r| r.len()),
+            orphaned,
+            dangling,
+        })
+    }
+
     /**
      * Lit un bloc mémo et retourne uniquement le texte décodé.
      * 
@@ -129,6 +677,24 @@ impl MmoFile {
         block.text.ok_or_else(|| anyhow::anyhow!("Bloc non textuel à l'offset {}", offset))
     }
 
+    /**
+     * Lit un bloc mémo et le décode avec l'encodage de la rubrique.
+     * 
+     * Un texte UTF-8 valide est conservé tel quel, sauf pour une rubrique
+     * UTF-16LE dont les caractères ASCII forment aussi de l'UTF-8 valide.
+     * 
+     * @param offset - Offset du bloc dans le fichier
+     * @param encoding - Encodage de la rubrique mémo
+     * @returns Result<String> - Texte décodé
+     * 
+     * Effets de bord :
+     * - Lit depuis le fichier
+     */
+    pub fn read_text_with(&mut self, offset: u32, encoding: TextEncoding) -> Result<String> {
+        let data = self.read_raw(offset)?;
+        Ok(decode_text(&data, encoding))
+    }
+
     /**
      * Lit un bloc mémo et retourne les données brutes (sans décodage).
      * 
@@ -142,6 +708,147 @@ impl MmoFile {
         let block = self.read_block(offset)?;
         Ok(block.data)
     }
+
+    /**
+     * Indique si un mémo valide commence à l'offset donné, sans lire son
+     * contenu (validation de pointeurs mémo supposés).
+     *
+     * @param offset - Offset supposé d'un mémo
+     * @returns bool - true si le premier bloc est valide (alignement, type,
+     *   longueurs compatibles avec la taille du fichier)
+     *
+     * Effets de bord :
+     * - Lit l'en-tête du bloc depuis le fichier
+     */
+    pub fn has_block(&mut self, offset: u32) -> bool {
+        let layout = self.layout;
+        let Some(file) = self.file.as_mut() else {
+            return false;
+        };
+        let Ok(size) = file.metadata().map(|m| m.len()) else {
+            return false;
+        };
+        match layout {
+            None => false,
+            Some(Layout::Legacy | Layout::Unknown) => Self::legacy_length(file, offset, size).is_ok(),
+            Some(Layout::Blocks { block_size }) => {
+                let capacity = (block_size - BLOCK_HEADER_SIZE) as u64;
+                match Self::read_block_header(file, offset as u64, block_size, size) {
+                    Ok(header) => matches!(header.kind, BLOCK_TEXT | BLOCK_BINARY)
+                        && header.used <= header.total
+                        && header.total as u64 <= (size / block_size as u64) * capacity,
+                    Err(_) => false,
+                }
+            }
+        }
+    }
+}
+
+impl BlockInfo {
+    fn new(offset: u64, block_type: BlockType) -> Self {
+        Self {
+            offset,
+            block_type,
+            used: 0,
+            next: None,
+            length: None,
+            blocks: None,
+            encoding: None,
+            mime_type: None,
+            preview: None,
+            error: None,
+        }
+    }
+
+    /// Renseigne l'encodage probable (ou le type MIME) et l'aperçu d'un mémo
+    fn describe(&mut self, data: &[u8], kind: Option<MemoKind>) {
+        const PREVIEW_CHARS: usize = 60;
+        let signature = MIME_SIGNATURES.iter().find(|(signature, _)| data.starts_with(signature));
+        let encoding = if signature.is_some() || kind == Some(MemoKind::Binary) {
+            None
+        } else if looks_like_utf16(data) {
+            Some(TextEncoding::Utf16Le)
+        } else if binary_mime_type(data, TextEncoding::default()).is_none() {
+            Some(TextEncoding::default())
+        } else {
+            None
+        };
+
+        match encoding {
+            Some(encoding) => {
+                let text = decode_text(data, encoding);
+                self.encoding = Some(match encoding {
+                    TextEncoding::Utf16Le => "utf-16le".to_string(),
+                    _ if data.is_ascii() => "ascii".to_string(),
+                    _ if std::str::from_utf8(data).is_ok() => "utf-8".to_string(),
+                    _ => encoding.name().to_string(),
+                });
+                let mut preview: String = text.chars()
+                    .take(PREVIEW_CHARS)
+                    .map(|c| if c.is_control() { ' ' } else { c })
+                    .collect();
+                if text.chars().nth(PREVIEW_CHARS).is_some() {
+                    preview.push('…');
+                }
+                self.preview = Some(preview);
+            }
+            None => {
+                self.mime_type = Some(signature.map_or("application/octet-stream", |(_, mime)| mime).to_string());
+                self.preview = Some(data.iter().take(16).map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(" "));
+            }
+        }
+    }
+}
+
+/// Texte UTF-16LE probable : la plupart des unités ont un byte de poids fort nul
+fn looks_like_utf16(data: &[u8]) -> bool {
+    let units = data.len() / 2;
+    units > 0
+        && data.len().is_multiple_of(2)
+        && data.chunks(2).filter(|unit| unit[0] != 0 && unit[1] == 0).count() * 4 >= units * 3
+}
+
+/// Signatures des contenus binaires reconnus (début du bloc -> type MIME)
+const MIME_SIGNATURES: [(&[u8], &str); 5] = [
+    (b"\xFF\xD8\xFF", "image/jpeg"),
+    (b"\x89PNG\r\n\x1A\n", "image/png"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"%PDF-", "application/pdf"),
+];
+
+/**
+ * Détermine si un bloc mémo est binaire et, le cas échéant, son type MIME.
+ * 
+ * Les images et documents sont reconnus à leur signature. Les autres blocs
+ * sont considérés comme binaires s'ils contiennent des caractères de
+ * contrôle (hors tabulations, sauts de ligne et zéros de fin), sauf pour
+ * une rubrique UTF-16LE où les zéros font partie du texte.
+ * 
+ * @param data - Contenu du bloc
+ * @param encoding - Encodage de la rubrique mémo
+ * @returns Option<&str> - Type MIME d'un bloc binaire, None pour du texte
+ */
+pub fn binary_mime_type(data: &[u8], encoding: TextEncoding) -> Option<&'static str> {
+    if let Some((_, mime)) = MIME_SIGNATURES.iter().find(|(signature, _)| data.starts_with(signature)) {
+        return Some(mime);
+    }
+    if encoding == TextEncoding::Utf16Le {
+        return None;
+    }
+    let end = data.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+    let is_text = data[..end].iter().all(|&b| b >= 0x20 || matches!(b, b'\t' | b'\n' | b'\r' | 0x0C));
+    (!is_text).then_some("application/octet-stream")
+}
+
+/// Décode un bloc : UTF-8 s'il est valide, sinon l'encodage donné
+pub fn decode_text(data: &[u8], encoding: TextEncoding) -> String {
+    if encoding != TextEncoding::Utf16Le {
+        if let Ok(utf8_str) = std::str::from_utf8(data) {
+            return utf8_str.to_string();
+        }
+    }
+    encoding.decode(data).0
 }
 
 impl Drop for MmoFile {
@@ -150,3 +857,88 @@ impl Drop for MmoFile {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_chains_and_validation() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("notes.mmo");
+        let long_text = "Relance client. ".repeat(80);
+        let (text_offset, photo_offset) = {
+            let mut mmo = MmoFile::open_rw(&path)?;
+            (mmo.append_block(long_text.as_bytes(), MemoKind::Text)?, mmo.append_block(b"\xFF\xD8\xFF\xE0", MemoKind::Binary)?)
+        };
+        assert_eq!(text_offset, DEFAULT_BLOCK_SIZE);
+
+        let mut mmo = MmoFile::open(&path)?;
+        let block = mmo.read_block(text_offset)?;
+        assert_eq!((block.kind, block.blocks, block.length), (Some(MemoKind::Text), 3, long_text.len() as u32));
+        assert_eq!(block.text.as_deref(), Some(long_text.as_str()));
+        let photo = mmo.read_block(photo_offset)?;
+        assert_eq!((photo.kind, photo.text), (Some(MemoKind::Binary), None));
+
+        // Pointeurs erronés : non aligné, bloc de continuation, hors du fichier
+        for offset in [text_offset + 7, text_offset + DEFAULT_BLOCK_SIZE, 1 << 30] {
+            assert!(!mmo.has_block(offset));
+            assert!(mmo.read_block(offset).is_err());
+        }
+
+        // Longueur totale démesurée : refusée avant toute allocation
+        let mut bytes = std::fs::read(&path)?;
+        bytes[text_offset as usize + 12..text_offset as usize + 16].copy_from_slice(&u32::MAX.to_le_bytes());
+        std::fs::write(&path, &bytes)?;
+        assert!(MmoFile::open(&path)?.read_block(text_offset).is_err());
+
+        // Ancien format : 4 bytes réservés puis longueur + données
+        let legacy = dir.path().join("legacy.mmo");
+        std::fs::write(&legacy, [&[0u8; 4][..], &5u32.to_le_bytes(), b"Bonjour", &u32::MAX.to_le_bytes()].concat())?;
+        let mut mmo = MmoFile::open(&legacy)?;
+        assert_eq!(mmo.read_text(4)?, "Bonjo");
+        assert!(mmo.has_block(4));
+        assert!(mmo.read_block(13).is_err());
+
+        // Ni signature ni ancien format : lecture au mieux, aucune écriture
+        let foreign = dir.path().join("foreign.mmo");
+        std::fs::write(&foreign, [&[0x57u8, 0x44, 0x4D, 0x4F][..], &5u32.to_le_bytes(), b"Bonjour"].concat())?;
+        let mut mmo = MmoFile::open(&foreign)?;
+        assert!(mmo.has_block(4));
+        assert_eq!(mmo.read_text(4)?, "Bonjo");
+        assert!(mmo.read_block(13).is_err());
+        assert!(mmo.walk().is_err());
+        assert!(mmo.plan_append(b"x", MemoKind::Text).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_inspect_orphans_and_dangling() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("produit.mmo");
+        let (note, photo, orphan) = {
+            let mut mmo = MmoFile::open_rw(&path)?;
+            (
+                mmo.append_block("Très bon état".as_bytes(), MemoKind::Text)?,
+                mmo.append_block(b"\x89PNG\r\n\x1A\n\0\0\0\rIHDR", MemoKind::Binary)?,
+                mmo.append_block("x".repeat(600).as_bytes(), MemoKind::Text)?,
+            )
+        };
+        let reference = |record, field: &str, offset| MemoReference { record, field: field.to_string(), offset };
+        let references = [reference(0, "NOTE", note), reference(1, "PHOTO", photo), reference(2, "NOTE", 12345)];
+
+        let report = MmoFile::open(&path)?.inspect(Some(&references))?;
+        let types: Vec<BlockType> = report.blocks.iter().map(|b| b.block_type).collect();
+        assert_eq!(types, vec![BlockType::Header, BlockType::Text, BlockType::Binary, BlockType::Text, BlockType::Continuation]);
+        assert_eq!(report.blocks[1].encoding.as_deref(), Some("utf-8"));
+        assert_eq!(report.blocks[1].preview.as_deref(), Some("Très bon état"));
+        assert_eq!(report.blocks[2].mime_type.as_deref(), Some("image/png"));
+        assert_eq!((report.blocks[3].length, report.blocks[3].blocks), (Some(600), Some(2)));
+        assert_eq!(report.orphaned, vec![orphan as u64]);
+        assert_eq!(report.dangling, vec![references[2].clone()]);
+
+        let unchecked = MmoFile::open(&path)?.inspect(None)?;
+        assert!(!unchecked.references_checked && unchecked.orphaned.is_empty());
+        Ok(())
+    }
+}
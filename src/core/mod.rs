@@ -7,25 +7,38 @@
  * - fic.rs : Gestion des fichiers .fic (données principales)
  * - mmo.rs : Gestion des fichiers .mmo (données mémo/blobs)
  * - ndx.rs : Gestion des fichiers .ndx (index)
+ * - descriptor.rs : Description des rubriques embarquée dans les .fic
+ * - encoding.rs : Encodages des chaînes (pages de code, CP850, UTF-16LE)
+ * - infer.rs : Inférence heuristique du schéma (fichiers sans description)
+ * - schema_file.rs : Fichiers de schéma fournis par l'utilisateur
+ * - wdd.rs : Analyses .wdd au format de ce moteur (schémas, clés et liaisons)
  * 
  * Il définit également les structures de schéma (TableSchema, FieldInfo)
  * utilisées pour représenter la structure des tables.
  * 
  * Exports :
- * - FicFile, FicHeader, FicRecord : Structures pour les fichiers .fic
- * - MmoFile, MmoBlock : Structures pour les fichiers .mmo
- * - NdxFile, NdxEntry : Structures pour les fichiers .ndx
+ * - FicFile, FicHeader, FicRecord, MemoPointer : Structures pour les fichiers .fic
+ * - MmoFile, MmoBlock, MemoKind : Structures pour les fichiers .mmo
+ * - NdxFile, NdxEntry, NdxKey : Structures pour les fichiers .ndx (B-tree, descripteurs de clés)
  * - TableSchema, FieldInfo, FieldType : Structures de schéma
+ * - TextEncoding : Encodage des chaînes d'une rubrique
  * - TableFiles : Représentation d'un ensemble de fichiers liés
+ * - PendingWrite : Écriture préparée, appliquée via le journal du stockage
  */
 
 pub mod fic;
 pub mod mmo;
 pub mod ndx;
+pub mod descriptor;
+pub mod encoding;
+pub mod infer;
+pub mod schema_file;
+pub mod wdd;
 
-pub use fic::{FicFile, FicHeader, FicRecord};
-pub use mmo::{MmoFile, MmoBlock};
-pub use ndx::{NdxFile, NdxEntry};
+pub use fic::{FicFile, FicHeader, FicRecord, HeaderLayout, MemoPointer, RawRecord, Records};
+pub use encoding::TextEncoding;
+pub use mmo::{MemoKind, MmoFile, MmoBlock};
+pub use ndx::{KeyComponent, KeyType, NdxEntry, NdxFile, NdxKey};
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -54,6 +67,45 @@ pub struct FieldInfo {
     pub length: u32,
     /// Type de données du champ
     pub field_type: FieldType,
+    /// Encodage des chaînes (libellé WHATWG, ex. "windows-1250", "utf-8",
+    /// ou "cp850", "utf-16le") ; Windows-1252 si absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+    /// Nombre de décimales d'une rubrique numérique ou monétaire ;
+    /// 4 (monétaire) ou 6 (numérique) si absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<u32>,
+}
+
+impl FieldInfo {
+    /**
+     * Retourne l'encodage des chaînes du champ.
+     * 
+     * @returns TextEncoding - UTF-16LE pour une rubrique Unicode, sinon
+     *   l'encodage déclaré, Windows-1252 par défaut (ou si le libellé est
+     *   inconnu)
+     */
+    pub fn text_encoding(&self) -> TextEncoding {
+        if matches!(self.field_type, FieldType::Unicode) {
+            return TextEncoding::Utf16Le;
+        }
+        self.encoding
+            .as_deref()
+            .and_then(TextEncoding::for_label)
+            .unwrap_or_default()
+    }
+
+    /**
+     * Retourne le nombre de décimales d'une rubrique monétaire ou numérique.
+     * 
+     * @returns u32 - Échelle déclarée, sinon celle par défaut du type
+     */
+    pub fn decimal_scale(&self) -> u32 {
+        self.scale.unwrap_or(match self.field_type {
+            FieldType::Currency => 4,
+            _ => 6,
+        })
+    }
 }
 
 /// Types de données supportés pour les champs
@@ -61,12 +113,28 @@ pub struct FieldInfo {
 pub enum FieldType {
     /// Chaîne de caractères
     String,
-    /// Nombre entier
+    /// Chaîne Unicode (UTF-16LE)
+    Unicode,
+    /// Entier signé (1 à 8 bytes, largeur donnée par FieldInfo::length)
     Integer,
+    /// Entier non signé (1 à 8 bytes)
+    Unsigned,
+    /// Booléen (0 = faux, toute autre valeur = vrai)
+    Boolean,
     /// Nombre décimal
     Float,
-    /// Date
+    /// Monétaire (entier de 8 bytes en dix-millièmes)
+    Currency,
+    /// Numérique (entier mis à l'échelle, FieldInfo::scale décimales)
+    Numeric,
+    /// Date (AAAAMMJJ)
     Date,
+    /// Heure (HHMMSS ou HHMMSSCC)
+    Time,
+    /// Date et heure
+    DateTime,
+    /// Durée (millisecondes)
+    Duration,
     /// Mémo (pointeur vers fichier .mmo)
     Memo,
     /// Données binaires
@@ -88,3 +156,15 @@ pub struct TableFiles {
     pub ndx_paths: Vec<PathBuf>,
 }
 
+
+/// Écriture préparée dans un fichier (offset absolu et contenu)
+///
+/// Les méthodes plan_* de FicFile et MmoFile calculent ces écritures sans
+/// toucher au disque ; le journal (src/storage/journal.rs) les applique.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingWrite {
+    /// Offset absolu dans le fichier
+    pub offset: u64,
+    /// Bytes à écrire
+    pub data: Vec<u8>,
+}
@@ -1,37 +1,87 @@
 /**
  * Gestionnaire de fichiers .ndx (fichiers d'index HFSQL).
- * 
+ *
  * Ce fichier contient les structures et fonctions pour lire les fichiers .ndx
  * qui contiennent des index permettant de rechercher rapidement des enregistrements
  * par clé dans les fichiers .fic.
- * 
- * Structure d'un fichier .ndx (simplifiée) :
- * - Header : Magic bytes, nombre d'entrées, longueur de clé
- * - Entrées : Liste de (clé, record_id) permettant de mapper une clé à un ID d'enregistrement
- * 
+ *
+ * Structure d'un fichier .ndx (B-tree paginé, little-endian) :
+ * - Page 0 (header) :
+ *   - 0x00 : magic "NDX\0", 0x04 : version (u16), 0x06 : nombre de clés (u16)
+ *   - 0x08 : taille de page (u32), 0x0C : nombre de pages (u32)
+ *   - 0x10 : descripteurs de clés (KEY_DESCRIPTOR_SIZE bytes chacun) :
+ *     page racine (u32), nombre d'entrées (u32), longueur de clé (u16),
+ *     flags (u16, bit 0 = unique), nombre de composantes (u16), réservé (u16),
+ *     puis MAX_KEY_COMPONENTS composantes : offset dans l'enregistrement (u32),
+ *     longueur (u16), type (u8), flags (u8, bit 0 = insensible à la casse,
+ *     bit 1 = décroissant)
+ * - Pages de nœuds (page_size bytes, à l'offset numéro * page_size) :
+ *   - type (u8 : 1 = feuille, 2 = nœud interne), réservé (u8), nombre
+ *     d'entrées (u16), lien (u32 : feuille suivante, ou premier fils)
+ *   - Feuille : entrées (clé, record_id u32) triées
+ *   - Nœud interne : entrées (séparateur, page fille u32) ; la page fille
+ *     d'un séparateur contient les clés >= séparateur, le premier fils les
+ *     clés inférieures au premier séparateur
+ *
+ * Les clés sont stockées sous une forme comparable octet par octet
+ * (entiers en big-endian avec bit de signe inversé, etc.) : la recherche
+ * descend de la racine vers une feuille en O(log n) pages lues.
+ *
+ * Cette organisation est celle de ce moteur (index construits par REINDEX
+ * ou NdxFile::create) : elle ne reproduit pas les .ndx écrits par WinDev et
+ * n'a été vérifiée que sur des fichiers produits ici. Un fichier sans la
+ * signature "NDX\0" est refusé à l'ouverture ; le moteur l'ignore alors
+ * (is_own_format) et le laisse intact.
+ *
  * Fonctionnalités :
- * - Lecture de l'index complet au chargement
- * - Recherche par clé
- * - Recherche par record_id
- * 
- * Note : Cette implémentation est simplifiée et suppose un format d'index linéaire.
- * Les vrais fichiers .ndx HFSQL utilisent généralement une structure B-tree.
- * 
+ * - Lecture des descripteurs de clés (clés multiples, composantes)
+ * - Correspondance clé -> rubriques via les offsets dans l'enregistrement
+ * - Recherche logarithmique par clé exacte ou par intervalle
+ * - Parcours ordonné de toutes les entrées d'une clé
+ * - Construction d'un fichier complet à partir d'entrées (chargement en masse)
+ * - Ajout et retrait d'entrées (pages modifiées gardées en mémoire, avec
+ *   découpage des pages pleines), écrites via plan_writes
+ *
  * Liens avec d'autres modules :
- * - Utilisé par src/storage/engine.rs pour les recherches indexées
+ * - Utilisé par src/storage/engine.rs pour les recherches indexées, la
+ *   maintenance des index lors des écritures et REINDEX
  */
 
 use anyhow::{Context, Result};
 use byteorder::{LittleEndian, ReadBytesExt};
 use serde::{Deserialize, Serialize};
+use crate::core::PendingWrite;
+use std::collections::BTreeMap;
 use std::fs::File;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::ops::Bound;
 use std::path::Path;
 
+/// Magic d'un fichier .ndx
+const NDX_MAGIC: &[u8; 4] = b"NDX\0";
+/// Version du format
+const NDX_VERSION: u16 = 1;
+/// Taille du header fixe avant les descripteurs de clés
+const NDX_HEADER_SIZE: usize = 0x10;
+/// Taille d'un descripteur de clé dans le header
+pub const KEY_DESCRIPTOR_SIZE: usize = 48;
+/// Nombre maximum de composantes (rubriques) par clé
+pub const MAX_KEY_COMPONENTS: usize = 4;
+/// Taille de l'en-tête d'une page de nœud
+const NODE_HEADER_SIZE: usize = 8;
+/// Taille de page par défaut pour les fichiers construits
+pub const DEFAULT_PAGE_SIZE: u32 = 4096;
+
+/// Entrées d'une clé à indexer : (clé encodée, record_id)
+pub type KeyEntries = Vec<(Vec<u8>, u32)>;
+
+const PAGE_LEAF: u8 = 1;
+const PAGE_INTERNAL: u8 = 2;
+
 /// Entrée dans un index .ndx
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct NdxEntry {
-    /// Clé de l'index (données brutes)
+    /// Clé de l'index (forme comparable octet par octet)
     pub key: Vec<u8>,
     /// ID de l'enregistrement associé dans le fichier .fic
     pub record_id: u32,
@@ -39,126 +89,961 @@ pub struct NdxEntry {
     pub offset: u64,
 }
 
+/// Type d'une composante de clé (détermine l'encodage comparable)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum KeyType {
+    /// Octets bruts
+    Binary,
+    /// Chaîne Windows-1252 complétée par des zéros
+    String,
+    /// Entier signé little-endian (1, 2, 4 ou 8 bytes)
+    Integer,
+    /// Entier non signé little-endian
+    Unsigned,
+    /// Flottant IEEE little-endian (4 ou 8 bytes)
+    Float,
+}
+
+impl KeyType {
+    fn from_code(code: u8) -> Result<Self> {
+        Ok(match code {
+            0 => KeyType::Binary,
+            1 => KeyType::String,
+            2 => KeyType::Integer,
+            3 => KeyType::Unsigned,
+            4 => KeyType::Float,
+            other => anyhow::bail!("Type de composante de clé inconnu: {}", other),
+        })
+    }
+
+    fn code(self) -> u8 {
+        match self {
+            KeyType::Binary => 0,
+            KeyType::String => 1,
+            KeyType::Integer => 2,
+            KeyType::Unsigned => 3,
+            KeyType::Float => 4,
+        }
+    }
+}
+
+/// Composante d'une clé : une rubrique de l'enregistrement
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyComponent {
+    /// Offset de la rubrique dans l'enregistrement (byte de flags inclus,
+    /// comme FieldInfo::offset)
+    pub offset: u32,
+    /// Longueur de la rubrique en bytes
+    pub length: u32,
+    /// Type de la rubrique
+    pub key_type: KeyType,
+    /// Comparaison insensible à la casse (chaînes)
+    pub case_insensitive: bool,
+    /// Ordre décroissant
+    pub descending: bool,
+}
+
+/// Descripteur d'une clé (un index) du fichier .ndx
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NdxKey {
+    /// Numéro de la clé dans le fichier
+    pub number: usize,
+    /// Page racine du B-tree (0 si la clé est vide)
+    pub root_page: u32,
+    /// Nombre d'entrées
+    pub entry_count: u32,
+    /// Longueur d'une clé encodée (somme des longueurs des composantes)
+    pub key_length: u32,
+    /// Clé unique (sans doublons)
+    pub unique: bool,
+    /// Rubriques composant la clé, dans l'ordre
+    pub components: Vec<KeyComponent>,
+}
+
+impl NdxKey {
+    /**
+     * Crée un descripteur de clé (pour construire un fichier avec NdxFile::create).
+     *
+     * @param components - Rubriques composant la clé
+     * @param unique - Clé unique
+     * @returns NdxKey - Descripteur (racine et nombre d'entrées renseignés à la construction)
+     */
+    pub fn new(components: Vec<KeyComponent>, unique: bool) -> Self {
+        let key_length = components.iter().map(|c| c.length).sum();
+        Self { number: 0, root_page: 0, entry_count: 0, key_length, unique, components }
+    }
+
+    /**
+     * Calcule la clé encodée d'un enregistrement.
+     *
+     * @param record - Emplacement complet de l'enregistrement (byte de flags inclus)
+     * @returns Result<Vec<u8>> - Clé comparable octet par octet
+     */
+    pub fn encode(&self, record: &[u8]) -> Result<Vec<u8>> {
+        let mut key = Vec::with_capacity(self.key_length as usize);
+        for component in &self.components {
+            let start = component.offset as usize;
+            let end = start + component.length as usize;
+            let raw = record.get(start..end)
+                .with_context(|| format!("Rubrique de clé hors de l'enregistrement ({}..{})", start, end))?;
+            key.extend(encode_component(component, raw));
+        }
+        Ok(key)
+    }
+
+    /**
+     * Indique si la clé porte uniquement sur la rubrique donnée.
+     *
+     * @param offset - Offset de la rubrique (FieldInfo::offset)
+     * @param length - Longueur de la rubrique
+     */
+    pub fn is_on_field(&self, offset: u32, length: u32) -> bool {
+        matches!(self.components.as_slice(), [c] if c.offset == offset && c.length == length)
+    }
+}
+
+/**
+ * Encode la valeur brute d'une rubrique sous forme comparable octet par octet.
+ */
+fn encode_component(component: &KeyComponent, raw: &[u8]) -> Vec<u8> {
+    let mut bytes = match component.key_type {
+        KeyType::Binary => raw.to_vec(),
+        KeyType::String if component.case_insensitive => raw.iter().map(|b| uppercase_cp1252(*b)).collect(),
+        KeyType::String => raw.to_vec(),
+        KeyType::Integer | KeyType::Unsigned | KeyType::Float => {
+            let mut be: Vec<u8> = raw.iter().rev().copied().collect();
+            match component.key_type {
+                KeyType::Integer if !be.is_empty() => be[0] ^= 0x80,
+                KeyType::Float if !be.is_empty() => {
+                    if be[0] & 0x80 != 0 {
+                        be.iter_mut().for_each(|b| *b = !*b);
+                    } else {
+                        be[0] ^= 0x80;
+                    }
+                }
+                _ => {}
+            }
+            be
+        }
+    };
+    if component.descending {
+        bytes.iter_mut().for_each(|b| *b = !*b);
+    }
+    bytes
+}
+
+/// Majuscule d'un caractère Windows-1252 (ASCII et lettres accentuées latines)
+fn uppercase_cp1252(b: u8) -> u8 {
+    match b {
+        b'a'..=b'z' => b - 0x20,
+        0xE0..=0xFE if b != 0xF7 => b - 0x20,
+        _ => b,
+    }
+}
+
+/// Page de nœud décodée
+enum Page {
+    Leaf { next: u32, entries: Vec<NdxEntry> },
+    Internal { first_child: u32, separators: Vec<(Vec<u8>, u32)> },
+}
+
 /// Gestionnaire de fichier d'index .ndx
 pub struct NdxFile {
     /// Chemin du fichier
-    #[allow(dead_code)]
     path: std::path::PathBuf,
     /// Handle du fichier ouvert
-    #[allow(dead_code)]
-    file: Option<File>,
-    /// Liste de toutes les entrées de l'index (chargée en mémoire)
-    entries: Vec<NdxEntry>,
+    file: File,
+    /// Taille des pages
+    page_size: u32,
+    /// Nombre de pages du fichier (header compris)
+    page_count: u32,
+    /// Descripteurs des clés
+    keys: Vec<NdxKey>,
+    /// Pages modifiées ou ajoutées, pas encore écrites (numéro -> contenu)
+    dirty: BTreeMap<u32, Vec<u8>>,
 }
 
 impl NdxFile {
     /**
-     * Ouvre un fichier .ndx en lecture et charge toutes les entrées.
-     * 
+     * Ouvre un fichier .ndx en lecture et lit les descripteurs de clés.
+     *
+     * Les pages de nœuds ne sont lues qu'à la demande, lors des recherches.
+     *
      * @param path - Chemin vers le fichier .ndx
      * @returns Result<NdxFile> - Gestionnaire de fichier ou erreur
-     * 
+     *
      * Effets de bord :
      * - Ouvre le fichier en lecture
-     * - Lit toutes les entrées de l'index en mémoire
+     * - Lit le header (page 0)
      */
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
         let path = path.as_ref().to_path_buf();
         let mut file = File::open(&path)
             .with_context(|| format!("Impossible d'ouvrir le fichier: {:?}", path))?;
 
-        // Lecture de l'index (structure simplifiée)
-        let entries = Self::read_index(&mut file)?;
+        let (page_size, page_count, keys) = Self::read_header(&mut file)
+            .with_context(|| format!("Header .ndx invalide: {:?}", path))?;
 
         Ok(Self {
             path,
-            file: Some(file),
-            entries,
+            file,
+            page_size,
+            page_count,
+            keys,
+            dirty: BTreeMap::new(),
         })
     }
 
     /**
-     * Lit la structure d'index depuis le fichier.
-     * 
-     * Parse le header puis lit toutes les entrées de l'index.
-     * Cette implémentation suppose un format linéaire simplifié.
-     * 
-     * @param reader - Reader positionné au début du fichier
-     * @returns Result<Vec<NdxEntry>> - Liste des entrées de l'index
-     * 
-     * Effets de bord :
-     * - Lit toutes les entrées depuis le reader
+     * Indique si un fichier .ndx porte la signature de ce moteur.
+     *
+     * Un fichier sans la signature "NDX\0" (index écrit par WinDev,
+     * notamment) n'est ni lu ni modifié : il est laissé tel quel.
+     *
+     * @param path - Chemin vers le fichier .ndx
+     * @returns Result<bool> - true pour un index de ce moteur, erreur si le
+     *   fichier ne peut pas être lu
      */
-    fn read_index<R: Read + Seek>(reader: &mut R) -> Result<Vec<NdxEntry>> {
+    pub fn is_own_format<P: AsRef<Path>>(path: P) -> Result<bool> {
+        let path = path.as_ref();
+        let mut file = File::open(path)
+            .with_context(|| format!("Impossible d'ouvrir le fichier: {:?}", path))?;
+        let mut magic = [0u8; 4];
+        match file.read_exact(&mut magic) {
+            Ok(()) => Ok(&magic == NDX_MAGIC),
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /**
+     * Lit le header et les descripteurs de clés.
+     *
+     * @param reader - Reader positionné n'importe où dans le fichier
+     * @returns Result<(u32, u32, Vec<NdxKey>)> - Taille de page, nombre de pages, clés
+     */
+    fn read_header<R: Read + Seek>(reader: &mut R) -> Result<(u32, u32, Vec<NdxKey>)> {
         reader.seek(SeekFrom::Start(0))?;
 
-        // Header hypothétique:
-        // - 4 bytes: magic
-        // - 4 bytes: nombre d'entrées
-        // - 4 bytes: longueur de clé
-        let _magic = reader.read_u32::<LittleEndian>()?;
-        let entry_count = reader.read_u32::<LittleEndian>()?;
-        let key_length = reader.read_u32::<LittleEndian>()?;
-
-        let mut entries = Vec::new();
-        let mut offset = 12u64; // Header size
-
-        for _i in 0..entry_count {
-            let mut key = vec![0u8; key_length as usize];
-            reader.read_exact(&mut key)?;
-            
-            let record_id = reader.read_u32::<LittleEndian>()?;
-
-            entries.push(NdxEntry {
-                key,
-                record_id,
-                offset,
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != NDX_MAGIC {
+            anyhow::bail!("Magic .ndx inattendu: {:02X?}", magic);
+        }
+        let version = reader.read_u16::<LittleEndian>()?;
+        if version != NDX_VERSION {
+            anyhow::bail!("Version .ndx non supportée: {}", version);
+        }
+        let key_count = reader.read_u16::<LittleEndian>()? as usize;
+        let page_size = reader.read_u32::<LittleEndian>()?;
+        let page_count = reader.read_u32::<LittleEndian>()?;
+        if (page_size as usize) < NDX_HEADER_SIZE + key_count * KEY_DESCRIPTOR_SIZE {
+            anyhow::bail!("Taille de page trop petite pour {} clés: {}", key_count, page_size);
+        }
+
+        let mut keys = Vec::with_capacity(key_count);
+        for number in 0..key_count {
+            let root_page = reader.read_u32::<LittleEndian>()?;
+            let entry_count = reader.read_u32::<LittleEndian>()?;
+            let key_length = reader.read_u16::<LittleEndian>()? as u32;
+            let flags = reader.read_u16::<LittleEndian>()?;
+            let component_count = reader.read_u16::<LittleEndian>()? as usize;
+            let _reserved = reader.read_u16::<LittleEndian>()?;
+            if component_count == 0 || component_count > MAX_KEY_COMPONENTS {
+                anyhow::bail!("Clé {} : nombre de composantes invalide ({})", number, component_count);
+            }
+
+            let mut components = Vec::with_capacity(component_count);
+            for slot in 0..MAX_KEY_COMPONENTS {
+                let offset = reader.read_u32::<LittleEndian>()?;
+                let length = reader.read_u16::<LittleEndian>()? as u32;
+                let key_type = reader.read_u8()?;
+                let component_flags = reader.read_u8()?;
+                if slot < component_count {
+                    components.push(KeyComponent {
+                        offset,
+                        length,
+                        key_type: KeyType::from_code(key_type)?,
+                        case_insensitive: component_flags & 0x01 != 0,
+                        descending: component_flags & 0x02 != 0,
+                    });
+                }
+            }
+            if components.iter().map(|c| c.length).sum::<u32>() != key_length {
+                anyhow::bail!("Clé {} : longueur incohérente avec ses composantes", number);
+            }
+            if root_page >= page_count {
+                anyhow::bail!("Clé {} : page racine {} hors du fichier", number, root_page);
+            }
+
+            keys.push(NdxKey {
+                number,
+                root_page,
+                entry_count,
+                key_length,
+                unique: flags & 0x01 != 0,
+                components,
             });
+        }
+
+        Ok((page_size, page_count, keys))
+    }
+
+    /// Descripteurs des clés du fichier
+    pub fn keys(&self) -> &[NdxKey] {
+        &self.keys
+    }
+
+    /// Taille des pages du fichier
+    pub fn page_size(&self) -> u32 {
+        self.page_size
+    }
+
+    /// Chemin du fichier
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /**
+     * Retourne la clé portant uniquement sur une rubrique.
+     *
+     * @param offset - Offset de la rubrique (FieldInfo::offset)
+     * @param length - Longueur de la rubrique
+     * @returns Option<&NdxKey> - Première clé correspondante
+     */
+    pub fn key_for_field(&self, offset: u32, length: u32) -> Option<&NdxKey> {
+        self.keys.iter().find(|k| k.is_on_field(offset, length))
+    }
+
+    /**
+     * Lit et décode une page de nœud.
+     *
+     * @param page_no - Numéro de page (>= 1)
+     * @param key_length - Longueur des clés de l'index parcouru
+     *
+     * Effets de bord :
+     * - Lit une page depuis le fichier
+     */
+    fn read_page(&mut self, page_no: u32, key_length: usize) -> Result<Page> {
+        if page_no == 0 || page_no >= self.page_count {
+            anyhow::bail!("Page {} hors du fichier .ndx ({} pages)", page_no, self.page_count);
+        }
+        let page_offset = page_no as u64 * self.page_size as u64;
+        let buffer = match self.dirty.get(&page_no) {
+            Some(page) => page.clone(),
+            None => {
+                let mut buffer = vec![0u8; self.page_size as usize];
+                self.file.seek(SeekFrom::Start(page_offset))?;
+                self.file.read_exact(&mut buffer)
+                    .with_context(|| format!("Page {} tronquée", page_no))?;
+                buffer
+            }
+        };
+
+        let mut cursor = Cursor::new(&buffer);
+        let page_type = cursor.read_u8()?;
+        let _reserved = cursor.read_u8()?;
+        let count = cursor.read_u16::<LittleEndian>()? as usize;
+        let link = cursor.read_u32::<LittleEndian>()?;
+        if NODE_HEADER_SIZE + count * (key_length + 4) > buffer.len() {
+            anyhow::bail!("Page {} corrompue : {} entrées ne tiennent pas dans la page", page_no, count);
+        }
+
+        let mut items = Vec::with_capacity(count);
+        for i in 0..count {
+            let mut key = vec![0u8; key_length];
+            cursor.read_exact(&mut key)?;
+            let value = cursor.read_u32::<LittleEndian>()?;
+            let offset = page_offset + (NODE_HEADER_SIZE + i * (key_length + 4)) as u64;
+            items.push((key, value, offset));
+        }
+
+        match page_type {
+            PAGE_LEAF => Ok(Page::Leaf {
+                next: link,
+                entries: items
+                    .into_iter()
+                    .map(|(key, record_id, offset)| NdxEntry { key, record_id, offset })
+                    .collect(),
+            }),
+            PAGE_INTERNAL => Ok(Page::Internal {
+                first_child: link,
+                separators: items.into_iter().map(|(key, child, _)| (key, child)).collect(),
+            }),
+            other => anyhow::bail!("Page {} : type inconnu {}", page_no, other),
+        }
+    }
+
+    /// Descripteur d'une clé par numéro
+    fn key(&self, key_no: usize) -> Result<&NdxKey> {
+        self.keys.get(key_no)
+            .with_context(|| format!("Clé {} inexistante ({} clés)", key_no, self.keys.len()))
+    }
+
+    /**
+     * Recherche une entrée par sa clé exacte.
+     *
+     * @param key_no - Numéro de la clé (index) dans le fichier
+     * @param key - Clé encodée (NdxKey::encode)
+     * @returns Result<Option<NdxEntry>> - Première entrée trouvée ou None
+     *
+     * Effets de bord :
+     * - Lit O(log n) pages du fichier
+     */
+    pub fn find(&mut self, key_no: usize, key: &[u8]) -> Result<Option<NdxEntry>> {
+        let found = self.range(key_no, Bound::Included(key), Bound::Included(key), Some(1))?;
+        Ok(found.into_iter().next())
+    }
 
-            offset += (key_length + 4) as u64;
+    /**
+     * Recherche toutes les entrées d'une clé exacte (clés avec doublons).
+     *
+     * @param key_no - Numéro de la clé (index) dans le fichier
+     * @param key - Clé encodée (NdxKey::encode)
+     * @returns Result<Vec<NdxEntry>> - Entrées trouvées, dans l'ordre de l'index
+     */
+    pub fn find_all(&mut self, key_no: usize, key: &[u8]) -> Result<Vec<NdxEntry>> {
+        self.range(key_no, Bound::Included(key), Bound::Included(key), None)
+    }
+
+    /**
+     * Parcourt les entrées d'une clé comprises dans un intervalle.
+     *
+     * Descend de la racine jusqu'à la feuille contenant la borne basse, puis
+     * suit le chaînage des feuilles jusqu'à dépasser la borne haute.
+     *
+     * @param key_no - Numéro de la clé (index) dans le fichier
+     * @param lower - Borne basse (clé encodée)
+     * @param upper - Borne haute (clé encodée)
+     * @param limit - Nombre maximum d'entrées retournées
+     * @returns Result<Vec<NdxEntry>> - Entrées dans l'ordre de l'index
+     *
+     * Effets de bord :
+     * - Lit les pages du chemin de descente puis les feuilles parcourues
+     */
+    pub fn range(
+        &mut self,
+        key_no: usize,
+        lower: Bound<&[u8]>,
+        upper: Bound<&[u8]>,
+        limit: Option<usize>,
+    ) -> Result<Vec<NdxEntry>> {
+        let (root, key_length) = {
+            let key = self.key(key_no)?;
+            (key.root_page, key.key_length as usize)
+        };
+        let mut results = Vec::new();
+        if root == 0 {
+            return Ok(results);
         }
 
-        Ok(entries)
+        // Descente : fils le plus à gauche pouvant contenir la borne basse
+        let mut page_no = root;
+        let mut depth = 0;
+        let mut leaf = loop {
+            match self.read_page(page_no, key_length)? {
+                Page::Internal { first_child, separators } => {
+                    let index = match lower {
+                        Bound::Unbounded => 0,
+                        Bound::Included(k) | Bound::Excluded(k) => {
+                            separators.partition_point(|(sep, _)| sep.as_slice() < k)
+                        }
+                    };
+                    page_no = if index == 0 { first_child } else { separators[index - 1].1 };
+                    depth += 1;
+                    if depth > 64 {
+                        anyhow::bail!("Profondeur de B-tree anormale (cycle de pages ?)");
+                    }
+                }
+                leaf @ Page::Leaf { .. } => break leaf,
+            }
+        };
+
+        let mut visited = 0u32;
+        loop {
+            let Page::Leaf { next, entries } = leaf else {
+                anyhow::bail!("Chaînage de feuilles vers une page interne");
+            };
+            for entry in entries {
+                let key = entry.key.as_slice();
+                let above_lower = match lower {
+                    Bound::Unbounded => true,
+                    Bound::Included(k) => key >= k,
+                    Bound::Excluded(k) => key > k,
+                };
+                if !above_lower {
+                    continue;
+                }
+                let below_upper = match upper {
+                    Bound::Unbounded => true,
+                    Bound::Included(k) => key <= k,
+                    Bound::Excluded(k) => key < k,
+                };
+                if !below_upper || limit.is_some_and(|l| results.len() >= l) {
+                    return Ok(results);
+                }
+                results.push(entry);
+            }
+            visited += 1;
+            if next == 0 || visited > self.page_count {
+                return Ok(results);
+            }
+            leaf = self.read_page(next, key_length)?;
+        }
     }
 
     /**
-     * Recherche une entrée par sa clé.
-     * 
-     * Effectue une recherche linéaire dans l'index. Pour de meilleures
-     * performances, cette fonction devrait être optimisée avec une recherche
-     * binaire si les entrées sont triées.
-     * 
-     * @param key - Clé à rechercher
-     * @returns Option<&NdxEntry> - Entrée trouvée ou None
-     * 
-     * Effets de bord : Aucun
+     * Retourne toutes les entrées d'une clé, dans l'ordre de l'index.
+     *
+     * @param key_no - Numéro de la clé (index) dans le fichier
+     * @returns Result<Vec<NdxEntry>> - Toutes les entrées
      */
-    pub fn find(&self, key: &[u8]) -> Option<&NdxEntry> {
-        // Recherche linéaire (à optimiser avec binaire si trié)
-        self.entries.iter().find(|e| e.key == key)
+    pub fn entries(&mut self, key_no: usize) -> Result<Vec<NdxEntry>> {
+        self.range(key_no, Bound::Unbounded, Bound::Unbounded, None)
     }
 
     /**
-     * Retourne toutes les entrées de l'index.
-     * 
-     * @returns &[NdxEntry] - Référence vers toutes les entrées
+     * Ajoute une entrée à une clé (en mémoire, voir plan_writes).
+     *
+     * L'entrée est placée après les entrées de même clé. Une feuille pleine
+     * est coupée en deux et la moitié haute déplacée dans une nouvelle page
+     * en fin de fichier ; le séparateur remonte dans le nœud parent, qui
+     * peut à son tour être coupé (nouvelle racine si nécessaire).
+     *
+     * @param key_no - Numéro de la clé
+     * @param key - Clé encodée (NdxKey::encode)
+     * @param record_id - ID de l'enregistrement
+     * @returns Result<()> - Erreur si la clé est unique et déjà présente
+     *
+     * Effets de bord :
+     * - Modifie les pages en mémoire et le descripteur de la clé
      */
-    pub fn entries(&self) -> &[NdxEntry] {
-        &self.entries
+    pub fn plan_insert(&mut self, key_no: usize, key: &[u8], record_id: u32) -> Result<()> {
+        let (root, key_length, unique) = {
+            let descriptor = self.key(key_no)?;
+            (descriptor.root_page, descriptor.key_length as usize, descriptor.unique)
+        };
+        if key.len() != key_length {
+            anyhow::bail!("Clé de {} bytes pour l'index {} (attendu {})", key.len(), key_no, key_length);
+        }
+        if unique && self.find(key_no, key)?.is_some() {
+            anyhow::bail!("Valeur en double pour la clé unique {} de {:?}", key_no, self.path);
+        }
+        let capacity = (self.page_size as usize - NODE_HEADER_SIZE) / (key_length + 4);
+        let entry = (key.to_vec(), record_id);
+
+        if root == 0 {
+            let page_no = self.allocate_page();
+            self.set_page(page_no, PAGE_LEAF, 0, &[entry]);
+            self.keys[key_no].root_page = page_no;
+            self.keys[key_no].entry_count += 1;
+            return Ok(());
+        }
+
+        // Descente vers la feuille la plus à droite pouvant recevoir la clé
+        let mut path: Vec<(u32, usize)> = Vec::new();
+        let mut page_no = root;
+        let mut promoted = loop {
+            match self.read_page(page_no, key_length)? {
+                Page::Internal { first_child, separators } => {
+                    let index = separators.partition_point(|(sep, _)| sep.as_slice() <= key);
+                    path.push((page_no, index));
+                    page_no = if index == 0 { first_child } else { separators[index - 1].1 };
+                    if path.len() > 64 {
+                        anyhow::bail!("Profondeur de B-tree anormale (cycle de pages ?)");
+                    }
+                }
+                Page::Leaf { next, entries } => {
+                    let mut items: Vec<(Vec<u8>, u32)> = entries.into_iter().map(|e| (e.key, e.record_id)).collect();
+                    let position = items.partition_point(|(k, _)| k.as_slice() <= key);
+                    items.insert(position, entry);
+                    if items.len() <= capacity {
+                        self.set_page(page_no, PAGE_LEAF, next, &items);
+                        break None;
+                    }
+                    let right = items.split_off(items.len() / 2);
+                    let right_page = self.allocate_page();
+                    self.set_page(page_no, PAGE_LEAF, right_page, &items);
+                    self.set_page(right_page, PAGE_LEAF, next, &right);
+                    break Some((right[0].0.clone(), right_page));
+                }
+            }
+        };
+
+        // Remontée des séparateurs
+        while let Some((separator, child)) = promoted.take() {
+            match path.pop() {
+                Some((parent, index)) => {
+                    let Page::Internal { first_child, mut separators } = self.read_page(parent, key_length)? else {
+                        anyhow::bail!("Page {} : nœud interne attendu", parent);
+                    };
+                    separators.insert(index, (separator, child));
+                    if separators.len() <= capacity {
+                        self.set_page(parent, PAGE_INTERNAL, first_child, &separators);
+                    } else {
+                        let mut right = separators.split_off(separators.len() / 2);
+                        let (up, right_first) = right.remove(0);
+                        let right_page = self.allocate_page();
+                        self.set_page(parent, PAGE_INTERNAL, first_child, &separators);
+                        self.set_page(right_page, PAGE_INTERNAL, right_first, &right);
+                        promoted = Some((up, right_page));
+                    }
+                }
+                None => {
+                    let old_root = self.keys[key_no].root_page;
+                    let new_root = self.allocate_page();
+                    self.set_page(new_root, PAGE_INTERNAL, old_root, &[(separator, child)]);
+                    self.keys[key_no].root_page = new_root;
+                }
+            }
+        }
+        self.keys[key_no].entry_count += 1;
+        Ok(())
     }
 
     /**
-     * Recherche toutes les entrées associées à un record_id.
-     * 
-     * @param record_id - ID de l'enregistrement à rechercher
-     * @returns Vec<&NdxEntry> - Liste des entrées trouvées (peut y en avoir plusieurs)
-     * 
-     * Effets de bord : Aucun
+     * Retire l'entrée (clé, record_id) d'une clé (en mémoire, voir plan_writes).
+     *
+     * Les pages ne sont pas fusionnées : une feuille peut rester vide, elle
+     * est simplement sautée lors des parcours.
+     *
+     * @param key_no - Numéro de la clé
+     * @param key - Clé encodée de l'enregistrement
+     * @param record_id - ID de l'enregistrement
+     * @returns Result<bool> - true si l'entrée a été trouvée et retirée
+     *
+     * Effets de bord :
+     * - Modifie la feuille en mémoire et le descripteur de la clé
      */
-    pub fn find_by_record_id(&self, record_id: u32) -> Vec<&NdxEntry> {
-        self.entries.iter()
-            .filter(|e| e.record_id == record_id)
-            .collect()
+    pub fn plan_remove(&mut self, key_no: usize, key: &[u8], record_id: u32) -> Result<bool> {
+        let key_length = self.key(key_no)?.key_length as usize;
+        let Some(found) = self.find_all(key_no, key)?.into_iter().find(|e| e.record_id == record_id) else {
+            return Ok(false);
+        };
+        let page_no = (found.offset / self.page_size as u64) as u32;
+        let Page::Leaf { next, entries } = self.read_page(page_no, key_length)? else {
+            anyhow::bail!("Page {} : feuille attendue", page_no);
+        };
+        let items: Vec<(Vec<u8>, u32)> = entries
+            .into_iter()
+            .filter(|e| e.offset != found.offset)
+            .map(|e| (e.key, e.record_id))
+            .collect();
+        self.set_page(page_no, PAGE_LEAF, next, &items);
+        self.keys[key_no].entry_count -= 1;
+        Ok(true)
+    }
+
+    /**
+     * Retourne les écritures des pages modifiées et du header.
+     *
+     * @returns Vec<PendingWrite> - Écritures à appliquer (vide sans modification)
+     */
+    pub fn plan_writes(&self) -> Vec<PendingWrite> {
+        if self.dirty.is_empty() {
+            return Vec::new();
+        }
+        let mut writes = vec![PendingWrite {
+            offset: 0,
+            data: encode_header(self.page_size, self.page_count, &self.keys),
+        }];
+        writes.extend(self.dirty.iter().map(|(page_no, page)| PendingWrite {
+            offset: *page_no as u64 * self.page_size as u64,
+            data: page.clone(),
+        }));
+        writes
+    }
+
+    /// Réserve une nouvelle page en fin de fichier
+    fn allocate_page(&mut self) -> u32 {
+        let page_no = self.page_count;
+        self.page_count += 1;
+        page_no
+    }
+
+    /// Remplace le contenu d'une page (en mémoire)
+    fn set_page(&mut self, page_no: u32, page_type: u8, link: u32, items: &[(Vec<u8>, u32)]) {
+        let page = encode_page(self.page_size as usize, page_type, link, items);
+        self.dirty.insert(page_no, page);
+    }
+
+    /**
+     * Construit un fichier .ndx complet (chargement en masse).
+     *
+     * Les entrées de chaque clé sont triées puis réparties en feuilles
+     * pleines chaînées, surmontées des niveaux internes nécessaires.
+     *
+     * @param path - Fichier à créer (remplacé s'il existe)
+     * @param page_size - Taille des pages
+     * @param keys - Descripteurs et entrées (clé encodée, record_id) de chaque clé
+     * @returns Result<()> - Succès ou erreur
+     *
+     * Effets de bord :
+     * - Crée ou remplace le fichier
+     */
+    pub fn create<P: AsRef<Path>>(path: P, page_size: u32, keys: Vec<(NdxKey, KeyEntries)>) -> Result<()> {
+        let bytes = Self::build(page_size, keys)?;
+        let path = path.as_ref();
+        let mut file = File::create(path)
+            .with_context(|| format!("Impossible de créer le fichier: {:?}", path))?;
+        file.write_all(&bytes)?;
+        file.sync_all()?;
+        Ok(())
+    }
+
+    /**
+     * Construit le contenu d'un fichier .ndx en mémoire (voir create).
+     */
+    pub fn build(page_size: u32, keys: Vec<(NdxKey, KeyEntries)>) -> Result<Vec<u8>> {
+        let page = page_size as usize;
+        if page < NDX_HEADER_SIZE + keys.len() * KEY_DESCRIPTOR_SIZE {
+            anyhow::bail!("Taille de page trop petite pour {} clés: {}", keys.len(), page_size);
+        }
+        let mut pages: Vec<Vec<u8>> = vec![vec![0u8; page]];
+        let mut descriptors = Vec::new();
+
+        for (number, (mut key, mut entries)) in keys.into_iter().enumerate() {
+            let key_length = key.key_length as usize;
+            let per_page = (page - NODE_HEADER_SIZE) / (key_length + 4);
+            if per_page < 2 {
+                anyhow::bail!("Clé {} trop longue pour des pages de {} bytes", number, page_size);
+            }
+            if let Some((bad, _)) = entries.iter().find(|(k, _)| k.len() != key_length) {
+                anyhow::bail!("Clé {} : entrée de {} bytes (attendu {})", number, bad.len(), key_length);
+            }
+            entries.sort();
+            if key.unique && entries.windows(2).any(|w| w[0].0 == w[1].0) {
+                anyhow::bail!("Clé {} unique : doublon", number);
+            }
+
+            key.number = number;
+            key.entry_count = entries.len() as u32;
+            key.root_page = if entries.is_empty() {
+                0
+            } else {
+                // Feuilles chaînées, puis niveaux internes jusqu'à une racine unique
+                let mut level: Vec<(Vec<u8>, u32)> = Vec::new();
+                let chunks: Vec<&[(Vec<u8>, u32)]> = entries.chunks(per_page).collect();
+                let first_leaf = pages.len() as u32;
+                for (i, chunk) in chunks.iter().enumerate() {
+                    let next = if i + 1 < chunks.len() { first_leaf + i as u32 + 1 } else { 0 };
+                    level.push((chunk[0].0.clone(), pages.len() as u32));
+                    pages.push(encode_page(page, PAGE_LEAF, next, chunk));
+                }
+                while level.len() > 1 {
+                    let mut parents = Vec::new();
+                    for chunk in level.chunks(per_page + 1) {
+                        parents.push((chunk[0].0.clone(), pages.len() as u32));
+                        pages.push(encode_page(page, PAGE_INTERNAL, chunk[0].1, &chunk[1..]));
+                    }
+                    level = parents;
+                }
+                level[0].1
+            };
+            descriptors.push(key);
+        }
+
+        for key in &descriptors {
+            if key.components.is_empty() || key.components.len() > MAX_KEY_COMPONENTS {
+                anyhow::bail!("Clé {} : de 1 à {} composantes attendues", key.number, MAX_KEY_COMPONENTS);
+            }
+        }
+        let page_count = pages.len() as u32;
+        pages[0] = encode_header(page_size, page_count, &descriptors);
+
+        Ok(pages.concat())
     }
 }
 
+/// Encode le header (page 0) : champs fixes et descripteurs de clés
+fn encode_header(page_size: u32, page_count: u32, keys: &[NdxKey]) -> Vec<u8> {
+    let mut header = Vec::with_capacity(page_size as usize);
+    header.extend_from_slice(NDX_MAGIC);
+    header.extend_from_slice(&NDX_VERSION.to_le_bytes());
+    header.extend_from_slice(&(keys.len() as u16).to_le_bytes());
+    header.extend_from_slice(&page_size.to_le_bytes());
+    header.extend_from_slice(&page_count.to_le_bytes());
+    for key in keys {
+        header.extend_from_slice(&key.root_page.to_le_bytes());
+        header.extend_from_slice(&key.entry_count.to_le_bytes());
+        header.extend_from_slice(&(key.key_length as u16).to_le_bytes());
+        header.extend_from_slice(&(key.unique as u16).to_le_bytes());
+        header.extend_from_slice(&(key.components.len() as u16).to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes());
+        for slot in 0..MAX_KEY_COMPONENTS {
+            match key.components.get(slot) {
+                Some(c) => {
+                    header.extend_from_slice(&c.offset.to_le_bytes());
+                    header.extend_from_slice(&(c.length as u16).to_le_bytes());
+                    header.push(c.key_type.code());
+                    header.push(c.case_insensitive as u8 | (c.descending as u8) << 1);
+                }
+                None => header.extend_from_slice(&[0u8; 8]),
+            }
+        }
+    }
+    header.resize(page_size as usize, 0);
+    header
+}
+
+/// Encode une page de nœud (type, lien, entrées (clé, valeur))
+fn encode_page(page_size: usize, page_type: u8, link: u32, items: &[(Vec<u8>, u32)]) -> Vec<u8> {
+    let mut page = Vec::with_capacity(page_size);
+    page.push(page_type);
+    page.push(0);
+    page.extend_from_slice(&(items.len() as u16).to_le_bytes());
+    page.extend_from_slice(&link.to_le_bytes());
+    for (key, value) in items {
+        page.extend_from_slice(key);
+        page.extend_from_slice(&value.to_le_bytes());
+    }
+    page.resize(page_size, 0);
+    page
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn component(offset: u32, length: u32, key_type: KeyType) -> KeyComponent {
+        KeyComponent { offset, length, key_type, case_insensitive: false, descending: false }
+    }
+
+    /// Index à deux clés : entier signé (avec doublons) et chaîne insensible à la casse
+    fn create_test_ndx() -> Result<NamedTempFile> {
+        let file = NamedTempFile::new()?;
+        let by_number = NdxKey::new(vec![component(1, 4, KeyType::Integer)], false);
+        let mut by_name = NdxKey::new(vec![component(5, 8, KeyType::String)], true);
+        by_name.components[0].case_insensitive = true;
+
+        let mut numbers = Vec::new();
+        let mut names = Vec::new();
+        for id in 0..1000u32 {
+            let mut record = vec![0u8; 13];
+            let value = id as i32 / 2 - 200;
+            record[1..5].copy_from_slice(&value.to_le_bytes());
+            record[5..13].copy_from_slice(format!("n{:07}", id).as_bytes());
+            numbers.push((by_number.encode(&record)?, id));
+            names.push((by_name.encode(&record)?, id));
+        }
+        // Petites pages : plusieurs niveaux internes
+        NdxFile::create(file.path(), 128, vec![(by_number, numbers), (by_name, names)])?;
+        Ok(file)
+    }
+
+    fn int_key(value: i32) -> Vec<u8> {
+        let mut record = vec![0u8; 5];
+        record[1..5].copy_from_slice(&value.to_le_bytes());
+        NdxKey::new(vec![component(1, 4, KeyType::Integer)], false).encode(&record).unwrap()
+    }
+
+    #[test]
+    fn test_read_key_descriptors() -> Result<()> {
+        let file = create_test_ndx()?;
+        let ndx = NdxFile::open(file.path())?;
+        assert_eq!(ndx.keys().len(), 2);
+        assert_eq!(ndx.keys()[0].entry_count, 1000);
+        assert!(!ndx.keys()[0].unique);
+        assert!(ndx.keys()[1].unique);
+        assert!(ndx.keys()[1].components[0].case_insensitive);
+        assert_eq!(ndx.key_for_field(5, 8).map(|k| k.number), Some(1));
+        assert!(ndx.key_for_field(5, 4).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_and_range() -> Result<()> {
+        let file = create_test_ndx()?;
+        let mut ndx = NdxFile::open(file.path())?;
+
+        // Doublons : la valeur -200 correspond aux enregistrements 0 et 1
+        let ids: Vec<u32> = ndx.find_all(0, &int_key(-200))?.iter().map(|e| e.record_id).collect();
+        assert_eq!(ids, vec![0, 1]);
+        assert_eq!(ndx.find(0, &int_key(299))?.map(|e| e.record_id), Some(998));
+        assert!(ndx.find(0, &int_key(300))?.is_none());
+
+        // Intervalle traversant plusieurs feuilles, ordre signé respecté
+        let range = ndx.range(0, Bound::Excluded(&int_key(-2)), Bound::Included(&int_key(2)), None)?;
+        let ids: Vec<u32> = range.iter().map(|e| e.record_id).collect();
+        assert_eq!(ids, vec![398, 399, 400, 401, 402, 403, 404, 405]);
+
+        // Clé chaîne insensible à la casse
+        let mut record = vec![0u8; 13];
+        record[5..13].copy_from_slice(b"N0000042");
+        let key = ndx.keys()[1].encode(&record)?;
+        assert_eq!(ndx.find(1, &key)?.map(|e| e.record_id), Some(42));
+
+        let all = ndx.entries(1)?;
+        assert_eq!(all.len(), 1000);
+        assert!(all.windows(2).all(|w| w[0].key < w[1].key));
+        Ok(())
+    }
+
+    #[test]
+    fn test_component_encoding_order() {
+        let float = component(0, 8, KeyType::Float);
+        let values = [-10.5f64, -0.25, 0.0, 0.5, 3.0, 1e9];
+        let keys: Vec<Vec<u8>> = values.iter().map(|v| encode_component(&float, &v.to_le_bytes())).collect();
+        assert!(keys.windows(2).all(|w| w[0] < w[1]));
+
+        let mut descending = component(0, 2, KeyType::Unsigned);
+        descending.descending = true;
+        assert!(encode_component(&descending, &5u16.to_le_bytes()) > encode_component(&descending, &300u16.to_le_bytes()));
+    }
+
+    /// Applique des écritures préparées au fichier
+    fn apply(path: &Path, writes: Vec<PendingWrite>) -> Result<()> {
+        let mut file = std::fs::OpenOptions::new().write(true).open(path)?;
+        for write in writes {
+            file.seek(SeekFrom::Start(write.offset))?;
+            file.write_all(&write.data)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_insert_and_remove_entries() -> Result<()> {
+        let file = NamedTempFile::new()?;
+        let by_number = NdxKey::new(vec![component(1, 4, KeyType::Integer)], false);
+        let unique = NdxKey::new(vec![component(1, 4, KeyType::Integer)], true);
+        NdxFile::create(file.path(), 128, vec![(by_number, Vec::new()), (unique, vec![(int_key(5), 0)])])?;
+
+        // Insertions dans le désordre : feuilles et nœuds internes coupés
+        let mut ndx = NdxFile::open(file.path())?;
+        for id in 0..300u32 {
+            ndx.plan_insert(0, &int_key(((id * 37) % 100) as i32 - 50), id)?;
+        }
+        assert!(ndx.plan_insert(1, &int_key(5), 1).is_err());
+        ndx.plan_insert(1, &int_key(6), 1)?;
+        assert!(ndx.plan_remove(0, &int_key(-50), 0)?);
+        assert!(!ndx.plan_remove(0, &int_key(-50), 0)?);
+        apply(file.path(), ndx.plan_writes())?;
+
+        let mut ndx = NdxFile::open(file.path())?;
+        assert_eq!(ndx.keys()[0].entry_count, 299);
+        let all = ndx.entries(0)?;
+        assert_eq!(all.len(), 299);
+        assert!(all.windows(2).all(|w| w[0].key <= w[1].key));
+        let ids: Vec<u32> = ndx.find_all(0, &int_key(-50))?.iter().map(|e| e.record_id).collect();
+        assert_eq!(ids, vec![100, 200]);
+        assert_eq!(ndx.find(1, &int_key(6))?.map(|e| e.record_id), Some(1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_files_are_rejected() -> Result<()> {
+        let mut file = NamedTempFile::new()?;
+        file.write_all(&[0u8; 64])?;
+        assert!(NdxFile::open(file.path()).is_err());
+
+        let bytes = NdxFile::build(256, vec![(NdxKey::new(vec![component(1, 4, KeyType::Integer)], true), vec![(int_key(1), 0), (int_key(2), 1)])])?;
+        let mut truncated = NamedTempFile::new()?;
+        truncated.write_all(&bytes[..300])?;
+        let mut ndx = NdxFile::open(truncated.path())?;
+        assert!(ndx.find(0, &int_key(1)).is_err());
+
+        assert!(NdxFile::build(256, vec![(NdxKey::new(vec![component(1, 4, KeyType::Integer)], true), vec![(int_key(1), 0), (int_key(1), 1)])]).is_err());
+        Ok(())
+    }
+}
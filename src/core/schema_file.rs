@@ -0,0 +1,218 @@
+/**
+ * Fichiers de schéma fournis par l'utilisateur (`<table>.schema.toml` ou
+ * `<table>.schema.json`).
+ *
+ * Quand la description des rubriques est absente ou fausse, un fichier placé
+ * à côté du .fic (ou dans le dossier `storage.schema_dir` de la
+ * configuration) décrit les rubriques de la table. Il remplace alors le
+ * schéma détecté par FicFile::analyze_schema.
+ *
+ * Format (TOML ; le JSON a la même structure) :
+ *
+ * ```toml
+ * [[fields]]
+ * name = "CODE"
+ * offset = 1          # byte de flags inclus
+ * length = 6
+ * field_type = "String"
+ * encoding = "utf-8"  # optionnel, Windows-1252 par défaut
+ *
+ * [[fields]]
+ * name = "MONTANT"
+ * offset = 7
+ * length = 8
+ * field_type = "Numeric"
+ * scale = 2           # optionnel : décimales d'un Numeric / Currency
+ * ```
+ *
+ * Liens avec d'autres modules :
+ * - Utilisé par src/storage/engine.rs (get_schema, save_schema_override)
+ * - Les schémas sont édités par l'inspecteur via src/api/handlers.rs
+ */
+
+use crate::core::{FieldInfo, TextEncoding};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Extensions reconnues, par ordre de priorité
+pub const SCHEMA_EXTENSIONS: [&str; 2] = ["schema.toml", "schema.json"];
+
+/// Contenu d'un fichier de schéma
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaFile {
+    /// Rubriques de la table
+    pub fields: Vec<FieldInfo>,
+}
+
+/**
+ * Cherche le fichier de schéma d'une table.
+ *
+ * Le dossier configuré est consulté avant celui du .fic ; dans chaque
+ * dossier, le nom est comparé sans tenir compte de la casse
+ * (`CLIENT.FIC` -> `client.schema.toml`).
+ *
+ * @param table - Nom de la table
+ * @param fic_path - Chemin du fichier .fic
+ * @param schema_dir - Dossier de schémas configuré (optionnel)
+ * @returns Option<PathBuf> - Chemin du fichier trouvé
+ *
+ * Effets de bord :
+ * - Lit le contenu des dossiers
+ */
+pub fn find_schema_file(table: &str, fic_path: &Path, schema_dir: Option<&Path>) -> Option<PathBuf> {
+    // Chemin relatif sans dossier (`CLIENT.FIC`) : dossier courant
+    let fic_dir = fic_path.parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    schema_dir
+        .into_iter()
+        .chain(std::iter::once(fic_dir))
+        .find_map(|dir| {
+            SCHEMA_EXTENSIONS.iter().find_map(|ext| {
+                let wanted = format!("{}.{}", table, ext).to_lowercase();
+                std::fs::read_dir(dir).ok()?
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.path())
+                    .find(|path| {
+                        path.is_file()
+                            && path.file_name().map(|n| n.to_string_lossy().to_lowercase()) == Some(wanted.clone())
+                    })
+            })
+        })
+}
+
+/**
+ * Lit un fichier de schéma (TOML ou JSON selon l'extension).
+ *
+ * @param path - Chemin du fichier
+ * @returns Result<Vec<FieldInfo>> - Rubriques décrites
+ *
+ * Effets de bord :
+ * - Lit le fichier
+ */
+pub fn read_schema_file(path: &Path) -> Result<Vec<FieldInfo>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Impossible de lire le schéma: {:?}", path))?;
+    let file: SchemaFile = if is_json(path) {
+        serde_json::from_str(&content)
+            .with_context(|| format!("Schéma JSON invalide: {:?}", path))?
+    } else {
+        toml::from_str(&content)
+            .with_context(|| format!("Schéma TOML invalide: {:?}", path))?
+    };
+    Ok(file.fields)
+}
+
+/**
+ * Écrit un fichier de schéma (TOML ou JSON selon l'extension).
+ *
+ * Le contenu est écrit dans un fichier temporaire puis renommé, pour ne
+ * jamais laisser un schéma à moitié écrit.
+ *
+ * @param path - Chemin du fichier
+ * @param fields - Rubriques à enregistrer
+ * @returns Result<()> - Succès ou erreur d'écriture
+ *
+ * Effets de bord :
+ * - Crée ou remplace le fichier
+ */
+pub fn write_schema_file(path: &Path, fields: &[FieldInfo]) -> Result<()> {
+    let file = SchemaFile { fields: fields.to_vec() };
+    let content = if is_json(path) {
+        serde_json::to_string_pretty(&file)?
+    } else {
+        toml::to_string_pretty(&file)?
+    };
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, content)
+        .with_context(|| format!("Impossible d'écrire le schéma: {:?}", tmp_path))?;
+    std::fs::rename(&tmp_path, path)
+        .with_context(|| format!("Impossible de remplacer le schéma: {:?}", path))?;
+    Ok(())
+}
+
+/**
+ * Vérifie qu'un schéma est utilisable pour des enregistrements donnés.
+ *
+ * @param fields - Rubriques à vérifier
+ * @param record_length - Longueur d'un enregistrement (byte de flags inclus)
+ * @returns Result<()> - Erreur décrivant la première rubrique invalide
+ */
+pub fn validate_fields(fields: &[FieldInfo], record_length: u32) -> Result<()> {
+    if fields.is_empty() {
+        anyhow::bail!("Le schéma ne contient aucune rubrique");
+    }
+    for (i, field) in fields.iter().enumerate() {
+        if field.name.trim().is_empty() {
+            anyhow::bail!("Rubrique {} sans nom", i + 1);
+        }
+        if fields[..i].iter().any(|f| f.name.eq_ignore_ascii_case(&field.name)) {
+            anyhow::bail!("Rubrique {} en double", field.name);
+        }
+        // Le byte 0 est le byte de flags de l'enregistrement
+        if field.offset == 0 || field.length == 0 || field.offset as u64 + field.length as u64 > record_length as u64 {
+            anyhow::bail!(
+                "Rubrique {} hors de l'enregistrement (offset {}, taille {}, enregistrement {})",
+                field.name, field.offset, field.length, record_length
+            );
+        }
+        if field.scale.is_some_and(|scale| scale > 18) {
+            anyhow::bail!("Nombre de décimales trop grand pour la rubrique {} (maximum 18)", field.name);
+        }
+        if let Some(label) = &field.encoding {
+            if TextEncoding::for_label(label).is_none() {
+                anyhow::bail!("Encodage non supporté pour la rubrique {}: {}", field.name, label);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().map(|e| e.eq_ignore_ascii_case("json")).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::FieldType;
+
+    #[test]
+    fn test_schema_file_round_trip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let fic_path = dir.path().join("CLIENT.FIC");
+        assert!(find_schema_file("CLIENT", &fic_path, None).is_none());
+
+        std::fs::write(dir.path().join("client.schema.toml"), r#"
+[[fields]]
+name = "CODE"
+offset = 1
+length = 6
+field_type = "String"
+encoding = "utf-8"
+
+[[fields]]
+name = "AGE"
+offset = 7
+length = 4
+field_type = "Integer"
+"#)?;
+        let path = find_schema_file("CLIENT", &fic_path, None).expect("schéma à côté du .fic");
+        let fields = read_schema_file(&path)?;
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].encoding.as_deref(), Some("utf-8"));
+        assert!(matches!(fields[1].field_type, FieldType::Integer));
+        validate_fields(&fields, 11)?;
+        assert!(validate_fields(&fields, 10).is_err());
+
+        // Le dossier configuré est prioritaire
+        let schema_dir = dir.path().join("schemas");
+        std::fs::create_dir(&schema_dir)?;
+        let json_path = schema_dir.join("CLIENT.schema.json");
+        write_schema_file(&json_path, &fields[..1])?;
+        assert_eq!(find_schema_file("CLIENT", &fic_path, Some(&schema_dir)), Some(json_path.clone()));
+        assert_eq!(read_schema_file(&json_path)?.len(), 1);
+        Ok(())
+    }
+}
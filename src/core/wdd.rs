@@ -0,0 +1,356 @@
+/**
+ * Lecture des fichiers d'analyse (.wdd).
+ *
+ * Une analyse décrit chaque fichier de données (rubriques, clés) et les
+ * liaisons entre fichiers. Ce fichier la décode en TableSchema/FieldInfo et
+ * en relations, pour servir de source de schéma prioritaire sur la
+ * détection dans les .fic.
+ *
+ * L'organisation "WDD\0" ci-dessous est propre à ce moteur : elle ne
+ * reproduit pas les analyses .wdd écrites par WinDev, qui ne sont pas
+ * décodées. Un tel fichier n'a pas la signature attendue et est ignoré
+ * (avec un avertissement) lors du chargement.
+ *
+ * Structure (little-endian) :
+ * - 0x00 : signature "WDD\0"
+ * - 0x04 : version (u16)
+ * - 0x06 : nombre de fichiers (u16)
+ * - 0x08 : nombre de liaisons (u16)
+ * - 0x0A : réservé (u16)
+ * - Puis, pour chaque fichier :
+ *   - nom logique et nom physique (u8 + Windows-1252, ex. "Client", "CLIENT.FIC")
+ *   - longueur d'un enregistrement (u32, byte de flags inclus)
+ *   - nombre de rubriques (u16), nombre de clés (u16)
+ *   - rubriques, au format du bloc de description des .fic (src/core/descriptor.rs)
+ *   - clés : numéro de rubrique (u16), type (u8 : 0 avec doublons, 1 unique,
+ *     2 clé primaire)
+ * - Puis, pour chaque liaison (clé étrangère -> clé référencée) :
+ *   - fichier source (u16), rubrique source (u16)
+ *   - fichier cible (u16), rubrique cible (u16)
+ *
+ * Les fichiers et rubriques sont désignés par leur position dans l'analyse.
+ *
+ * Liens avec d'autres modules :
+ * - Chargée par src/storage/engine.rs lors du scan (schémas et relations)
+ * - Exposée par l'API (GET /analysis)
+ */
+
+use crate::core::descriptor::read_item;
+use crate::core::schema_file::validate_fields;
+use crate::core::{FieldInfo, TableSchema};
+use anyhow::{Context, Result};
+use byteorder::{LittleEndian, ReadBytesExt};
+use encoding_rs::WINDOWS_1252;
+use serde::Serialize;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+
+/// Signature d'un fichier d'analyse
+pub const WDD_SIGNATURE: &[u8; 4] = b"WDD\0";
+
+/// Type d'une clé de l'analyse
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum KeyKind {
+    /// Clé avec doublons
+    Duplicates,
+    /// Clé unique
+    Unique,
+    /// Clé primaire
+    Primary,
+}
+
+/// Clé d'un fichier de l'analyse
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalysisKey {
+    /// Rubrique indexée
+    pub item: String,
+    /// Type de clé
+    pub kind: KeyKind,
+}
+
+/// Fichier de données décrit par l'analyse
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalysisFile {
+    /// Nom logique (ex. "Client")
+    pub name: String,
+    /// Nom du fichier physique (ex. "CLIENT.FIC")
+    pub physical_name: String,
+    /// Schéma de la table (nommé d'après le fichier physique)
+    pub schema: TableSchema,
+    /// Clés du fichier
+    pub keys: Vec<AnalysisKey>,
+}
+
+/// Liaison entre deux fichiers (clé étrangère)
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalysisLink {
+    pub from_table: String,
+    pub from_column: String,
+    pub to_table: String,
+    pub to_column: String,
+}
+
+/// Analyse (.wdd) décodée
+#[derive(Debug, Clone, Serialize)]
+pub struct Analysis {
+    /// Chemin du fichier .wdd
+    pub path: PathBuf,
+    /// Fichiers de données
+    pub files: Vec<AnalysisFile>,
+    /// Liaisons entre fichiers
+    pub links: Vec<AnalysisLink>,
+}
+
+impl Analysis {
+    /**
+     * Ouvre et décode un fichier d'analyse.
+     *
+     * @param path - Chemin du fichier .wdd
+     * @returns Result<Analysis> - Analyse décodée ou erreur de format
+     *
+     * Effets de bord :
+     * - Lit le fichier
+     */
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Impossible d'ouvrir l'analyse: {:?}", path))?;
+        Self::parse(path, &bytes)
+            .with_context(|| format!("Analyse invalide: {:?}", path))
+    }
+
+    /**
+     * Décode le contenu d'un fichier d'analyse.
+     *
+     * @param path - Chemin du fichier (reporté dans l'analyse)
+     * @param bytes - Contenu du fichier
+     * @returns Result<Analysis> - Analyse décodée ou erreur de format
+     */
+    pub fn parse(path: &Path, bytes: &[u8]) -> Result<Self> {
+        let mut cursor = Cursor::new(bytes);
+        let mut signature = [0u8; 4];
+        cursor.read_exact(&mut signature).context("Fichier trop court")?;
+        if &signature != WDD_SIGNATURE {
+            anyhow::bail!("Signature d'analyse invalide: {:?}", signature);
+        }
+        let _version = cursor.read_u16::<LittleEndian>()?;
+        let file_count = cursor.read_u16::<LittleEndian>()?;
+        let link_count = cursor.read_u16::<LittleEndian>()?;
+        let _reserved = cursor.read_u16::<LittleEndian>()?;
+
+        let files = (0..file_count)
+            .map(|index| read_file(&mut cursor).with_context(|| format!("Fichier {} de l'analyse", index)))
+            .collect::<Result<Vec<AnalysisFile>>>()?;
+
+        let mut links = Vec::with_capacity(link_count as usize);
+        for index in 0..link_count {
+            let mut ends = [0u16; 4];
+            for end in ends.iter_mut() {
+                *end = cursor.read_u16::<LittleEndian>()
+                    .with_context(|| format!("Liaison {} tronquée", index))?;
+            }
+            let item = |file: u16, item: u16| -> Result<(&AnalysisFile, &FieldInfo)> {
+                let file = files.get(file as usize)
+                    .with_context(|| format!("Liaison {}: fichier {} inconnu", index, file))?;
+                let field = file.schema.fields.get(item as usize)
+                    .with_context(|| format!("Liaison {}: rubrique {} inconnue dans {}", index, item, file.name))?;
+                Ok((file, field))
+            };
+            let (from_file, from_field) = item(ends[0], ends[1])?;
+            let (to_file, to_field) = item(ends[2], ends[3])?;
+            links.push(AnalysisLink {
+                from_table: from_file.schema.name.clone(),
+                from_column: from_field.name.clone(),
+                to_table: to_file.schema.name.clone(),
+                to_column: to_field.name.clone(),
+            });
+        }
+
+        Ok(Self { path: path.to_path_buf(), files, links })
+    }
+
+    /**
+     * Retourne le fichier de l'analyse correspondant à une table.
+     *
+     * La table est comparée au nom du fichier physique sans extension, puis
+     * au nom logique (sans tenir compte de la casse).
+     *
+     * @param table - Nom de la table (nom du .fic sans extension)
+     * @returns Option<&AnalysisFile> - Fichier décrit, None si absent de l'analyse
+     */
+    pub fn file(&self, table: &str) -> Option<&AnalysisFile> {
+        self.files.iter()
+            .find(|f| f.schema.name.eq_ignore_ascii_case(table))
+            .or_else(|| self.files.iter().find(|f| f.name.eq_ignore_ascii_case(table)))
+    }
+}
+
+/**
+ * Cherche un fichier d'analyse dans un dossier de projet.
+ *
+ * Le dossier lui-même puis ses sous-dossiers `*.ana` (emplacement des
+ * analyses dans un projet WinDev) sont parcourus ; le premier .wdd trouvé
+ * (ordre alphabétique) est retenu.
+ *
+ * @param dir - Dossier du projet ou des données
+ * @returns Option<PathBuf> - Chemin du fichier .wdd
+ *
+ * Effets de bord :
+ * - Lit le contenu des dossiers
+ */
+pub fn find_analysis(dir: &Path) -> Option<PathBuf> {
+    let has_extension = |path: &Path, ext: &str| {
+        path.extension().map(|e| e.eq_ignore_ascii_case(ext)).unwrap_or(false)
+    };
+    let sorted_entries = |dir: &Path| -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+            .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+            .unwrap_or_default();
+        paths.sort();
+        paths
+    };
+
+    let entries = sorted_entries(dir);
+    entries.iter()
+        .find(|p| p.is_file() && has_extension(p, "wdd"))
+        .cloned()
+        .or_else(|| {
+            entries.iter()
+                .filter(|p| p.is_dir() && has_extension(p, "ana"))
+                .flat_map(|ana| sorted_entries(ana))
+                .find(|p| p.is_file() && has_extension(p, "wdd"))
+        })
+}
+
+/// Lit un nom (u8 + Windows-1252)
+fn read_name<R: Read>(reader: &mut R) -> Result<String> {
+    let length = reader.read_u8()?;
+    let mut bytes = vec![0u8; length as usize];
+    reader.read_exact(&mut bytes).context("Nom tronqué")?;
+    let (name, _, _) = WINDOWS_1252.decode(&bytes);
+    Ok(name.trim().to_string())
+}
+
+/// Lit la description d'un fichier de données
+fn read_file<R: Read>(reader: &mut R) -> Result<AnalysisFile> {
+    let name = read_name(reader)?;
+    let physical_name = read_name(reader)?;
+    if physical_name.is_empty() {
+        anyhow::bail!("Fichier {} sans nom physique", name);
+    }
+    let record_length = reader.read_u32::<LittleEndian>()?;
+    let item_count = reader.read_u16::<LittleEndian>()?;
+    let key_count = reader.read_u16::<LittleEndian>()?;
+
+    let fields = (0..item_count)
+        .map(|index| read_item(reader, index))
+        .collect::<Result<Vec<FieldInfo>>>()?;
+    validate_fields(&fields, record_length)
+        .with_context(|| format!("Fichier {}", name))?;
+
+    let mut keys = Vec::with_capacity(key_count as usize);
+    for _ in 0..key_count {
+        let item = reader.read_u16::<LittleEndian>()?;
+        let kind = match reader.read_u8()? {
+            0 => KeyKind::Duplicates,
+            1 => KeyKind::Unique,
+            2 => KeyKind::Primary,
+            other => anyhow::bail!("Type de clé inconnu dans {}: {}", name, other),
+        };
+        let field = fields.get(item as usize)
+            .with_context(|| format!("Clé sur une rubrique inconnue dans {}: {}", name, item))?;
+        keys.push(AnalysisKey { item: field.name.clone(), kind });
+    }
+
+    // La table porte le nom du fichier physique, comme lors du scan des .fic
+    let table = Path::new(&physical_name)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| physical_name.clone());
+
+    Ok(AnalysisFile {
+        name,
+        physical_name,
+        schema: TableSchema {
+            name: table,
+            record_length,
+            field_count: fields.len() as u32,
+            fields,
+        },
+        keys,
+    })
+}
+
+/// Fichier d'une analyse de test : nom logique, nom physique, longueur,
+/// rubriques (nom, code de type, offset, taille) et clés (rubrique, type)
+#[cfg(test)]
+pub(crate) type TestFile<'a> = (&'a str, &'a str, u32, &'a [(&'a str, u8, u32, u32)], &'a [(u16, u8)]);
+
+/// Encode une analyse (fichiers de test) : fichiers et liaisons
+#[cfg(test)]
+pub(crate) fn encode_analysis(
+    files: &[TestFile],
+    links: &[[u16; 4]],
+) -> Vec<u8> {
+    let mut out = WDD_SIGNATURE.to_vec();
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&(files.len() as u16).to_le_bytes());
+    out.extend_from_slice(&(links.len() as u16).to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    for (name, physical_name, record_length, items, keys) in files {
+        for text in [name, physical_name] {
+            out.push(text.len() as u8);
+            out.extend_from_slice(text.as_bytes());
+        }
+        out.extend_from_slice(&record_length.to_le_bytes());
+        out.extend_from_slice(&(items.len() as u16).to_le_bytes());
+        out.extend_from_slice(&(keys.len() as u16).to_le_bytes());
+        for (item, code, offset, length) in items.iter() {
+            crate::core::descriptor::encode_item(&mut out, item, *code, *offset, *length);
+        }
+        for (item, kind) in keys.iter() {
+            out.extend_from_slice(&item.to_le_bytes());
+            out.push(*kind);
+        }
+    }
+    for link in links {
+        for end in link {
+            out.extend_from_slice(&end.to_le_bytes());
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::descriptor::{TYPE_INTEGER, TYPE_TEXT};
+
+    #[test]
+    fn test_parse_analysis() -> Result<()> {
+        let bytes = encode_analysis(
+            &[
+                ("Client", "CLIENT.FIC", 15, &[("IDCLIENT", TYPE_INTEGER, 1, 4), ("NOM", TYPE_TEXT, 5, 10)], &[(0, 2)]),
+                ("Commande", "COMMANDE.FIC", 9, &[("IDCOMMANDE", TYPE_INTEGER, 1, 4), ("IDCLIENT", TYPE_INTEGER, 5, 4)], &[(0, 2), (1, 0)]),
+            ],
+            &[[1, 1, 0, 0]],
+        );
+        let analysis = Analysis::parse(Path::new("projet.wdd"), &bytes)?;
+        assert_eq!(analysis.files.len(), 2);
+
+        let client = analysis.file("client").expect("fichier CLIENT");
+        assert_eq!(client.schema.name, "CLIENT");
+        assert_eq!(client.schema.fields[1].name, "NOM");
+        assert_eq!(client.keys[0].kind, KeyKind::Primary);
+        assert!(analysis.file("Commande").is_some());
+
+        let link = &analysis.links[0];
+        assert_eq!((link.from_table.as_str(), link.from_column.as_str()), ("COMMANDE", "IDCLIENT"));
+        assert_eq!((link.to_table.as_str(), link.to_column.as_str()), ("CLIENT", "IDCLIENT"));
+
+        // Liaison vers une rubrique inexistante
+        let broken = encode_analysis(&[("Client", "CLIENT.FIC", 5, &[("ID", TYPE_INTEGER, 1, 4)], &[])], &[[0, 0, 0, 3]]);
+        assert!(Analysis::parse(Path::new("projet.wdd"), &broken).is_err());
+        Ok(())
+    }
+}
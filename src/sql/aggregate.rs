@@ -0,0 +1,417 @@
+/**
+ * Agrégats et regroupements (GROUP BY / HAVING) pour l'exécuteur SQL.
+ *
+ * Ce fichier calcule COUNT, SUM, AVG, MIN et MAX sur les enregistrements
+ * décodés retournés par le StorageEngine, regroupés selon GROUP BY :
+ *
+ * - COUNT(*) compte les enregistrements, COUNT(x) les valeurs non NULL
+ * - DISTINCT ignore les doublons de valeurs avant agrégation
+ * - SUM reste entier tant que toutes les valeurs le sont (flottant en cas
+ *   de dépassement) et exact tant qu'il n'y a que des entiers et des
+ *   décimaux (monétaire, numérique)
+ * - AVG est flottant, sauf pour des décimaux : quotient exact arrondi à
+ *   4 décimales de plus que les valeurs
+ * - SUM, AVG, MIN et MAX d'un groupe sans valeur valent NULL
+ * - Les chaînes numériques sont prises en compte par SUM et AVG, les
+ *   booléens comme 1 (vrai) ou 0 (faux)
+ *
+ * Chaque groupe devient un Record dont les champs sont les colonnes du
+ * résultat ; HAVING et ORDER BY sont évalués sur ces Records avec les
+ * évaluateurs du module storage.
+ *
+ * Liens avec d'autres modules :
+ * - Utilisé par src/sql/executor.rs pour les SELECT avec agrégats
+ * - Utilise src/storage/filter.rs (HAVING) et src/storage/sort.rs (ORDER BY)
+ */
+
+use crate::sql::parser::{Aggregate, AggregateFunction, SelectExpr, SelectStatement};
+use crate::storage::decimal::Decimal;
+use crate::storage::engine::{FieldValue, Record};
+use crate::storage::{filter, sort};
+use anyhow::{bail, Result};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+/**
+ * Regroupe les enregistrements et calcule les agrégats d'un SELECT.
+ *
+ * Sans GROUP BY, tous les enregistrements forment un seul groupe (y compris
+ * s'il n'y en a aucun : COUNT(*) vaut alors 0).
+ *
+ * @param select - Requête SELECT avec agrégats
+ * @param records - Enregistrements décodés satisfaisant la clause WHERE
+ * @returns Result<(Vec<String>, Vec<Record>)> - Colonnes et lignes du résultat,
+ *   après HAVING, ORDER BY, OFFSET et LIMIT
+ *
+ * Effets de bord : Aucun
+ */
+pub fn execute_grouped(select: &SelectStatement, records: Vec<Record>) -> Result<(Vec<String>, Vec<Record>)> {
+    if select.columns.is_empty() {
+        bail!("SELECT * n'est pas compatible avec GROUP BY ou les fonctions d'agrégat");
+    }
+    for item in &select.columns {
+        if let SelectExpr::Column(column) = &item.expr {
+            if !select.group_by.iter().any(|g| g.eq_ignore_ascii_case(column)) {
+                bail!("La colonne {} doit figurer dans GROUP BY ou dans une fonction d'agrégat", column);
+            }
+        }
+    }
+
+    // Agrégats à calculer : ceux projetés puis ceux de HAVING / ORDER BY
+    let mut aggregates: Vec<Aggregate> = Vec::new();
+    let projected = select.columns.iter().filter_map(|item| match &item.expr {
+        SelectExpr::Aggregate(aggregate) => Some(aggregate),
+        SelectExpr::Column(_) => None,
+    });
+    for aggregate in projected.chain(select.extra_aggregates.iter()) {
+        if !aggregates.contains(aggregate) {
+            aggregates.push(aggregate.clone());
+        }
+    }
+
+    // Groupes dans l'ordre de première apparition
+    let mut groups: Vec<(Vec<FieldValue>, Vec<Accumulator>)> = Vec::new();
+    let mut group_index: HashMap<String, usize> = HashMap::new();
+
+    for record in &records {
+        let key: Vec<FieldValue> = select.group_by
+            .iter()
+            .map(|column| filter::lookup_field(record, column).cloned().unwrap_or_else(FieldValue::null))
+            .collect();
+        let key_text = serde_json::to_string(&key)?;
+        let index = *group_index.entry(key_text).or_insert_with(|| {
+            groups.push((key, aggregates.iter().cloned().map(Accumulator::new).collect()));
+            groups.len() - 1
+        });
+        for accumulator in &mut groups[index].1 {
+            accumulator.update(record);
+        }
+    }
+
+    if groups.is_empty() && select.group_by.is_empty() {
+        groups.push((Vec::new(), aggregates.iter().cloned().map(Accumulator::new).collect()));
+    }
+
+    // Un Record par groupe : colonnes de regroupement, agrégats, alias
+    let mut rows: Vec<Record> = groups
+        .into_iter()
+        .enumerate()
+        .map(|(i, (key, accumulators))| {
+            let mut fields = HashMap::new();
+            for (column, value) in select.group_by.iter().zip(key) {
+                fields.insert(column.clone(), value);
+            }
+            for accumulator in accumulators {
+                fields.insert(accumulator.aggregate.name(), accumulator.finish());
+            }
+            let mut row = Record { id: i as u32, fields, memo_data: HashMap::new() };
+            for item in &select.columns {
+                let source = match &item.expr {
+                    SelectExpr::Column(column) => column.clone(),
+                    SelectExpr::Aggregate(aggregate) => aggregate.name(),
+                };
+                let value = filter::lookup_field(&row, &source).cloned().unwrap_or_else(FieldValue::null);
+                row.fields.insert(item.output_name(), value);
+            }
+            row
+        })
+        .collect();
+
+    if let Some(having) = &select.having {
+        rows.retain(|row| filter::matches_expr(row, having));
+    }
+    if !select.order_by.is_empty() {
+        rows.sort_by(|a, b| sort::compare_records(a, b, &select.order_by));
+    }
+
+    let columns: Vec<String> = select.columns.iter().map(|item| item.output_name()).collect();
+    let rows = rows
+        .into_iter()
+        .skip(select.offset.unwrap_or(0) as usize)
+        .take(select.limit.map(|l| l as usize).unwrap_or(usize::MAX))
+        .enumerate()
+        .map(|(i, mut row)| {
+            // Ne garder que les colonnes projetées
+            let fields = columns
+                .iter()
+                .map(|name| (name.clone(), row.fields.remove(name).unwrap_or_else(FieldValue::null)))
+                .collect();
+            Record { id: i as u32, fields, memo_data: HashMap::new() }
+        })
+        .collect();
+
+    Ok((columns, rows))
+}
+
+/// État de calcul d'un agrégat pour un groupe
+struct Accumulator {
+    aggregate: Aggregate,
+    /// Valeurs déjà vues (DISTINCT uniquement)
+    seen: Option<HashSet<String>>,
+    /// Nombre de valeurs prises en compte
+    count: i64,
+    int_sum: i64,
+    float_sum: f64,
+    /// Somme exacte dès qu'une valeur décimale est rencontrée
+    decimal_sum: Option<Decimal>,
+    /// La somme est passée en flottant (valeur flottante ou dépassement)
+    is_float: bool,
+    /// Minimum ou maximum courant
+    extreme: Option<FieldValue>,
+}
+
+impl Accumulator {
+    fn new(aggregate: Aggregate) -> Self {
+        Self {
+            seen: aggregate.distinct.then(HashSet::new),
+            aggregate,
+            count: 0,
+            int_sum: 0,
+            float_sum: 0.0,
+            decimal_sum: None,
+            is_float: false,
+            extreme: None,
+        }
+    }
+
+    /// Prend en compte un enregistrement du groupe
+    fn update(&mut self, record: &Record) {
+        let Some(column) = &self.aggregate.column else {
+            // COUNT(*)
+            self.count += 1;
+            return;
+        };
+        let value = match filter::lookup_field(record, column) {
+            None | Some(FieldValue::Null { .. }) => return,
+            Some(value) => value,
+        };
+        if let Some(seen) = &mut self.seen {
+            let key = serde_json::to_string(value).unwrap_or_default();
+            if !seen.insert(key) {
+                return;
+            }
+        }
+
+        match self.aggregate.function {
+            AggregateFunction::Count => self.count += 1,
+            AggregateFunction::Sum | AggregateFunction::Avg => match numeric_value(value) {
+                Some(Numeric::Int(i)) => {
+                    self.count += 1;
+                    if self.is_float {
+                        self.float_sum += i as f64;
+                    } else if self.decimal_sum.is_some() {
+                        self.add_decimal(Decimal::new(i as i128, 0));
+                    } else if let Some(sum) = self.int_sum.checked_add(i) {
+                        self.int_sum = sum;
+                    } else {
+                        self.is_float = true;
+                        self.float_sum = self.int_sum as f64 + i as f64;
+                    }
+                }
+                Some(Numeric::Decimal(d)) => {
+                    self.count += 1;
+                    if self.is_float {
+                        self.float_sum += d.to_f64();
+                    } else {
+                        self.add_decimal(d);
+                    }
+                }
+                Some(Numeric::Float(f)) => {
+                    self.count += 1;
+                    if !self.is_float {
+                        self.is_float = true;
+                        self.float_sum = self.exact_total();
+                    }
+                    self.float_sum += f;
+                }
+                // Valeur non numérique : ignorée comme un NULL
+                None => {}
+            },
+            AggregateFunction::Min | AggregateFunction::Max => {
+                let wanted = if self.aggregate.function == AggregateFunction::Min {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                };
+                let replace = self.extreme
+                    .as_ref()
+                    .is_none_or(|current| sort::compare_field_values(value, current) == wanted);
+                if replace {
+                    self.extreme = Some(value.clone());
+                }
+            }
+        }
+    }
+
+    /// Ajoute un décimal à la somme exacte (flottante en cas de dépassement)
+    fn add_decimal(&mut self, value: Decimal) {
+        let sum = self.decimal_sum.unwrap_or(Decimal::new(self.int_sum as i128, 0));
+        match sum.checked_add(&value) {
+            Some(sum) => self.decimal_sum = Some(sum),
+            None => {
+                self.float_sum = sum.to_f64() + value.to_f64();
+                self.is_float = true;
+            }
+        }
+    }
+
+    /// Somme exacte courante convertie en flottant
+    fn exact_total(&self) -> f64 {
+        match self.decimal_sum {
+            Some(sum) => sum.to_f64(),
+            None => self.int_sum as f64,
+        }
+    }
+
+    /// Valeur finale de l'agrégat
+    fn finish(self) -> FieldValue {
+        match self.aggregate.function {
+            AggregateFunction::Count => FieldValue::integer(self.count),
+            _ if self.count == 0 && self.extreme.is_none() => FieldValue::null(),
+            AggregateFunction::Sum if self.is_float => FieldValue::float(self.float_sum),
+            AggregateFunction::Sum => match self.decimal_sum {
+                Some(sum) => FieldValue::decimal(sum),
+                None => FieldValue::integer(self.int_sum),
+            },
+            AggregateFunction::Avg => {
+                let average = self.decimal_sum
+                    .filter(|_| !self.is_float)
+                    .and_then(|sum| sum.checked_div_round(self.count, 4));
+                match average {
+                    Some(average) => FieldValue::decimal(average),
+                    None => {
+                        let total = if self.is_float { self.float_sum } else { self.exact_total() };
+                        FieldValue::float(total / self.count as f64)
+                    }
+                }
+            }
+            AggregateFunction::Min | AggregateFunction::Max => self.extreme.unwrap_or_else(FieldValue::null),
+        }
+    }
+}
+
+/// Valeur numérique extraite d'un champ
+enum Numeric {
+    Int(i64),
+    Decimal(Decimal),
+    Float(f64),
+}
+
+/// Interprète une valeur de champ comme un nombre (chaînes numériques comprises)
+fn numeric_value(value: &FieldValue) -> Option<Numeric> {
+    match value {
+        FieldValue::Integer { value } => Some(Numeric::Int(*value)),
+        FieldValue::Boolean { value } => Some(Numeric::Int(*value as i64)),
+        FieldValue::Float { value } => Some(Numeric::Float(*value)),
+        FieldValue::Decimal { value } => Decimal::parse(value).map(Numeric::Decimal),
+        FieldValue::String { value } => {
+            let text = value.trim();
+            text.parse::<i64>()
+                .map(Numeric::Int)
+                .or_else(|_| text.parse::<f64>().map(Numeric::Float))
+                .ok()
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql::parser::{SqlParser, SqlStatement};
+
+    fn invoice(id: u32, status: &str, client: &str, amount: Option<i64>) -> Record {
+        let mut fields = HashMap::new();
+        fields.insert("STATUT".to_string(), FieldValue::string(status.to_string()));
+        fields.insert("CLIENT".to_string(), FieldValue::string(client.to_string()));
+        fields.insert(
+            "MONTANT".to_string(),
+            amount.map(FieldValue::integer).unwrap_or_else(FieldValue::null),
+        );
+        Record { id, fields, memo_data: HashMap::new() }
+    }
+
+    fn run(sql: &str, records: Vec<Record>) -> Result<(Vec<String>, Vec<Record>)> {
+        match SqlParser::parse(sql)? {
+            SqlStatement::Select(select) => execute_grouped(&select, records),
+            other => panic!("SELECT attendu: {:?}", other),
+        }
+    }
+
+    fn invoices() -> Vec<Record> {
+        vec![
+            invoice(0, "PAYEE", "A", Some(100)),
+            invoice(1, "PAYEE", "B", Some(50)),
+            invoice(2, "EN_ATTENTE", "A", None),
+            invoice(3, "PAYEE", "A", Some(30)),
+            invoice(4, "ANNULEE", "C", Some(5)),
+        ]
+    }
+
+    #[test]
+    fn test_group_by_with_aggregates() -> Result<()> {
+        let (columns, rows) = run(
+            "SELECT STATUT, COUNT(*) AS NB, COUNT(MONTANT), COUNT(DISTINCT CLIENT), SUM(MONTANT), \
+             AVG(MONTANT), MIN(CLIENT), MAX(MONTANT) FROM FACTURE GROUP BY STATUT ORDER BY NB DESC, STATUT",
+            invoices(),
+        )?;
+        assert_eq!(columns[1], "NB");
+        assert_eq!(rows.len(), 3);
+
+        let payee = &rows[0].fields;
+        assert!(matches!(&payee["STATUT"], FieldValue::String { value } if value == "PAYEE"));
+        assert!(matches!(payee["NB"], FieldValue::Integer { value: 3 }));
+        assert!(matches!(payee["COUNT(DISTINCT CLIENT)"], FieldValue::Integer { value: 2 }));
+        assert!(matches!(payee["SUM(MONTANT)"], FieldValue::Integer { value: 180 }));
+        assert!(matches!(payee["AVG(MONTANT)"], FieldValue::Float { value } if value == 60.0));
+        assert!(matches!(&payee["MIN(CLIENT)"], FieldValue::String { value } if value == "A"));
+        assert!(matches!(payee["MAX(MONTANT)"], FieldValue::Integer { value: 100 }));
+
+        // Groupe sans montant : COUNT(x) = 0, SUM/AVG = NULL
+        let pending = rows.iter().find(|r| matches!(&r.fields["STATUT"], FieldValue::String { value } if value == "EN_ATTENTE")).unwrap();
+        assert!(matches!(pending.fields["COUNT(MONTANT)"], FieldValue::Integer { value: 0 }));
+        assert!(matches!(pending.fields["SUM(MONTANT)"], FieldValue::Null { .. }));
+        Ok(())
+    }
+
+    #[test]
+    fn test_having_and_global_aggregates() -> Result<()> {
+        let (_, rows) = run(
+            "SELECT STATUT FROM FACTURE GROUP BY STATUT HAVING COUNT(*) > 1 OR SUM(MONTANT) < 10",
+            invoices(),
+        )?;
+        let statuses: Vec<String> = rows
+            .iter()
+            .map(|r| match &r.fields["STATUT"] { FieldValue::String { value } => value.clone(), _ => String::new() })
+            .collect();
+        assert_eq!(statuses, vec!["PAYEE", "ANNULEE"]);
+        // Les agrégats de HAVING ne sont pas projetés
+        assert_eq!(rows[0].fields.len(), 1);
+
+        let (_, rows) = run("SELECT COUNT(*), SUM(MONTANT) FROM FACTURE", Vec::new())?;
+        assert_eq!(rows.len(), 1);
+        assert!(matches!(rows[0].fields["COUNT(*)"], FieldValue::Integer { value: 0 }));
+        assert!(matches!(rows[0].fields["SUM(MONTANT)"], FieldValue::Null { .. }));
+
+        assert!(run("SELECT CLIENT, COUNT(*) FROM FACTURE GROUP BY STATUT", invoices()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_decimal_sum_is_exact() -> Result<()> {
+        let records: Vec<Record> = ["0.10", "0.20", "0.05"]
+            .iter()
+            .enumerate()
+            .map(|(i, amount)| {
+                let mut fields = HashMap::new();
+                fields.insert("MONTANT".to_string(), FieldValue::Decimal { value: amount.to_string() });
+                Record { id: i as u32, fields, memo_data: HashMap::new() }
+            })
+            .collect();
+        let (_, rows) = run("SELECT SUM(MONTANT), AVG(MONTANT), MAX(MONTANT) FROM FACTURE", records)?;
+        assert_eq!(rows[0].fields["SUM(MONTANT)"], FieldValue::Decimal { value: "0.35".to_string() });
+        assert_eq!(rows[0].fields["AVG(MONTANT)"], FieldValue::Decimal { value: "0.116667".to_string() });
+        assert_eq!(rows[0].fields["MAX(MONTANT)"], FieldValue::Decimal { value: "0.20".to_string() });
+        Ok(())
+    }
+}
@@ -6,10 +6,16 @@
  * en appels aux méthodes du moteur de stockage.
  * 
  * Fonctionnalités :
- * - Exécution de SELECT avec filtres et pagination
- * - Exécution de INSERT, UPDATE, DELETE
+ * - Exécution de SELECT avec filtres, tri et pagination
+ * - Exécution de INSERT, UPDATE, DELETE, seules (transaction implicite) ou
+ *   dans une transaction de session (execute_in)
+ * - REINDEX : reconstruction des index .ndx d'une table
  * - Conversion des valeurs SQL en FieldValue
- * - Filtrage des colonnes pour SELECT
+ * - Filtrage des colonnes pour SELECT (avec alias)
+ * - Agrégats, GROUP BY et HAVING (via src/sql/aggregate.rs)
+ * - Jointures INNER / LEFT avec colonnes qualifiées (via src/sql/join.rs)
+ * - Parcours par index .ndx quand WHERE contraint une colonne indexée
+ *   (via src/sql/planner.rs)
  * 
  * Liens avec d'autres modules :
  * - Utilise src/sql/parser.rs pour les structures de requêtes
@@ -17,13 +23,18 @@
  * - Utilisé par src/sql/server.rs pour exécuter les requêtes HTTP
  */
 
-use crate::storage::{QueryFilters, Record, StorageEngine};
+use crate::storage::{filter, QueryFilters, QueryResult, Record, StorageEngine, Transaction};
 use crate::storage::engine::FieldValue;
+use crate::sql::{aggregate, join, planner};
 use crate::sql::parser::*;
 use anyhow::{Context, Result};
 use std::collections::HashMap;
+use std::ops::Bound;
 use std::sync::Arc;
 
+/// Nombre de lignes retournées par un SELECT sans LIMIT
+pub(crate) const DEFAULT_SELECT_LIMIT: u32 = 100;
+
 /// Exécuteur SQL qui traduit les requêtes SQL en opérations sur StorageEngine
 pub struct SqlExecutor {
     /// Moteur de stockage partagé
@@ -45,7 +56,9 @@ impl SqlExecutor {
      * Exécute une requête SQL parsée.
      * 
      * Déroute vers la méthode d'exécution appropriée selon le type
-     * de requête (SELECT, INSERT, UPDATE, DELETE).
+     * de requête (SELECT, INSERT, UPDATE, DELETE). Une écriture est
+     * exécutée dans une transaction implicite : toutes les lignes touchées
+     * par l'instruction sont modifiées, ou aucune.
      * 
      * @param statement - Requête SQL parsée
      * @returns Result<SqlResult> - Résultat de l'exécution ou erreur
@@ -55,38 +68,72 @@ impl SqlExecutor {
      */
     pub fn execute(&self, statement: &SqlStatement) -> Result<SqlResult> {
         match statement {
-            SqlStatement::Select(select) => self.execute_select(select),
-            SqlStatement::Insert(insert) => self.execute_insert(insert),
-            SqlStatement::Update(update) => self.execute_update(update),
-            SqlStatement::Delete(delete) => self.execute_delete(delete),
+            SqlStatement::Select(select) => self.execute_select(select, None),
+            SqlStatement::Begin | SqlStatement::Commit | SqlStatement::Rollback => {
+                anyhow::bail!("BEGIN, COMMIT et ROLLBACK nécessitent une session (POST /sql)")
+            }
+            SqlStatement::Reindex(table) => {
+                let count = self.engine.reindex(table)
+                    .with_context(|| format!("Erreur lors de la reconstruction des index de la table {}", table))?;
+                Ok(SqlResult::Reindex { count })
+            }
+            _ => {
+                let mut txn = self.engine.begin();
+                let result = self.execute_in(statement, &mut txn)?;
+                self.engine.commit(txn)?;
+                Ok(result)
+            }
         }
     }
 
-    fn execute_select(&self, select: &SelectStatement) -> Result<SqlResult> {
-        // Convertir la clause WHERE en QueryFilters
-        let mut filters = QueryFilters {
-            limit: select.limit,
+    /**
+     * Exécute une requête SQL parsée dans une transaction ouverte.
+     * 
+     * Les lectures voient les modifications en attente de la transaction ;
+     * les écritures y sont ajoutées sans toucher aux fichiers.
+     * 
+     * @param statement - Requête SQL parsée (hors BEGIN/COMMIT/ROLLBACK)
+     * @param txn - Transaction de la session
+     * @returns Result<SqlResult> - Résultat de l'exécution ou erreur
+     */
+    pub fn execute_in(&self, statement: &SqlStatement, txn: &mut Transaction) -> Result<SqlResult> {
+        match statement {
+            SqlStatement::Select(select) => self.execute_select(select, Some(txn)),
+            SqlStatement::Insert(insert) => self.execute_insert(insert, txn),
+            SqlStatement::Update(update) => self.execute_update(update, txn),
+            SqlStatement::Delete(delete) => self.execute_delete(delete, txn),
+            SqlStatement::Begin | SqlStatement::Commit | SqlStatement::Rollback => {
+                anyhow::bail!("BEGIN, COMMIT et ROLLBACK sont gérés par la session")
+            }
+            SqlStatement::Reindex(_) => {
+                anyhow::bail!("REINDEX ne peut pas être exécuté dans une transaction")
+            }
+        }
+    }
+
+    fn execute_select(&self, select: &SelectStatement, txn: Option<&Transaction>) -> Result<SqlResult> {
+        if !select.joins.is_empty() {
+            let (columns, rows) = join::execute_join(&self.engine, select, txn)?;
+            return Ok(SqlResult::Select { columns, rows });
+        }
+        if select.is_aggregate() {
+            return self.execute_aggregate_select(select, txn);
+        }
+
+        // WHERE et ORDER BY sont évalués par le moteur sur les valeurs décodées
+        let filters = QueryFilters {
+            limit: Some(select.limit.unwrap_or(DEFAULT_SELECT_LIMIT)),
             offset: select.offset,
             field_filters: HashMap::new(),
+            where_clause: select.where_clause.clone(),
+            order_by: select.order_by.clone(),
+            count_total: false,
         };
 
-        if let Some(where_clause) = &select.where_clause {
-            for condition in &where_clause.conditions {
-                let value = match &condition.value {
-                    SqlValue::String(s) => s.clone(),
-                    SqlValue::Integer(i) => i.to_string(),
-                    SqlValue::Float(f) => f.to_string(),
-                    SqlValue::Boolean(b) => b.to_string(),
-                    SqlValue::Null => "".to_string(),
-                };
-                filters.field_filters.insert(condition.column.clone(), value);
-            }
-        }
-
-        let query_result = self.engine.select(&select.table, filters)
+        let query_result = self.select_rows(txn, &select.table, filters)
             .with_context(|| format!("Erreur lors de la sélection depuis la table {}", select.table))?;
 
-        // Filtrer les colonnes si nécessaire
+        // Filtrer les colonnes si nécessaire (en appliquant les alias)
         let records = if select.columns.is_empty() {
             query_result.records
         } else {
@@ -94,9 +141,11 @@ impl SqlExecutor {
                 .into_iter()
                 .map(|record| {
                     let mut filtered_fields = HashMap::new();
-                    for col in &select.columns {
-                        if let Some(value) = record.fields.get(col) {
-                            filtered_fields.insert(col.clone(), value.clone());
+                    for item in &select.columns {
+                        if let SelectExpr::Column(col) = &item.expr {
+                            if let Some(value) = filter::lookup_field(&record, col) {
+                                filtered_fields.insert(item.output_name(), value.clone());
+                            }
                         }
                     }
                     Record {
@@ -115,13 +164,31 @@ impl SqlExecutor {
                     .map(|s| s.fields.iter().map(|f| f.name.clone()).collect())
                     .unwrap_or_else(|_| vec!["id".to_string()])
             } else {
-                select.columns.clone()
+                select.columns.iter().map(|item| item.output_name()).collect()
             },
             rows: records,
         })
     }
 
-    fn execute_insert(&self, insert: &InsertStatement) -> Result<SqlResult> {
+    /**
+     * Exécute un SELECT avec agrégats, GROUP BY ou HAVING.
+     * 
+     * Récupère toutes les correspondances de la clause WHERE puis délègue le
+     * regroupement à src/sql/aggregate.rs. Les colonnes du résultat sont les
+     * colonnes calculées (alias ou nom de l'agrégat, ex: "COUNT(*)").
+     */
+    fn execute_aggregate_select(&self, select: &SelectStatement, txn: Option<&Transaction>) -> Result<SqlResult> {
+        let filters = Self::all_matching(select.where_clause.clone());
+
+        let query_result = self.select_rows(txn, &select.table, filters)
+            .with_context(|| format!("Erreur lors de la sélection depuis la table {}", select.table))?;
+
+        let (columns, rows) = aggregate::execute_grouped(select, query_result.records)?;
+
+        Ok(SqlResult::Select { columns, rows })
+    }
+
+    fn execute_insert(&self, insert: &InsertStatement, txn: &mut Transaction) -> Result<SqlResult> {
         // Créer un Record à partir des valeurs
         let mut fields = HashMap::new();
         for (i, col) in insert.columns.iter().enumerate() {
@@ -137,46 +204,42 @@ impl SqlExecutor {
             memo_data: HashMap::new(),
         };
 
-        let id = self.engine.insert(&insert.table, record)
+        let id = self.engine.insert_in(txn, &insert.table, record)
             .with_context(|| format!("Erreur lors de l'insertion dans la table {}", insert.table))?;
 
         Ok(SqlResult::Insert { id })
     }
 
-    fn execute_update(&self, update: &UpdateStatement) -> Result<SqlResult> {
+    fn execute_update(&self, update: &UpdateStatement, txn: &mut Transaction) -> Result<SqlResult> {
         // Pour UPDATE, on doit d'abord trouver les enregistrements à mettre à jour
-        let mut filters = QueryFilters {
-            limit: None,
-            offset: None,
-            field_filters: HashMap::new(),
-        };
+        let filters = Self::all_matching(update.where_clause.clone());
 
-        if let Some(where_clause) = &update.where_clause {
-            for condition in &where_clause.conditions {
-                let value = match &condition.value {
-                    SqlValue::String(s) => s.clone(),
-                    SqlValue::Integer(i) => i.to_string(),
-                    SqlValue::Float(f) => f.to_string(),
-                    SqlValue::Boolean(b) => b.to_string(),
-                    SqlValue::Null => "".to_string(),
-                };
-                filters.field_filters.insert(condition.column.clone(), value);
-            }
-        }
-
-        let query_result = self.engine.select(&update.table, filters)
+        let query_result = self.select_rows(Some(txn), &update.table, filters)
             .with_context(|| format!("Erreur lors de la sélection pour UPDATE dans la table {}", update.table))?;
 
+        // Les colonnes de SET sont associées au schéma sans tenir compte de
+        // la casse ; une colonne inconnue est refusée même sans correspondance
+        let schema = self.engine.get_schema(&update.table)?;
+        let set_columns = update.set_clauses
+            .iter()
+            .map(|set_clause| {
+                schema.fields.iter()
+                    .find(|f| f.name.eq_ignore_ascii_case(&set_clause.column))
+                    .map(|f| f.name.clone())
+                    .with_context(|| format!("Colonne inconnue dans la table {}: {}", update.table, set_clause.column))
+            })
+            .collect::<Result<Vec<String>>>()?;
+
         let mut updated_count = 0;
         for record in query_result.records {
             // Mettre à jour les champs
             let mut updated_record = record.clone();
-            for set_clause in &update.set_clauses {
+            for (set_clause, column) in update.set_clauses.iter().zip(&set_columns) {
                 let value = self.sql_value_to_field_value(&set_clause.value)?;
-                updated_record.fields.insert(set_clause.column.clone(), value);
+                updated_record.fields.insert(column.clone(), value);
             }
 
-            self.engine.update(&update.table, record.id, updated_record)
+            self.engine.update_in(txn, &update.table, record.id, updated_record)
                 .with_context(|| format!("Erreur lors de la mise à jour de l'enregistrement {}", record.id))?;
             updated_count += 1;
         }
@@ -184,33 +247,16 @@ impl SqlExecutor {
         Ok(SqlResult::Update { count: updated_count })
     }
 
-    fn execute_delete(&self, delete: &DeleteStatement) -> Result<SqlResult> {
+    fn execute_delete(&self, delete: &DeleteStatement, txn: &mut Transaction) -> Result<SqlResult> {
         // Pour DELETE, on doit d'abord trouver les enregistrements à supprimer
-        let mut filters = QueryFilters {
-            limit: None,
-            offset: None,
-            field_filters: HashMap::new(),
-        };
+        let filters = Self::all_matching(delete.where_clause.clone());
 
-        if let Some(where_clause) = &delete.where_clause {
-            for condition in &where_clause.conditions {
-                let value = match &condition.value {
-                    SqlValue::String(s) => s.clone(),
-                    SqlValue::Integer(i) => i.to_string(),
-                    SqlValue::Float(f) => f.to_string(),
-                    SqlValue::Boolean(b) => b.to_string(),
-                    SqlValue::Null => "".to_string(),
-                };
-                filters.field_filters.insert(condition.column.clone(), value);
-            }
-        }
-
-        let query_result = self.engine.select(&delete.table, filters)
+        let query_result = self.select_rows(Some(txn), &delete.table, filters)
             .with_context(|| format!("Erreur lors de la sélection pour DELETE dans la table {}", delete.table))?;
 
         let mut deleted_count = 0;
         for record in query_result.records {
-            self.engine.delete(&delete.table, record.id)
+            self.engine.delete_in(txn, &delete.table, record.id)
                 .with_context(|| format!("Erreur lors de la suppression de l'enregistrement {}", record.id))?;
             deleted_count += 1;
         }
@@ -218,15 +264,66 @@ impl SqlExecutor {
         Ok(SqlResult::Delete { count: deleted_count })
     }
 
+    /**
+     * Sélectionne les enregistrements d'une table, via un index .ndx si la
+     * clause WHERE contraint une colonne indexée, sinon par parcours complet.
+     * 
+     * Les intervalles candidats sont essayés du plus sélectif au moins
+     * sélectif ; le premier couvert par un index est parcouru.
+     * 
+     * @param txn - Transaction de la session (None hors transaction)
+     * @param table - Nom de la table
+     * @param filters - Filtres de requête complets
+     * @returns Result<QueryResult> - Résultats identiques à un parcours complet
+     */
+    fn select_rows(&self, txn: Option<&Transaction>, table: &str, filters: QueryFilters) -> Result<QueryResult> {
+        // Les .ndx ne reflètent pas les modifications en attente de la transaction
+        if !txn.is_some_and(|t| t.touches(table)) {
+            for range in planner::candidate_ranges(filters.where_clause.as_ref()) {
+                let lower = self.bound_to_field_value(&range.lower)?;
+                let upper = self.bound_to_field_value(&range.upper)?;
+                if let Some(result) = self.engine.select_by_index(table, &range.column, lower.as_ref(), upper.as_ref(), filters.clone())? {
+                    return Ok(result);
+                }
+            }
+        }
+        self.engine.select_in(txn, table, filters)
+    }
+
+    fn bound_to_field_value(&self, bound: &Bound<SqlValue>) -> Result<Bound<FieldValue>> {
+        Ok(match bound {
+            Bound::Included(v) => Bound::Included(self.sql_value_to_field_value(v)?),
+            Bound::Excluded(v) => Bound::Excluded(self.sql_value_to_field_value(v)?),
+            Bound::Unbounded => Bound::Unbounded,
+        })
+    }
+
+    /**
+     * Construit des filtres sans pagination pour cibler toutes les
+     * correspondances d'une clause WHERE (UPDATE / DELETE).
+     * 
+     * @param where_clause - Clause WHERE optionnelle
+     * @returns QueryFilters - Filtres sans limite
+     */
+    fn all_matching(where_clause: Option<Expr>) -> QueryFilters {
+        QueryFilters {
+            limit: None,
+            offset: None,
+            field_filters: HashMap::new(),
+            where_clause,
+            order_by: Vec::new(),
+            count_total: false,
+        }
+    }
+
     fn sql_value_to_field_value(&self, sql_value: &SqlValue) -> Result<FieldValue> {
         match sql_value {
             SqlValue::String(s) => Ok(FieldValue::string(s.clone())),
-            SqlValue::Integer(i) => Ok(FieldValue::integer(*i as i64)),
+            SqlValue::Integer(i) => Ok(FieldValue::integer(*i)),
             SqlValue::Float(f) => Ok(FieldValue::float(*f)),
-            SqlValue::Boolean(b) => {
-                // Convertir boolean en integer (1 ou 0) car FieldValue n'a pas de type boolean
-                Ok(FieldValue::integer(if *b { 1 } else { 0 }))
-            }
+            SqlValue::Decimal(d) => Ok(FieldValue::Decimal { value: d.clone() }),
+            // Converti en 1 / 0 à l'écriture d'un champ entier
+            SqlValue::Boolean(b) => Ok(FieldValue::boolean(*b)),
             SqlValue::Null => Ok(FieldValue::null()),
         }
     }
@@ -235,6 +332,7 @@ impl SqlExecutor {
 /// Résultat d'une exécution SQL
 #[derive(Debug, Clone)]
 pub enum SqlResult {
+    /// Colonnes du résultat (colonnes projetées ou calculées) et lignes
     Select {
         columns: Vec<String>,
         rows: Vec<Record>,
@@ -248,5 +346,9 @@ pub enum SqlResult {
     Delete {
         count: usize,
     },
+    /// Nombre d'entrées écrites dans les index reconstruits
+    Reindex {
+        count: usize,
+    },
 }
 
@@ -0,0 +1,560 @@
+/**
+ * Jointures entre tables HFSQL pour l'exécuteur SQL.
+ *
+ * Ce fichier exécute les SELECT comportant des clauses [INNER] JOIN ou
+ * LEFT JOIN. Les colonnes du résultat sont qualifiées par l'alias (ou le
+ * nom) de leur table, par exemple `c.NOM` et `co.NOM`, pour éviter les
+ * collisions entre tables.
+ *
+ * Déroulement :
+ * 1. Résolution des noms : chaque colonne de la requête est qualifiée ;
+ *    une colonne non qualifiée doit appartenir à une seule table
+ * 2. Jointures dans l'ordre de la requête : via l'index .ndx de la table
+ *    jointe s'il couvre la colonne de jointure (une seule égalité dans ON),
+ *    sinon par hachage des enregistrements retournés par StorageEngine::select
+ * 3. WHERE, puis agrégats (src/sql/aggregate.rs) ou tri et pagination
+ *
+ * Liens avec d'autres modules :
+ * - Utilisé par src/sql/executor.rs pour les SELECT avec jointures
+ * - Utilise src/storage/StorageEngine pour lire les tables et les index
+ */
+
+use crate::sql::aggregate;
+use crate::sql::executor::DEFAULT_SELECT_LIMIT;
+use crate::sql::parser::{Aggregate, Condition, Expr, JoinKind, SelectExpr, SelectStatement};
+use crate::storage::decimal::Decimal;
+use crate::storage::engine::{FieldValue, QueryFilters, Record, StorageEngine};
+use crate::storage::Transaction;
+use crate::storage::{filter, sort};
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+
+/// Table participant à la requête
+struct Source {
+    table: String,
+    /// Préfixe des colonnes (alias ou nom de la table)
+    qualifier: String,
+    /// Colonnes de la table (non qualifiées)
+    columns: Vec<String>,
+}
+
+impl Source {
+    fn load(engine: &StorageEngine, table: &str, alias: Option<&str>) -> Result<Self> {
+        let schema = engine.get_schema(table)
+            .with_context(|| format!("Table '{}' non trouvée", table))?;
+        let mut columns: Vec<String> = schema.fields.into_iter().map(|f| f.name).collect();
+        if !columns.iter().any(|c| c.eq_ignore_ascii_case("id")) {
+            columns.insert(0, "id".to_string());
+        }
+        Ok(Self {
+            table: table.to_string(),
+            qualifier: alias.unwrap_or(table).to_string(),
+            columns,
+        })
+    }
+
+    /// Colonnes qualifiées (qualifier.colonne)
+    fn qualified_columns(&self) -> Vec<String> {
+        self.columns.iter().map(|c| format!("{}.{}", self.qualifier, c)).collect()
+    }
+}
+
+/**
+ * Exécute un SELECT avec jointures.
+ *
+ * @param engine - Moteur de stockage
+ * @param select - Requête SELECT comportant au moins une jointure
+ * @param txn - Transaction de la session (ses modifications sont visibles)
+ * @returns Result<(Vec<String>, Vec<Record>)> - Colonnes qualifiées et lignes
+ *
+ * Effets de bord :
+ * - Lit les fichiers .fic/.mmo/.ndx des tables jointes
+ */
+pub fn execute_join(
+    engine: &StorageEngine,
+    select: &SelectStatement,
+    txn: Option<&Transaction>,
+) -> Result<(Vec<String>, Vec<Record>)> {
+    let mut sources = vec![Source::load(engine, &select.table, select.table_alias.as_deref())?];
+    for join in &select.joins {
+        let source = Source::load(engine, &join.table, join.alias.as_deref())?;
+        if sources.iter().any(|s| s.qualifier.eq_ignore_ascii_case(&source.qualifier)) {
+            bail!("La table ou l'alias '{}' apparaît plusieurs fois, utilisez des alias distincts", source.qualifier);
+        }
+        sources.push(source);
+    }
+
+    let select = resolve_statement(select, &sources)?;
+
+    let mut rows: Vec<Record> = load_all(engine, txn, &sources[0].table)?
+        .into_iter()
+        .map(|r| qualify(r, &sources[0].qualifier))
+        .collect();
+
+    for (join, source) in select.joins.iter().zip(&sources[1..]) {
+        let (left_keys, right_keys) = split_join_keys(join, &source.qualifier)?;
+        let right_columns = source.qualified_columns();
+
+        // Les .ndx ne reflètent pas les modifications en attente de la transaction
+        let indexed = if right_keys.len() == 1 && !txn.is_some_and(|t| t.touches(&source.table)) {
+            index_join(engine, &rows, join.kind, &left_keys[0], source, &right_keys[0], &right_columns)?
+        } else {
+            None
+        };
+        rows = match indexed {
+            Some(joined) => joined,
+            None => {
+                let right_rows: Vec<Record> = load_all(engine, txn, &source.table)?
+                    .into_iter()
+                    .map(|r| qualify(r, &source.qualifier))
+                    .collect();
+                let right_keys: Vec<String> = right_keys
+                    .iter()
+                    .map(|c| format!("{}.{}", source.qualifier, c))
+                    .collect();
+                hash_join(rows, right_rows, join.kind, &left_keys, &right_keys, &right_columns)
+            }
+        };
+    }
+
+    if let Some(where_clause) = &select.where_clause {
+        rows.retain(|row| filter::matches_expr(row, where_clause));
+    }
+
+    if select.is_aggregate() {
+        return aggregate::execute_grouped(&select, rows);
+    }
+
+    // Les alias sont utilisables dans ORDER BY
+    for row in &mut rows {
+        for item in &select.columns {
+            if let (Some(alias), SelectExpr::Column(column)) = (&item.alias, &item.expr) {
+                let value = row.fields.get(column).cloned().unwrap_or_else(FieldValue::null);
+                row.fields.insert(alias.clone(), value);
+            }
+        }
+    }
+    if !select.order_by.is_empty() {
+        rows.sort_by(|a, b| sort::compare_records(a, b, &select.order_by));
+    }
+
+    let columns: Vec<String> = if select.columns.is_empty() {
+        sources.iter().flat_map(|s| s.qualified_columns()).collect()
+    } else {
+        select.columns.iter().map(|item| item.output_name()).collect()
+    };
+
+    let rows = rows
+        .into_iter()
+        .skip(select.offset.unwrap_or(0) as usize)
+        .take(select.limit.unwrap_or(DEFAULT_SELECT_LIMIT) as usize)
+        .map(|mut row| {
+            let fields = columns
+                .iter()
+                .map(|name| (name.clone(), row.fields.remove(name).unwrap_or_else(FieldValue::null)))
+                .collect();
+            Record { id: row.id, fields, memo_data: row.memo_data }
+        })
+        .collect();
+
+    Ok((columns, rows))
+}
+
+/// Lit tous les enregistrements d'une table
+fn load_all(engine: &StorageEngine, txn: Option<&Transaction>, table: &str) -> Result<Vec<Record>> {
+    let filters = QueryFilters {
+        limit: None,
+        offset: None,
+        field_filters: HashMap::new(),
+        where_clause: None,
+        order_by: Vec::new(),
+        count_total: false,
+    };
+    Ok(engine.select_in(txn, table, filters)?.records)
+}
+
+/// Préfixe les champs (et mémos) d'un enregistrement par le qualificatif de sa table
+fn qualify(record: Record, qualifier: &str) -> Record {
+    Record {
+        id: record.id,
+        fields: record.fields
+            .into_iter()
+            .map(|(name, value)| (format!("{}.{}", qualifier, name), value))
+            .collect(),
+        memo_data: record.memo_data
+            .into_iter()
+            .map(|(name, value)| (format!("{}.{}", qualifier, name), value))
+            .collect(),
+    }
+}
+
+/**
+ * Sépare les conditions ON en clés de la partie gauche (tables déjà jointes)
+ * et colonnes de la table jointe (non qualifiées).
+ */
+fn split_join_keys(join: &crate::sql::parser::JoinClause, qualifier: &str) -> Result<(Vec<String>, Vec<String>)> {
+    let belongs = |column: &str| {
+        column.split_once('.').is_some_and(|(q, _)| q.eq_ignore_ascii_case(qualifier))
+    };
+    let mut left_keys = Vec::new();
+    let mut right_keys = Vec::new();
+    for condition in &join.on {
+        let (left, right) = match (belongs(&condition.left), belongs(&condition.right)) {
+            (false, true) => (&condition.left, &condition.right),
+            (true, false) => (&condition.right, &condition.left),
+            _ => bail!(
+                "La condition ON {} = {} doit relier {} à une table précédente",
+                condition.left, condition.right, qualifier
+            ),
+        };
+        left_keys.push(left.clone());
+        right_keys.push(right.split_once('.').map(|(_, c)| c.to_string()).unwrap_or_default());
+    }
+    Ok((left_keys, right_keys))
+}
+
+/**
+ * Jointure par index : pour chaque ligne, recherche les correspondances via
+ * le .ndx de la table jointe (index et fichiers ouverts une seule fois).
+ * Les candidats de l'index sont comparés avec join_key, comme dans la
+ * jointure par hachage.
+ *
+ * @returns Result<Option<Vec<Record>>> - Lignes jointes, ou None si aucun
+ *   index ne couvre la colonne (repli sur la jointure par hachage)
+ */
+fn index_join(
+    engine: &StorageEngine,
+    rows: &[Record],
+    kind: JoinKind,
+    left_key: &str,
+    source: &Source,
+    right_column: &str,
+    right_columns: &[String],
+) -> Result<Option<Vec<Record>>> {
+    let Some(mut lookup) = engine.index_lookup(&source.table, right_column)? else {
+        return Ok(None);
+    };
+    let left_keys = [left_key.to_string()];
+    let right_keys = [right_column.to_string()];
+    let mut joined = Vec::new();
+    for row in rows {
+        let matches = match (filter::lookup_field(row, left_key), join_key(row, &left_keys)) {
+            (Some(value), Some(key)) => match lookup.find(value)? {
+                Some(candidates) => candidates
+                    .into_iter()
+                    .filter(|r| join_key(r, &right_keys).as_ref() == Some(&key))
+                    .collect(),
+                None => return Ok(None),
+            },
+            _ => Vec::new(),
+        };
+        let matches: Vec<Record> = matches.into_iter().map(|r| qualify(r, &source.qualifier)).collect();
+        push_matches(&mut joined, row, matches.iter(), kind, right_columns);
+    }
+    Ok(Some(joined))
+}
+
+/**
+ * Jointure par hachage : indexe la table jointe en mémoire sur ses colonnes
+ * de jointure, puis parcourt les lignes de gauche.
+ */
+fn hash_join(
+    rows: Vec<Record>,
+    right_rows: Vec<Record>,
+    kind: JoinKind,
+    left_keys: &[String],
+    right_keys: &[String],
+    right_columns: &[String],
+) -> Vec<Record> {
+    let mut table: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, record) in right_rows.iter().enumerate() {
+        if let Some(key) = join_key(record, right_keys) {
+            table.entry(key).or_default().push(i);
+        }
+    }
+
+    let mut joined = Vec::new();
+    for row in &rows {
+        let matches = join_key(row, left_keys)
+            .and_then(|key| table.get(&key))
+            .map(|indices| indices.as_slice())
+            .unwrap_or_default();
+        push_matches(&mut joined, row, matches.iter().map(|&i| &right_rows[i]), kind, right_columns);
+    }
+    joined
+}
+
+/// Ajoute les combinaisons d'une ligne avec ses correspondances (ou NULL en LEFT JOIN)
+fn push_matches<'a>(
+    joined: &mut Vec<Record>,
+    row: &Record,
+    matches: impl Iterator<Item = &'a Record>,
+    kind: JoinKind,
+    right_columns: &[String],
+) {
+    let mut matched = false;
+    for right in matches {
+        matched = true;
+        let mut merged = row.clone();
+        merged.fields.extend(right.fields.iter().map(|(k, v)| (k.clone(), v.clone())));
+        merged.memo_data.extend(right.memo_data.iter().map(|(k, v)| (k.clone(), v.clone())));
+        joined.push(merged);
+    }
+    if !matched && kind == JoinKind::Left {
+        let mut merged = row.clone();
+        for column in right_columns {
+            merged.fields.insert(column.clone(), FieldValue::null());
+        }
+        joined.push(merged);
+    }
+}
+
+/**
+ * Clé de hachage des colonnes de jointure d'une ligne.
+ *
+ * Les nombres sont normalisés (10, 10.0 et le décimal 10.00 se correspondent), les espaces de
+ * fin des chaînes à longueur fixe ignorés. NULL ne correspond à rien.
+ */
+fn join_key(record: &Record, columns: &[String]) -> Option<String> {
+    let mut key = String::new();
+    for column in columns {
+        let part = match filter::lookup_field(record, column)? {
+            FieldValue::Null { .. } => return None,
+            FieldValue::Integer { value } => value.to_string(),
+            FieldValue::Boolean { value } => (*value as i64).to_string(),
+            FieldValue::Float { value } if value.fract() == 0.0 && value.abs() < 1e15 => (*value as i64).to_string(),
+            FieldValue::Float { value } => value.to_string(),
+            FieldValue::Decimal { value } => match Decimal::parse(value) {
+                Some(decimal) => decimal.normalized().to_string(),
+                None => value.clone(),
+            },
+            FieldValue::String { value } => value.trim_end().to_string(),
+            FieldValue::Binary { value }
+            | FieldValue::Date { value }
+            | FieldValue::Time { value }
+            | FieldValue::DateTime { value }
+            | FieldValue::Duration { value } => value.clone(),
+        };
+        key.push_str(&part);
+        key.push('\u{1f}');
+    }
+    Some(key)
+}
+
+/**
+ * Qualifie toutes les références de colonnes de la requête.
+ *
+ * Les agrégats sont renommés avec leur colonne qualifiée (COUNT(ID) devient
+ * COUNT(c.ID)) ; HAVING et ORDER BY peuvent aussi désigner un alias.
+ */
+fn resolve_statement(select: &SelectStatement, sources: &[Source]) -> Result<SelectStatement> {
+    let resolve = |name: &str| resolve_column(name, sources);
+    let mut resolved = select.clone();
+    let mut renamed: HashMap<String, String> = HashMap::new();
+
+    let mut resolve_aggregate = |aggregate: &Aggregate| -> Result<Aggregate> {
+        let mut qualified = aggregate.clone();
+        qualified.column = aggregate.column.as_deref().map(resolve).transpose()?;
+        renamed.insert(aggregate.name(), qualified.name());
+        Ok(qualified)
+    };
+    for item in &mut resolved.columns {
+        item.expr = match &item.expr {
+            SelectExpr::Column(column) => SelectExpr::Column(resolve(column)?),
+            SelectExpr::Aggregate(aggregate) => SelectExpr::Aggregate(resolve_aggregate(aggregate)?),
+        };
+    }
+    for aggregate in &mut resolved.extra_aggregates {
+        *aggregate = resolve_aggregate(aggregate)?;
+    }
+
+    for join in &mut resolved.joins {
+        for condition in &mut join.on {
+            condition.left = resolve(&condition.left)?;
+            condition.right = resolve(&condition.right)?;
+        }
+    }
+    if let Some(where_clause) = &select.where_clause {
+        resolved.where_clause = Some(map_columns(where_clause, &mut |c| resolve(c))?);
+    }
+    resolved.group_by = select.group_by.iter().map(|c| resolve(c)).collect::<Result<_>>()?;
+
+    // HAVING / ORDER BY : agrégat, alias de colonne, ou colonne
+    let aliases: Vec<&str> = select.columns.iter().filter_map(|i| i.alias.as_deref()).collect();
+    let mut resolve_output = |name: &str| -> Result<String> {
+        if let Some(new_name) = renamed.get(name) {
+            Ok(new_name.clone())
+        } else if aliases.iter().any(|a| a.eq_ignore_ascii_case(name)) {
+            Ok(name.to_string())
+        } else {
+            resolve(name)
+        }
+    };
+    if let Some(having) = &select.having {
+        resolved.having = Some(map_columns(having, &mut resolve_output)?);
+    }
+    for item in &mut resolved.order_by {
+        item.column = resolve_output(&item.column)?;
+    }
+
+    Ok(resolved)
+}
+
+/**
+ * Qualifie un nom de colonne.
+ *
+ * `alias.COL` est vérifié contre les colonnes de la table correspondante ;
+ * `COL` doit appartenir à exactement une table.
+ */
+fn resolve_column(name: &str, sources: &[Source]) -> Result<String> {
+    if let Some((qualifier, column)) = name.split_once('.') {
+        let source = sources
+            .iter()
+            .find(|s| s.qualifier.eq_ignore_ascii_case(qualifier))
+            .with_context(|| format!("Table ou alias inconnu: {}", qualifier))?;
+        let column = source.columns
+            .iter()
+            .find(|c| c.eq_ignore_ascii_case(column))
+            .with_context(|| format!("Colonne inconnue: {}", name))?;
+        return Ok(format!("{}.{}", source.qualifier, column));
+    }
+
+    let mut candidates = sources.iter().filter_map(|s| {
+        s.columns
+            .iter()
+            .find(|c| c.eq_ignore_ascii_case(name))
+            .map(|c| format!("{}.{}", s.qualifier, c))
+    });
+    match (candidates.next(), candidates.next()) {
+        (Some(qualified), None) => Ok(qualified),
+        (Some(_), Some(_)) => bail!("Colonne ambiguë: {} (préfixez-la par la table ou son alias)", name),
+        (None, _) => bail!("Colonne inconnue: {}", name),
+    }
+}
+
+/// Applique une transformation à toutes les colonnes d'une expression
+fn map_columns(expr: &Expr, f: &mut dyn FnMut(&str) -> Result<String>) -> Result<Expr> {
+    Ok(match expr {
+        Expr::Comparison(condition) => Expr::Comparison(Condition {
+            column: f(&condition.column)?,
+            operator: condition.operator.clone(),
+            value: condition.value.clone(),
+        }),
+        Expr::And(left, right) => Expr::And(Box::new(map_columns(left, f)?), Box::new(map_columns(right, f)?)),
+        Expr::Or(left, right) => Expr::Or(Box::new(map_columns(left, f)?), Box::new(map_columns(right, f)?)),
+        Expr::Not(inner) => Expr::Not(Box::new(map_columns(inner, f)?)),
+        Expr::In { column, values, negated } => Expr::In {
+            column: f(column)?,
+            values: values.clone(),
+            negated: *negated,
+        },
+        Expr::Between { column, low, high, negated } => Expr::Between {
+            column: f(column)?,
+            low: low.clone(),
+            high: high.clone(),
+            negated: *negated,
+        },
+        Expr::IsNull { column, negated } => Expr::IsNull { column: f(column)?, negated: *negated },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(qualifier: &str, columns: &[&str]) -> Source {
+        Source {
+            table: qualifier.to_uppercase(),
+            qualifier: qualifier.to_string(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+
+    fn row(id: u32, fields: &[(&str, FieldValue)]) -> Record {
+        Record {
+            id,
+            fields: fields.iter().map(|(k, v)| (k.to_string(), v.clone())).collect(),
+            memo_data: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_column() {
+        let sources = vec![source("c", &["id", "NOM", "IDCLIENT"]), source("co", &["id", "IDCLIENT", "TOTAL"])];
+        assert_eq!(resolve_column("nom", &sources).unwrap(), "c.NOM");
+        assert_eq!(resolve_column("CO.idclient", &sources).unwrap(), "co.IDCLIENT");
+        assert!(resolve_column("IDCLIENT", &sources).is_err());
+        assert!(resolve_column("x.NOM", &sources).is_err());
+        assert!(resolve_column("PRIX", &sources).is_err());
+    }
+
+    #[test]
+    fn test_hash_join_inner_and_left() {
+        let clients = vec![
+            row(0, &[("c.IDCLIENT", FieldValue::integer(1)), ("c.NOM", FieldValue::string("Dupont".to_string()))]),
+            row(1, &[("c.IDCLIENT", FieldValue::integer(2)), ("c.NOM", FieldValue::string("Martin".to_string()))]),
+            row(2, &[("c.IDCLIENT", FieldValue::null()), ("c.NOM", FieldValue::string("Anonyme".to_string()))]),
+        ];
+        let orders = vec![
+            row(0, &[("co.IDCLIENT", FieldValue::float(1.0)), ("co.TOTAL", FieldValue::integer(10))]),
+            row(1, &[("co.IDCLIENT", FieldValue::integer(1)), ("co.TOTAL", FieldValue::integer(20))]),
+            row(2, &[("co.IDCLIENT", FieldValue::integer(3)), ("co.TOTAL", FieldValue::integer(30))]),
+        ];
+        let left_keys = vec!["c.IDCLIENT".to_string()];
+        let right_keys = vec!["co.IDCLIENT".to_string()];
+        let right_columns = vec!["co.IDCLIENT".to_string(), "co.TOTAL".to_string()];
+
+        let inner = hash_join(clients.clone(), orders.clone(), JoinKind::Inner, &left_keys, &right_keys, &right_columns);
+        let totals: Vec<i64> = inner
+            .iter()
+            .map(|r| match r.fields["co.TOTAL"] { FieldValue::Integer { value } => value, _ => -1 })
+            .collect();
+        assert_eq!(totals, vec![10, 20]);
+        assert!(inner.iter().all(|r| r.fields.contains_key("c.NOM")));
+
+        let left = hash_join(clients, orders, JoinKind::Left, &left_keys, &right_keys, &right_columns);
+        assert_eq!(left.len(), 4);
+        assert!(matches!(left[2].fields["co.TOTAL"], FieldValue::Null { .. }));
+        assert!(matches!(left[3].fields["co.TOTAL"], FieldValue::Null { .. }));
+    }
+
+    #[test]
+    fn test_index_join_matches_hash_join() -> Result<()> {
+        use crate::core::fic::encode_header;
+        use crate::core::{KeyComponent, KeyType, NdxFile, NdxKey};
+
+        // Tables de 16 bytes : rubrique flags (offset 4) indexée dans B
+        let dir = tempfile::tempdir()?;
+        let write_table = |name: &str, flags: &[u8]| -> Result<()> {
+            let mut bytes = encode_header(16, flags.len() as u32);
+            for (i, flag) in flags.iter().enumerate() {
+                let mut slot = vec![0u8; 16];
+                slot[4] = *flag;
+                slot[5] = b'a' + i as u8;
+                bytes.extend_from_slice(&slot);
+            }
+            Ok(std::fs::write(dir.path().join(format!("{}.fic", name)), bytes)?)
+        };
+        write_table("A", &[1, 2, 3])?;
+        write_table("B", &[1, 3, 1, 4])?;
+        let component = KeyComponent { offset: 4, length: 1, key_type: KeyType::Unsigned, case_insensitive: false, descending: false };
+        let entries = vec![(vec![1], 0), (vec![1], 2), (vec![3], 1), (vec![4], 3)];
+        NdxFile::create(dir.path().join("B.ndx"), 128, vec![(NdxKey::new(vec![component], false), entries)])?;
+        let engine = StorageEngine::new(dir.path(), true)?;
+        engine.scan_tables()?;
+
+        let rows: Vec<Record> = load_all(&engine, None, "A")?.into_iter().map(|r| qualify(r, "A")).collect();
+        let source = Source::load(&engine, "B", None)?;
+        let right_columns = source.qualified_columns();
+        let right_rows: Vec<Record> = load_all(&engine, None, "B")?.into_iter().map(|r| qualify(r, "B")).collect();
+
+        for kind in [JoinKind::Inner, JoinKind::Left] {
+            let indexed = index_join(&engine, &rows, kind, "A.flags", &source, "flags", &right_columns)?
+                .expect("index sur flags");
+            let hashed = hash_join(rows.clone(), right_rows.clone(), kind, &["A.flags".to_string()], &["B.flags".to_string()], &right_columns);
+            let fields = |records: &[Record]| records.iter().map(|r| r.fields.clone()).collect::<Vec<_>>();
+            assert_eq!(fields(&indexed), fields(&hashed));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,300 @@
+/**
+ * Analyseur lexical SQL pour FIC Engine.
+ *
+ * Ce fichier découpe le texte d'une requête SQL en tokens positionnés
+ * (ligne, colonne) pour le parser à descente récursive de src/sql/parser.rs.
+ *
+ * Éléments reconnus :
+ * - Identifiants et mots-clés (insensibles à la casse, comparés par le parser)
+ * - Identifiants entre délimiteurs : "NOM", [NOM], `NOM`
+ * - Chaînes littérales 'texte' avec échappement par doublement ('')
+ * - Nombres entiers et décimaux
+ * - Opérateurs : = != <> < > <= >= * , ( ) . ;
+ * - Commentaires : -- jusqu'à la fin de ligne, et /* ... */
+ *
+ * Les erreurs (chaîne non terminée, caractère inconnu) sont des
+ * SqlSyntaxError portant la position exacte, que le client peut utiliser
+ * pour souligner le token fautif.
+ *
+ * Liens avec d'autres modules :
+ * - Utilisé par src/sql/parser.rs
+ * - SqlSyntaxError est exposée par src/sql/server.rs dans les réponses HTTP
+ */
+
+use serde::Serialize;
+
+/// Erreur de syntaxe SQL positionnée dans le texte de la requête
+#[derive(Debug, Clone, Serialize, thiserror::Error)]
+#[error("{message} (ligne {line}, colonne {column})")]
+pub struct SqlSyntaxError {
+    /// Description de l'erreur
+    pub message: String,
+    /// Ligne du token fautif (1-based)
+    pub line: usize,
+    /// Colonne du token fautif (1-based, en caractères)
+    pub column: usize,
+    /// Longueur du token fautif en caractères (0 en fin de requête)
+    pub length: usize,
+}
+
+impl SqlSyntaxError {
+    /**
+     * Crée une erreur de syntaxe à la position d'un token.
+     *
+     * @param message - Description de l'erreur
+     * @param token - Token fautif
+     * @returns SqlSyntaxError - Erreur positionnée
+     */
+    pub fn at(message: impl Into<String>, token: &Token) -> Self {
+        Self {
+            message: message.into(),
+            line: token.line,
+            column: token.column,
+            length: token.length,
+        }
+    }
+}
+
+/// Nature d'un token SQL
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    /// Identifiant ou mot-clé non délimité
+    Word(String),
+    /// Identifiant délimité ("NOM", [NOM] ou `NOM`)
+    QuotedIdent(String),
+    /// Chaîne littérale (quotes retirées, '' remplacé par ')
+    Str(String),
+    /// Nombre littéral (texte brut, signe exclu)
+    Number(String),
+    /// Opérateur de comparaison (=, !=, <>, <, >, <=, >=)
+    Op(String),
+    Star,
+    Comma,
+    Dot,
+    LParen,
+    RParen,
+    Semicolon,
+    /// Fin de la requête
+    Eof,
+}
+
+/// Token SQL avec sa position dans le texte source
+#[derive(Debug, Clone)]
+pub struct Token {
+    /// Nature et contenu du token
+    pub kind: TokenKind,
+    /// Ligne (1-based)
+    pub line: usize,
+    /// Colonne (1-based, en caractères)
+    pub column: usize,
+    /// Longueur dans le texte source (en caractères)
+    pub length: usize,
+}
+
+impl Token {
+    /**
+     * Indique si le token est le mot-clé donné (insensible à la casse).
+     *
+     * Les identifiants délimités ne sont jamais des mots-clés.
+     */
+    pub fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(&self.kind, TokenKind::Word(w) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    /// Représentation lisible pour les messages d'erreur
+    pub fn describe(&self) -> String {
+        match &self.kind {
+            TokenKind::Word(w) => w.clone(),
+            TokenKind::QuotedIdent(w) => format!("\"{}\"", w),
+            TokenKind::Str(s) => format!("'{}'", s),
+            TokenKind::Number(n) | TokenKind::Op(n) => n.clone(),
+            TokenKind::Star => "*".to_string(),
+            TokenKind::Comma => ",".to_string(),
+            TokenKind::Dot => ".".to_string(),
+            TokenKind::LParen => "(".to_string(),
+            TokenKind::RParen => ")".to_string(),
+            TokenKind::Semicolon => ";".to_string(),
+            TokenKind::Eof => "fin de requête".to_string(),
+        }
+    }
+}
+
+/// Curseur de lecture sur le texte source avec suivi de la position
+struct Cursor {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Cursor {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.get(self.pos).copied()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+}
+
+/**
+ * Découpe une requête SQL en tokens.
+ *
+ * Le dernier token retourné est toujours TokenKind::Eof, positionné
+ * juste après la fin du texte.
+ *
+ * @param sql - Texte de la requête
+ * @returns Result<Vec<Token>, SqlSyntaxError> - Tokens ou erreur positionnée
+ *
+ * Effets de bord : Aucun
+ */
+pub fn tokenize(sql: &str) -> Result<Vec<Token>, SqlSyntaxError> {
+    let mut cursor = Cursor { chars: sql.chars().collect(), pos: 0, line: 1, column: 1 };
+    let mut tokens = Vec::new();
+
+    while let Some(c) = cursor.peek() {
+        let (line, column, start) = (cursor.line, cursor.column, cursor.pos);
+        let error = |message: String, length: usize| SqlSyntaxError { message, line, column, length };
+
+        let kind = match c {
+            _ if c.is_whitespace() => {
+                cursor.bump();
+                continue;
+            }
+            '-' if cursor.peek_at(1) == Some('-') => {
+                while cursor.peek().is_some_and(|ch| ch != '\n') {
+                    cursor.bump();
+                }
+                continue;
+            }
+            '/' if cursor.peek_at(1) == Some('*') => {
+                cursor.bump();
+                cursor.bump();
+                loop {
+                    match cursor.bump() {
+                        Some('*') if cursor.peek() == Some('/') => {
+                            cursor.bump();
+                            break;
+                        }
+                        Some(_) => {}
+                        None => return Err(error("Commentaire /* non terminé".to_string(), 2)),
+                    }
+                }
+                continue;
+            }
+            '\'' => TokenKind::Str(read_delimited(&mut cursor, '\'', '\'')
+                .ok_or_else(|| error("Chaîne non terminée".to_string(), 1))?),
+            '"' => TokenKind::QuotedIdent(read_delimited(&mut cursor, '"', '"')
+                .ok_or_else(|| error("Identifiant \"...\" non terminé".to_string(), 1))?),
+            '`' => TokenKind::QuotedIdent(read_delimited(&mut cursor, '`', '`')
+                .ok_or_else(|| error("Identifiant `...` non terminé".to_string(), 1))?),
+            '[' => TokenKind::QuotedIdent(read_delimited(&mut cursor, '[', ']')
+                .ok_or_else(|| error("Identifiant [...] non terminé".to_string(), 1))?),
+            '=' => {
+                cursor.bump();
+                TokenKind::Op("=".to_string())
+            }
+            '!' | '<' | '>' => {
+                cursor.bump();
+                match (c, cursor.peek()) {
+                    ('!', Some('=')) | ('<', Some('=')) | ('>', Some('=')) | ('<', Some('>')) => {
+                        let second = cursor.bump().unwrap_or_default();
+                        TokenKind::Op(format!("{}{}", c, second))
+                    }
+                    ('!', _) => return Err(error("Opérateur inconnu: !".to_string(), 1)),
+                    _ => TokenKind::Op(c.to_string()),
+                }
+            }
+            '*' => {
+                cursor.bump();
+                TokenKind::Star
+            }
+            ',' => {
+                cursor.bump();
+                TokenKind::Comma
+            }
+            '(' => {
+                cursor.bump();
+                TokenKind::LParen
+            }
+            ')' => {
+                cursor.bump();
+                TokenKind::RParen
+            }
+            ';' => {
+                cursor.bump();
+                TokenKind::Semicolon
+            }
+            '.' if !cursor.peek_at(1).is_some_and(|d| d.is_ascii_digit()) => {
+                cursor.bump();
+                TokenKind::Dot
+            }
+            _ if c.is_ascii_digit() || c == '.' => {
+                let mut number = String::new();
+                while let Some(ch) = cursor.peek().filter(|ch| ch.is_ascii_digit() || *ch == '.') {
+                    number.push(ch);
+                    cursor.bump();
+                }
+                TokenKind::Number(number)
+            }
+            _ if c.is_alphabetic() || c == '_' => {
+                let mut word = String::new();
+                while let Some(ch) = cursor.peek().filter(|ch| ch.is_alphanumeric() || *ch == '_') {
+                    word.push(ch);
+                    cursor.bump();
+                }
+                TokenKind::Word(word)
+            }
+            '-' | '+' => {
+                // Le signe est traité par le parser (valeurs littérales)
+                cursor.bump();
+                TokenKind::Op(c.to_string())
+            }
+            _ => return Err(error(format!("Caractère inattendu: '{}'", c), 1)),
+        };
+
+        tokens.push(Token { kind, line, column, length: cursor.pos - start });
+    }
+
+    tokens.push(Token { kind: TokenKind::Eof, line: cursor.line, column: cursor.column, length: 0 });
+    Ok(tokens)
+}
+
+/**
+ * Lit un élément délimité (chaîne ou identifiant) à partir du délimiteur ouvrant.
+ *
+ * Un délimiteur fermant doublé est interprété comme un caractère littéral
+ * ('' dans une chaîne, "" dans un identifiant, ]] entre crochets).
+ *
+ * @returns Option<String> - Contenu sans délimiteurs, None si non terminé
+ */
+fn read_delimited(cursor: &mut Cursor, open: char, close: char) -> Option<String> {
+    debug_assert_eq!(cursor.peek(), Some(open));
+    cursor.bump();
+    let mut value = String::new();
+    loop {
+        match cursor.bump()? {
+            ch if ch == close => {
+                if cursor.peek() == Some(close) {
+                    cursor.bump();
+                    value.push(close);
+                } else {
+                    return Some(value);
+                }
+            }
+            ch => value.push(ch),
+        }
+    }
+}
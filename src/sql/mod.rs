@@ -6,23 +6,33 @@
  * l'accès via ODBC pour interroger d'autres bases de données.
  * 
  * Structure :
- * - parser.rs : Parser SQL simple pour SELECT, INSERT, UPDATE, DELETE
+ * - lexer.rs : Découpage des requêtes en tokens positionnés (ligne, colonne)
+ * - parser.rs : Parser SQL à descente récursive pour SELECT, INSERT, UPDATE, DELETE
  * - executor.rs : Exécuteur SQL qui traduit les requêtes en opérations StorageEngine
+ * - aggregate.rs : Agrégats (COUNT, SUM, AVG, MIN, MAX), GROUP BY et HAVING
+ * - join.rs : Jointures INNER / LEFT JOIN (par index .ndx ou par hachage)
+ * - planner.rs : Choix d'un parcours par index .ndx selon la clause WHERE
  * - server.rs : Handlers HTTP pour les endpoints SQL
  * - odbc.rs : Support ODBC pour interroger d'autres bases de données
  * 
  * Exports :
  * - SqlParser : Parser de requêtes SQL
+ * - SqlSyntaxError : Erreur de syntaxe positionnée
  * - SqlExecutor : Exécuteur de requêtes SQL
  * - SqlServer : Serveur SQL (structure)
  * - OdbcResult : Résultat de requête ODBC
  */
 
+pub mod lexer;
 pub mod parser;
 pub mod executor;
+pub mod aggregate;
+pub mod join;
+pub mod planner;
 pub mod server;
 pub mod odbc;
 
+pub use lexer::SqlSyntaxError;
 pub use parser::SqlParser;
 pub use executor::SqlExecutor;
 pub use server::SqlServer;
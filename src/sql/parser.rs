@@ -1,52 +1,198 @@
 /**
- * Parser SQL simple pour FIC Engine.
+ * Parser SQL pour FIC Engine.
  * 
- * Ce fichier contient un parser SQL basique qui supporte les requêtes
- * SELECT, INSERT, UPDATE et DELETE. Le parser utilise des expressions
- * régulières pour analyser la syntaxe SQL et construit des structures
- * de données typées représentant la requête.
+ * Ce fichier contient le parser SQL qui supporte les requêtes SELECT,
+ * INSERT, UPDATE et DELETE. Le texte est d'abord découpé en tokens par
+ * src/sql/lexer.rs, puis analysé par descente récursive pour construire
+ * des structures de données typées représentant la requête.
  * 
  * Fonctionnalités supportées :
- * - SELECT avec colonnes, WHERE, LIMIT, OFFSET
+ * - SELECT avec colonnes et alias, WHERE, GROUP BY, HAVING, ORDER BY,
+ *   LIMIT, OFFSET
+ * - Jointures [INNER] JOIN et LEFT [OUTER] JOIN avec ON et alias de table
+ * - Agrégats : COUNT(*), COUNT([DISTINCT] x), SUM, AVG, MIN, MAX
  * - INSERT avec colonnes et valeurs
  * - UPDATE avec SET et WHERE
  * - DELETE avec WHERE
+ * - BEGIN, COMMIT, ROLLBACK (transactions, gérées par src/sql/server.rs)
+ * - REINDEX table (reconstruction des index .ndx)
+ * - WHERE : AND, OR, NOT, parenthèses, IN (...), BETWEEN, IS [NOT] NULL,
+ *   LIKE, mots-clés insensibles à la casse
+ * - Chaînes avec échappement (''), identifiants délimités ("NOM", [NOM],
+ *   `NOM`), commentaires (-- et /* */)
+ * 
+ * Les erreurs de syntaxe sont des SqlSyntaxError indiquant la ligne et la
+ * colonne du token fautif.
  * 
  * Limitations :
- * - Parser basique (pas de sous-requêtes, JOIN, etc.)
- * - Support limité des opérateurs (AND uniquement dans WHERE)
+ * - Pas de sous-requêtes
  * 
  * Liens avec d'autres modules :
+ * - Utilise src/sql/lexer.rs pour le découpage en tokens
  * - Utilisé par src/sql/executor.rs pour exécuter les requêtes
  * - Utilisé par src/sql/server.rs pour parser les requêtes HTTP
  */
 
+use crate::sql::lexer::{tokenize, SqlSyntaxError, Token, TokenKind};
+use crate::storage::decimal::Decimal;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
 /// Représente une requête SQL parsée sous forme d'arbre syntaxique
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SqlStatement {
-    /// Requête SELECT
-    Select(SelectStatement),
+    /// Requête SELECT (boxée : bien plus volumineuse que les autres variantes)
+    Select(Box<SelectStatement>),
     /// Requête INSERT
     Insert(InsertStatement),
     /// Requête UPDATE
     Update(UpdateStatement),
     /// Requête DELETE
     Delete(DeleteStatement),
+    /// BEGIN [TRANSACTION | WORK] ou START TRANSACTION
+    Begin,
+    /// COMMIT [TRANSACTION | WORK]
+    Commit,
+    /// ROLLBACK [TRANSACTION | WORK]
+    Rollback,
+    /// REINDEX table : reconstruit les index .ndx de la table
+    Reindex(String),
 }
 
 /// Requête SELECT
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SelectStatement {
     pub table: String,
-    pub columns: Vec<String>, // Vide = SELECT *
-    pub where_clause: Option<WhereClause>,
+    /// Alias de la table principale (FROM CLIENT c)
+    pub table_alias: Option<String>,
+    pub joins: Vec<JoinClause>,
+    pub columns: Vec<SelectItem>, // Vide = SELECT *
+    pub where_clause: Option<Expr>,
+    pub group_by: Vec<String>,
+    /// Condition sur les groupes ; les agrégats y sont référencés par leur nom (ex: "COUNT(*)")
+    pub having: Option<Expr>,
+    /// Agrégats utilisés par HAVING ou ORDER BY sans figurer dans les colonnes
+    pub extra_aggregates: Vec<Aggregate>,
+    pub order_by: Vec<OrderByItem>, // Vide = ordre physique du .fic
     pub limit: Option<u32>,
     pub offset: Option<u32>,
 }
 
+impl SelectStatement {
+    /// Indique si la requête regroupe les enregistrements (agrégats, GROUP BY ou HAVING)
+    pub fn is_aggregate(&self) -> bool {
+        !self.group_by.is_empty()
+            || self.having.is_some()
+            || self.columns.iter().any(|c| matches!(c.expr, SelectExpr::Aggregate(_)))
+    }
+}
+
+/// Jointure d'une table supplémentaire : [INNER | LEFT] JOIN table [alias] ON ...
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JoinClause {
+    pub kind: JoinKind,
+    pub table: String,
+    pub alias: Option<String>,
+    /// Égalités de colonnes combinées par AND
+    pub on: Vec<JoinCondition>,
+}
+
+impl JoinClause {
+    /// Nom utilisé pour qualifier les colonnes de la table (alias ou nom)
+    pub fn qualifier(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.table)
+    }
+}
+
+/// Type de jointure
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum JoinKind {
+    Inner,
+    Left,
+}
+
+/// Condition de jointure : égalité entre deux colonnes (a.x = b.y)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JoinCondition {
+    pub left: String,
+    pub right: String,
+}
+
+/// Élément de la liste de colonnes d'un SELECT
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectItem {
+    pub expr: SelectExpr,
+    /// Alias (AS nom)
+    pub alias: Option<String>,
+}
+
+impl SelectItem {
+    /// Nom de la colonne dans le résultat : alias, sinon colonne ou agrégat
+    pub fn output_name(&self) -> String {
+        match (&self.alias, &self.expr) {
+            (Some(alias), _) => alias.clone(),
+            (None, SelectExpr::Column(column)) => column.clone(),
+            (None, SelectExpr::Aggregate(aggregate)) => aggregate.name(),
+        }
+    }
+}
+
+/// Expression projetée par un SELECT
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SelectExpr {
+    Column(String),
+    Aggregate(Aggregate),
+}
+
+/// Appel de fonction d'agrégat : COUNT(*), COUNT(DISTINCT x), SUM(x)...
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Aggregate {
+    pub function: AggregateFunction,
+    /// Colonne agrégée (None pour COUNT(*))
+    pub column: Option<String>,
+    pub distinct: bool,
+}
+
+impl Aggregate {
+    /// Nom canonique de l'agrégat, utilisé comme nom de colonne par défaut
+    pub fn name(&self) -> String {
+        let function = match self.function {
+            AggregateFunction::Count => "COUNT",
+            AggregateFunction::Sum => "SUM",
+            AggregateFunction::Avg => "AVG",
+            AggregateFunction::Min => "MIN",
+            AggregateFunction::Max => "MAX",
+        };
+        let argument = self.column.as_deref().unwrap_or("*");
+        if self.distinct {
+            format!("{}(DISTINCT {})", function, argument)
+        } else {
+            format!("{}({})", function, argument)
+        }
+    }
+}
+
+/// Fonctions d'agrégat supportées
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AggregateFunction {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+/// Critère de tri d'une clause ORDER BY
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderByItem {
+    pub column: String,
+    /// true pour DESC
+    pub descending: bool,
+    /// NULLS FIRST / NULLS LAST explicite ; par défaut les NULL sont les
+    /// plus petites valeurs (en tête en ASC, en fin en DESC)
+    pub nulls_first: Option<bool>,
+}
+
 /// Requête INSERT
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InsertStatement {
@@ -60,23 +206,48 @@ pub struct InsertStatement {
 pub struct UpdateStatement {
     pub table: String,
     pub set_clauses: Vec<SetClause>,
-    pub where_clause: Option<WhereClause>,
+    pub where_clause: Option<Expr>,
 }
 
 /// Requête DELETE
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeleteStatement {
     pub table: String,
-    pub where_clause: Option<WhereClause>,
+    pub where_clause: Option<Expr>,
 }
 
-/// Clause WHERE
+/// Expression booléenne d'une clause WHERE
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct WhereClause {
-    pub conditions: Vec<Condition>,
+pub enum Expr {
+    /// Comparaison simple : colonne <opérateur> valeur
+    Comparison(Condition),
+    /// Conjonction de deux expressions
+    And(Box<Expr>, Box<Expr>),
+    /// Disjonction de deux expressions
+    Or(Box<Expr>, Box<Expr>),
+    /// Négation d'une expression
+    Not(Box<Expr>),
+    /// colonne [NOT] IN (v1, v2, ...)
+    In {
+        column: String,
+        values: Vec<SqlValue>,
+        negated: bool,
+    },
+    /// colonne [NOT] BETWEEN bas AND haut (bornes incluses)
+    Between {
+        column: String,
+        low: SqlValue,
+        high: SqlValue,
+        negated: bool,
+    },
+    /// colonne IS [NOT] NULL
+    IsNull {
+        column: String,
+        negated: bool,
+    },
 }
 
-/// Condition dans WHERE
+/// Condition de comparaison dans WHERE
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Condition {
     pub column: String,
@@ -108,234 +279,845 @@ pub struct SetClause {
 pub enum SqlValue {
     String(String),
     Integer(i64),
+    /// Nombre en notation exponentielle (1.5e3)
     Float(f64),
+    /// Nombre à virgule, gardé exact (texte décimal, ex. "0.10")
+    Decimal(String),
     Boolean(bool),
     Null,
 }
 
-/// Parser SQL simple utilisant des expressions régulières
+/// Parser SQL à descente récursive
 pub struct SqlParser;
 
+/// Résultat interne du parser (erreurs positionnées)
+type ParseResult<T> = std::result::Result<T, SqlSyntaxError>;
+
+/// Mots réservés qui ne peuvent pas servir d'identifiant sans délimiteurs
+const RESERVED_KEYWORDS: &[&str] = &[
+    "SELECT", "FROM", "WHERE", "AND", "OR", "NOT", "IN", "IS", "NULL", "LIKE",
+    "BETWEEN", "ORDER", "BY", "GROUP", "HAVING", "AS", "DISTINCT",
+    "JOIN", "INNER", "LEFT", "RIGHT", "FULL", "CROSS", "OUTER", "ON", "LIMIT", "OFFSET", "INSERT", "INTO", "VALUES", "UPDATE", "SET",
+    "DELETE", "TRUE", "FALSE",
+];
+
 impl SqlParser {
     /**
      * Parse une requête SQL et retourne une structure typée.
      * 
      * Analyse la requête SQL fournie et la convertit en structure
      * SqlStatement. Supporte SELECT, INSERT, UPDATE et DELETE.
+     * Un point-virgule final est accepté.
      * 
      * @param sql - Requête SQL à parser
      * @returns Result<SqlStatement> - Requête parsée ou erreur de syntaxe
+     *   (SqlSyntaxError récupérable via downcast_ref)
      * 
      * Effets de bord : Aucun
      */
     pub fn parse(sql: &str) -> Result<SqlStatement> {
-        // Nettoyer la requête : supprimer les espaces et le point-virgule final
-        let sql = sql.trim().trim_end_matches(';').trim();
-        
-        if sql.is_empty() {
-            anyhow::bail!("Requête SQL vide");
-        }
-
-        let upper = sql.to_uppercase();
-        
-        if upper.starts_with("SELECT") {
-            Self::parse_select(sql)
-        } else if upper.starts_with("INSERT") {
-            Self::parse_insert(sql)
-        } else if upper.starts_with("UPDATE") {
-            Self::parse_update(sql)
-        } else if upper.starts_with("DELETE") {
-            Self::parse_delete(sql)
+        let tokens = tokenize(sql)?;
+        let mut parser = Parser { tokens, pos: 0, aggregates_allowed: false, extra_aggregates: Vec::new() };
+        Ok(parser.parse_statement()?)
+    }
+}
+
+/// État du parser : tokens de la requête et position courante
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Autorise les agrégats dans les conditions (HAVING, ORDER BY)
+    aggregates_allowed: bool,
+    /// Agrégats rencontrés dans HAVING / ORDER BY
+    extra_aggregates: Vec<Aggregate>,
+}
+
+impl Parser {
+    /// Token courant (toujours défini : le dernier token est Eof)
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos.min(self.tokens.len() - 1)]
+    }
+
+    /// Token suivant le token courant
+    fn peek_next(&self) -> &Token {
+        &self.tokens[(self.pos + 1).min(self.tokens.len() - 1)]
+    }
+
+    /// Consomme et retourne le token courant (reste sur Eof en fin de requête)
+    fn advance(&mut self) -> Token {
+        let token = self.peek().clone();
+        if self.pos < self.tokens.len() - 1 {
+            self.pos += 1;
+        }
+        token
+    }
+
+    /// Erreur positionnée sur le token courant
+    fn error<T>(&self, message: impl Into<String>) -> ParseResult<T> {
+        Err(SqlSyntaxError::at(message, self.peek()))
+    }
+
+    /// Consomme le mot-clé s'il est le token courant
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if self.peek().is_keyword(keyword) {
+            self.advance();
+            true
         } else {
-            anyhow::bail!("Type de requête non supporté: {}", sql)
-        }
-    }
-
-    fn parse_select(sql: &str) -> Result<SqlStatement> {
-        // Parser simple pour SELECT * FROM table [WHERE ...] [LIMIT ...] [OFFSET ...]
-        // Note: regex plus flexible pour gérer les cas simples
-        let re = regex::Regex::new(
-            r"(?i)^SELECT\s+(.+?)\s+FROM\s+(\w+)(?:\s+WHERE\s+(.+?))?(?:\s+LIMIT\s+(\d+))?(?:\s+OFFSET\s+(\d+))?$"
-        ).map_err(|e| anyhow::anyhow!("Erreur de regex: {}", e))?;
-        
-        if let Some(caps) = re.captures(sql) {
-            let columns_str = caps.get(1).unwrap().as_str().trim();
-            let table = caps.get(2).unwrap().as_str().to_string();
-            let columns = if columns_str == "*" {
-                Vec::new()
-            } else {
-                columns_str.split(',').map(|s| s.trim().to_string()).collect()
-            };
-            
-            let where_clause = caps.get(3).map(|m| Self::parse_where(m.as_str())).transpose()?;
-            let limit = caps.get(4).and_then(|m| m.as_str().parse().ok());
-            let offset = caps.get(5).and_then(|m| m.as_str().parse().ok());
-            
-            Ok(SqlStatement::Select(SelectStatement {
-                table,
-                columns,
-                where_clause,
-                limit,
-                offset,
-            }))
+            false
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> ParseResult<()> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            self.error(format!("{} attendu, '{}' trouvé", keyword, self.peek().describe()))
+        }
+    }
+
+    /// Consomme le token s'il est du type donné
+    fn eat(&mut self, kind: &TokenKind) -> bool {
+        if &self.peek().kind == kind {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, kind: TokenKind) -> ParseResult<()> {
+        if self.eat(&kind) {
+            Ok(())
         } else {
-            anyhow::bail!("Syntaxe SELECT invalide: {}", sql)
-        }
-    }
-
-    fn parse_insert(sql: &str) -> Result<SqlStatement> {
-        // INSERT INTO table (col1, col2) VALUES (val1, val2)
-        let re = regex::Regex::new(
-            r"(?i)^INSERT\s+INTO\s+(\w+)\s*\((.+?)\)\s+VALUES\s*\((.+?)\)$"
-        ).map_err(|e| anyhow::anyhow!("Erreur de regex: {}", e))?;
-        
-        if let Some(caps) = re.captures(sql) {
-            let table = caps.get(1).unwrap().as_str().to_string();
-            let columns: Vec<String> = caps.get(2).unwrap()
-                .as_str()
-                .split(',')
-                .map(|s| s.trim().to_string())
-                .collect();
-            let values_str = caps.get(3).unwrap().as_str();
-            let values = Self::parse_values(values_str)?;
-            
-            if columns.len() != values.len() {
-                anyhow::bail!("Le nombre de colonnes ne correspond pas au nombre de valeurs");
+            let expected = Token { kind, line: 0, column: 0, length: 0 }.describe();
+            self.error(format!("'{}' attendu, '{}' trouvé", expected, self.peek().describe()))
+        }
+    }
+
+    /// statement := (select | insert | update | delete) [';']
+    fn parse_statement(&mut self) -> ParseResult<SqlStatement> {
+        let statement = if self.peek().kind == TokenKind::Eof {
+            return self.error("Requête SQL vide");
+        } else if self.eat_keyword("SELECT") {
+            SqlStatement::Select(Box::new(self.parse_select()?))
+        } else if self.eat_keyword("INSERT") {
+            SqlStatement::Insert(self.parse_insert()?)
+        } else if self.eat_keyword("UPDATE") {
+            SqlStatement::Update(self.parse_update()?)
+        } else if self.eat_keyword("DELETE") {
+            SqlStatement::Delete(self.parse_delete()?)
+        } else if self.eat_keyword("BEGIN") {
+            self.eat_transaction_keyword();
+            SqlStatement::Begin
+        } else if self.eat_keyword("START") {
+            self.expect_keyword("TRANSACTION")?;
+            SqlStatement::Begin
+        } else if self.eat_keyword("COMMIT") {
+            self.eat_transaction_keyword();
+            SqlStatement::Commit
+        } else if self.eat_keyword("ROLLBACK") {
+            self.eat_transaction_keyword();
+            SqlStatement::Rollback
+        } else if self.eat_keyword("REINDEX") {
+            SqlStatement::Reindex(self.parse_identifier()?)
+        } else {
+            return self.error(format!("Type de requête non supporté: {}", self.peek().describe()));
+        };
+
+        self.eat(&TokenKind::Semicolon);
+        if self.peek().kind != TokenKind::Eof {
+            return self.error(format!("Token inattendu: '{}'", self.peek().describe()));
+        }
+        Ok(statement)
+    }
+
+    /// Mot-clé optionnel après BEGIN / COMMIT / ROLLBACK
+    fn eat_transaction_keyword(&mut self) {
+        if !self.eat_keyword("TRANSACTION") {
+            self.eat_keyword("WORK");
+        }
+    }
+
+    /// SELECT (* | élément, ...) FROM table [alias] [jointure ...] [WHERE expr] [GROUP BY col, ...]
+    /// [HAVING expr] [ORDER BY ...] [LIMIT n] [OFFSET n]
+    fn parse_select(&mut self) -> ParseResult<SelectStatement> {
+        let columns = if self.eat(&TokenKind::Star) {
+            Vec::new()
+        } else {
+            let mut columns = vec![self.parse_select_item()?];
+            while self.eat(&TokenKind::Comma) {
+                columns.push(self.parse_select_item()?);
             }
-            
-            Ok(SqlStatement::Insert(InsertStatement {
-                table,
-                columns,
-                values,
-            }))
+            columns
+        };
+
+        self.expect_keyword("FROM")?;
+        let table = self.parse_identifier()?;
+        let table_alias = self.parse_optional_alias()?;
+
+        let mut joins = Vec::new();
+        while let Some(join) = self.parse_optional_join()? {
+            joins.push(join);
+        }
+
+        let where_clause = self.parse_optional_where()?;
+
+        let mut group_by = Vec::new();
+        if self.eat_keyword("GROUP") {
+            self.expect_keyword("BY")?;
+            group_by.push(self.parse_column_ref()?);
+            while self.eat(&TokenKind::Comma) {
+                group_by.push(self.parse_column_ref()?);
+            }
+        }
+
+        // Les agrégats sont autorisés dans HAVING et ORDER BY
+        self.aggregates_allowed = true;
+        let having = if self.eat_keyword("HAVING") {
+            Some(self.parse_or()?)
         } else {
-            anyhow::bail!("Syntaxe INSERT invalide: {}", sql)
-        }
-    }
-
-    fn parse_update(sql: &str) -> Result<SqlStatement> {
-        // UPDATE table SET col1=val1, col2=val2 [WHERE ...]
-        let re = regex::Regex::new(
-            r"(?i)^UPDATE\s+(\w+)\s+SET\s+(.+?)(?:\s+WHERE\s+(.+?))?$"
-        ).map_err(|e| anyhow::anyhow!("Erreur de regex: {}", e))?;
-        
-        if let Some(caps) = re.captures(sql) {
-            let table = caps.get(1).unwrap().as_str().to_string();
-            let set_str = caps.get(2).unwrap().as_str();
-            let set_clauses = Self::parse_set_clauses(set_str)?;
-            let where_clause = caps.get(3).map(|m| Self::parse_where(m.as_str())).transpose()?;
-            
-            Ok(SqlStatement::Update(UpdateStatement {
-                table,
-                set_clauses,
-                where_clause,
-            }))
+            None
+        };
+
+        let mut order_by = Vec::new();
+        if self.eat_keyword("ORDER") {
+            self.expect_keyword("BY")?;
+            order_by.push(self.parse_order_by_item()?);
+            while self.eat(&TokenKind::Comma) {
+                order_by.push(self.parse_order_by_item()?);
+            }
+        }
+
+        let mut limit = None;
+        let mut offset = None;
+        if self.eat_keyword("LIMIT") {
+            limit = Some(self.parse_unsigned()?);
+        }
+        if self.eat_keyword("OFFSET") {
+            offset = Some(self.parse_unsigned()?);
+        }
+
+        Ok(SelectStatement {
+            table,
+            table_alias,
+            joins,
+            columns,
+            where_clause,
+            group_by,
+            having,
+            extra_aggregates: std::mem::take(&mut self.extra_aggregates),
+            order_by,
+            limit,
+            offset,
+        })
+    }
+
+    /// élément := (colonne | agrégat) [[AS] alias]
+    fn parse_select_item(&mut self) -> ParseResult<SelectItem> {
+        let expr = match self.try_parse_aggregate()? {
+            Some(aggregate) => SelectExpr::Aggregate(aggregate),
+            None => SelectExpr::Column(self.parse_column_ref()?),
+        };
+
+        let alias = self.parse_optional_alias()?;
+        Ok(SelectItem { expr, alias })
+    }
+
+    /// Alias explicite (AS nom) ou implicite (identifiant qui suit)
+    fn parse_optional_alias(&mut self) -> ParseResult<Option<String>> {
+        let has_alias = self.eat_keyword("AS")
+            || matches!(&self.peek().kind, TokenKind::Word(w) if !is_reserved(w))
+            || matches!(self.peek().kind, TokenKind::QuotedIdent(_));
+        if has_alias {
+            Ok(Some(self.parse_identifier()?))
         } else {
-            anyhow::bail!("Syntaxe UPDATE invalide: {}", sql)
-        }
-    }
-
-    fn parse_delete(sql: &str) -> Result<SqlStatement> {
-        // DELETE FROM table [WHERE ...]
-        let re = regex::Regex::new(
-            r"(?i)^DELETE\s+FROM\s+(\w+)(?:\s+WHERE\s+(.+?))?$"
-        ).map_err(|e| anyhow::anyhow!("Erreur de regex: {}", e))?;
-        
-        if let Some(caps) = re.captures(sql) {
-            let table = caps.get(1).unwrap().as_str().to_string();
-            let where_clause = caps.get(2).map(|m| Self::parse_where(m.as_str())).transpose()?;
-            
-            Ok(SqlStatement::Delete(DeleteStatement {
-                table,
-                where_clause,
-            }))
+            Ok(None)
+        }
+    }
+
+    /// jointure := ([INNER] | LEFT [OUTER]) JOIN table [alias] ON col = col (AND col = col)*
+    fn parse_optional_join(&mut self) -> ParseResult<Option<JoinClause>> {
+        // Mots-clés réservés : jamais lus comme un alias ni ignorés
+        if let Some(kind) = ["RIGHT", "FULL", "CROSS"].into_iter().find(|k| self.peek().is_keyword(k)) {
+            return self.error(format!("Jointure {} non supportée (utilisez [INNER] JOIN ou LEFT [OUTER] JOIN)", kind));
+        }
+        let kind = if self.eat_keyword("LEFT") {
+            self.eat_keyword("OUTER");
+            self.expect_keyword("JOIN")?;
+            JoinKind::Left
+        } else if self.eat_keyword("INNER") {
+            self.expect_keyword("JOIN")?;
+            JoinKind::Inner
+        } else if self.eat_keyword("JOIN") {
+            JoinKind::Inner
         } else {
-            anyhow::bail!("Syntaxe DELETE invalide: {}", sql)
-        }
-    }
-
-    fn parse_where(where_str: &str) -> Result<WhereClause> {
-        // Parser simple: col = val AND col2 = val2
-        let conditions: Result<Vec<Condition>> = where_str
-            .split("AND")
-            .map(|cond| {
-                let cond = cond.trim();
-                // Support: col = val, col != val, col > val, etc.
-                for op in ["!=", ">=", "<=", "=", ">", "<", "LIKE"] {
-                    if cond.contains(op) {
-                        let parts: Vec<&str> = cond.split(op).collect();
-                        if parts.len() == 2 {
-                            let column = parts[0].trim().to_string();
-                            let value_str = parts[1].trim();
-                            let value = Self::parse_value(value_str)?;
-                            let operator = match op {
-                                "=" => ComparisonOperator::Equal,
-                                "!=" => ComparisonOperator::NotEqual,
-                                ">" => ComparisonOperator::GreaterThan,
-                                "<" => ComparisonOperator::LessThan,
-                                ">=" => ComparisonOperator::GreaterThanOrEqual,
-                                "<=" => ComparisonOperator::LessThanOrEqual,
-                                "LIKE" => ComparisonOperator::Like,
-                                _ => ComparisonOperator::Equal,
-                            };
-                            return Ok(Condition { column, operator, value });
-                        }
-                    }
+            return Ok(None);
+        };
+
+        let table = self.parse_identifier()?;
+        let alias = self.parse_optional_alias()?;
+        self.expect_keyword("ON")?;
+
+        let mut on = Vec::new();
+        loop {
+            let left = self.parse_column_ref()?;
+            if !matches!(&self.peek().kind, TokenKind::Op(op) if op == "=") {
+                return self.error(format!(
+                    "Seules les égalités entre colonnes sont supportées dans ON, '{}' trouvé",
+                    self.peek().describe()
+                ));
+            }
+            self.advance();
+            let right = self.parse_column_ref()?;
+            on.push(JoinCondition { left, right });
+            if !self.eat_keyword("AND") {
+                break;
+            }
+        }
+
+        Ok(Some(JoinClause { kind, table, alias, on }))
+    }
+
+    /**
+     * Parse un appel d'agrégat si le token courant en est un.
+     * 
+     * agrégat := (COUNT | SUM | AVG | MIN | MAX) '(' ( '*' | [DISTINCT] colonne ) ')'
+     * Seul COUNT accepte '*'.
+     */
+    fn try_parse_aggregate(&mut self) -> ParseResult<Option<Aggregate>> {
+        let function = match &self.peek().kind {
+            TokenKind::Word(w) if self.peek_next().kind == TokenKind::LParen => {
+                match w.to_ascii_uppercase().as_str() {
+                    "COUNT" => AggregateFunction::Count,
+                    "SUM" => AggregateFunction::Sum,
+                    "AVG" => AggregateFunction::Avg,
+                    "MIN" => AggregateFunction::Min,
+                    "MAX" => AggregateFunction::Max,
+                    _ => return self.error(format!("Fonction inconnue: {}", w)),
                 }
-                anyhow::bail!("Condition invalide: {}", cond)
-            })
-            .collect();
-        
-        Ok(WhereClause {
-            conditions: conditions?,
-        })
+            }
+            _ => return Ok(None),
+        };
+        self.advance();
+        self.advance();
+
+        let aggregate = if function == AggregateFunction::Count && self.eat(&TokenKind::Star) {
+            Aggregate { function, column: None, distinct: false }
+        } else {
+            let distinct = self.eat_keyword("DISTINCT");
+            Aggregate { function, column: Some(self.parse_column_ref()?), distinct }
+        };
+        self.expect(TokenKind::RParen)?;
+        Ok(Some(aggregate))
     }
 
-    fn parse_set_clauses(set_str: &str) -> Result<Vec<SetClause>> {
-        set_str
-            .split(',')
-            .map(|clause| {
-                let clause = clause.trim();
-                let parts: Vec<&str> = clause.split('=').collect();
-                if parts.len() == 2 {
-                    let column = parts[0].trim().to_string();
-                    let value = Self::parse_value(parts[1].trim())?;
-                    Ok(SetClause { column, value })
-                } else {
-                    anyhow::bail!("Clause SET invalide: {}", clause)
+    /**
+     * Opérande d'une condition ou d'un tri : colonne, ou agrégat dans
+     * HAVING / ORDER BY (référencé par son nom canonique).
+     */
+    fn parse_operand(&mut self) -> ParseResult<String> {
+        let token = self.peek().clone();
+        match self.try_parse_aggregate()? {
+            Some(_) if !self.aggregates_allowed => {
+                Err(SqlSyntaxError::at("Fonction d'agrégat interdite dans WHERE (utilisez HAVING)", &token))
+            }
+            Some(aggregate) => {
+                let name = aggregate.name();
+                if !self.extra_aggregates.contains(&aggregate) {
+                    self.extra_aggregates.push(aggregate);
                 }
-            })
-            .collect()
-    }
-
-    fn parse_values(values_str: &str) -> Result<Vec<SqlValue>> {
-        values_str
-            .split(',')
-            .map(|v| Self::parse_value(v.trim()))
-            .collect()
-    }
-
-    fn parse_value(value_str: &str) -> Result<SqlValue> {
-        let value_str = value_str.trim();
-        
-        if value_str.eq_ignore_ascii_case("NULL") {
-            Ok(SqlValue::Null)
-        } else if value_str.eq_ignore_ascii_case("TRUE") {
-            Ok(SqlValue::Boolean(true))
-        } else if value_str.eq_ignore_ascii_case("FALSE") {
-            Ok(SqlValue::Boolean(false))
-        } else if let Some(s) = value_str.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')) {
-            Ok(SqlValue::String(s.to_string()))
-        } else if let Some(s) = value_str.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
-            Ok(SqlValue::String(s.to_string()))
-        } else if let Ok(i) = value_str.parse::<i64>() {
-            Ok(SqlValue::Integer(i))
-        } else if let Ok(f) = value_str.parse::<f64>() {
-            Ok(SqlValue::Float(f))
+                Ok(name)
+            }
+            None => self.parse_column_ref(),
+        }
+    }
+
+    /// critère := colonne [ASC | DESC] [NULLS (FIRST | LAST)]
+    fn parse_order_by_item(&mut self) -> ParseResult<OrderByItem> {
+        let column = self.parse_operand()?;
+        let descending = if self.eat_keyword("DESC") {
+            true
+        } else {
+            self.eat_keyword("ASC");
+            false
+        };
+        let nulls_first = if self.eat_keyword("NULLS") {
+            if self.eat_keyword("FIRST") {
+                Some(true)
+            } else if self.eat_keyword("LAST") {
+                Some(false)
+            } else {
+                return self.error(format!("FIRST ou LAST attendu après NULLS, '{}' trouvé", self.peek().describe()));
+            }
         } else {
-            // Par défaut, traiter comme string
-            Ok(SqlValue::String(value_str.to_string()))
+            None
+        };
+        Ok(OrderByItem { column, descending, nulls_first })
+    }
+
+    /// INSERT INTO table (col, ...) VALUES (val, ...)
+    fn parse_insert(&mut self) -> ParseResult<InsertStatement> {
+        self.expect_keyword("INTO")?;
+        let table = self.parse_identifier()?;
+
+        self.expect(TokenKind::LParen)?;
+        let mut columns = vec![self.parse_identifier()?];
+        while self.eat(&TokenKind::Comma) {
+            columns.push(self.parse_identifier()?);
+        }
+        self.expect(TokenKind::RParen)?;
+
+        self.expect_keyword("VALUES")?;
+        let values_token = self.peek().clone();
+        self.expect(TokenKind::LParen)?;
+        let mut values = vec![self.parse_literal()?];
+        while self.eat(&TokenKind::Comma) {
+            values.push(self.parse_literal()?);
         }
+        self.expect(TokenKind::RParen)?;
+
+        if columns.len() != values.len() {
+            return Err(SqlSyntaxError::at(
+                format!("Le nombre de colonnes ({}) ne correspond pas au nombre de valeurs ({})", columns.len(), values.len()),
+                &values_token,
+            ));
+        }
+
+        Ok(InsertStatement {
+            table,
+            columns,
+            values,
+        })
+    }
+
+    /// UPDATE table SET col = val, ... [WHERE expr]
+    fn parse_update(&mut self) -> ParseResult<UpdateStatement> {
+        let table = self.parse_identifier()?;
+        self.expect_keyword("SET")?;
+
+        let mut set_clauses = Vec::new();
+        loop {
+            let column = self.parse_identifier()?;
+            if !matches!(&self.peek().kind, TokenKind::Op(op) if op == "=") {
+                return self.error(format!("'=' attendu après {}, '{}' trouvé", column, self.peek().describe()));
+            }
+            self.advance();
+            let value = self.parse_literal()?;
+            set_clauses.push(SetClause { column, value });
+            if !self.eat(&TokenKind::Comma) {
+                break;
+            }
+        }
+
+        let where_clause = self.parse_optional_where()?;
+
+        Ok(UpdateStatement {
+            table,
+            set_clauses,
+            where_clause,
+        })
+    }
+
+    /// DELETE FROM table [WHERE expr]
+    fn parse_delete(&mut self) -> ParseResult<DeleteStatement> {
+        self.expect_keyword("FROM")?;
+        let table = self.parse_identifier()?;
+        let where_clause = self.parse_optional_where()?;
+
+        Ok(DeleteStatement {
+            table,
+            where_clause,
+        })
+    }
+
+    fn parse_optional_where(&mut self) -> ParseResult<Option<Expr>> {
+        if self.eat_keyword("WHERE") {
+            Ok(Some(self.parse_or()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Identifiant simple : mot non réservé ou identifiant délimité
+    fn parse_identifier(&mut self) -> ParseResult<String> {
+        match &self.peek().kind {
+            TokenKind::Word(w) if !is_reserved(w) => {
+                let w = w.clone();
+                self.advance();
+                Ok(w)
+            }
+            TokenKind::QuotedIdent(w) => {
+                let w = w.clone();
+                self.advance();
+                Ok(w)
+            }
+            _ => self.error(format!("Identifiant attendu, '{}' trouvé", self.peek().describe())),
+        }
+    }
+
+    /// Référence de colonne, éventuellement qualifiée : col ou table.col
+    fn parse_column_ref(&mut self) -> ParseResult<String> {
+        let mut name = self.parse_identifier()?;
+        while self.eat(&TokenKind::Dot) {
+            name.push('.');
+            name.push_str(&self.parse_identifier()?);
+        }
+        Ok(name)
+    }
+
+    /// Entier positif (LIMIT, OFFSET)
+    fn parse_unsigned(&mut self) -> ParseResult<u32> {
+        if let TokenKind::Number(n) = &self.peek().kind {
+            if let Ok(value) = n.parse::<u32>() {
+                self.advance();
+                return Ok(value);
+            }
+        }
+        self.error(format!("Entier positif attendu, '{}' trouvé", self.peek().describe()))
+    }
+
+    /// or_expr := and_expr (OR and_expr)*
+    fn parse_or(&mut self) -> ParseResult<Expr> {
+        let mut left = self.parse_and()?;
+        while self.eat_keyword("OR") {
+            let right = self.parse_and()?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    /// and_expr := not_expr (AND not_expr)*
+    fn parse_and(&mut self) -> ParseResult<Expr> {
+        let mut left = self.parse_not()?;
+        while self.eat_keyword("AND") {
+            let right = self.parse_not()?;
+            left = Expr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    /// not_expr := NOT not_expr | '(' or_expr ')' | prédicat
+    fn parse_not(&mut self) -> ParseResult<Expr> {
+        if self.eat_keyword("NOT") {
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+        if self.eat(&TokenKind::LParen) {
+            let expr = self.parse_or()?;
+            self.expect(TokenKind::RParen)?;
+            return Ok(expr);
+        }
+        self.parse_predicate()
+    }
+
+    /// prédicat := colonne (op valeur | [NOT] LIKE | [NOT] IN | [NOT] BETWEEN | IS [NOT] NULL)
+    fn parse_predicate(&mut self) -> ParseResult<Expr> {
+        let column = self.parse_operand()?;
+
+        if self.eat_keyword("IS") {
+            let negated = self.eat_keyword("NOT");
+            self.expect_keyword("NULL")?;
+            return Ok(Expr::IsNull { column, negated });
+        }
+
+        let negated = self.eat_keyword("NOT");
+
+        if self.eat_keyword("LIKE") {
+            let value = self.parse_literal()?;
+            let like = Expr::Comparison(Condition { column, operator: ComparisonOperator::Like, value });
+            return Ok(if negated { Expr::Not(Box::new(like)) } else { like });
+        }
+
+        if self.eat_keyword("IN") {
+            self.expect(TokenKind::LParen)?;
+            let mut values = vec![self.parse_literal()?];
+            while self.eat(&TokenKind::Comma) {
+                values.push(self.parse_literal()?);
+            }
+            self.expect(TokenKind::RParen)?;
+            return Ok(Expr::In { column, values, negated });
+        }
+
+        if self.eat_keyword("BETWEEN") {
+            let low = self.parse_literal()?;
+            self.expect_keyword("AND")?;
+            let high = self.parse_literal()?;
+            return Ok(Expr::Between { column, low, high, negated });
+        }
+
+        if negated {
+            return self.error(format!("LIKE, IN ou BETWEEN attendu après NOT, '{}' trouvé", self.peek().describe()));
+        }
+
+        let operator = match &self.peek().kind {
+            TokenKind::Op(op) => match op.as_str() {
+                "=" => ComparisonOperator::Equal,
+                "!=" | "<>" => ComparisonOperator::NotEqual,
+                ">" => ComparisonOperator::GreaterThan,
+                "<" => ComparisonOperator::LessThan,
+                ">=" => ComparisonOperator::GreaterThanOrEqual,
+                "<=" => ComparisonOperator::LessThanOrEqual,
+                _ => return self.error(format!("Opérateur de comparaison attendu, '{}' trouvé", op)),
+            },
+            _ => return self.error(format!("Opérateur attendu après {}, '{}' trouvé", column, self.peek().describe())),
+        };
+        self.advance();
+        let value = self.parse_literal()?;
+        Ok(Expr::Comparison(Condition { column, operator, value }))
+    }
+
+    /**
+     * Valeur littérale : chaîne 'texte', nombre signé, NULL, TRUE, FALSE.
+     * 
+     * "texte" est un identifiant délimité et un mot nu un nom de colonne :
+     * ni l'un ni l'autre n'est accepté comme valeur.
+     */
+    fn parse_literal(&mut self) -> ParseResult<SqlValue> {
+        let token = self.peek().clone();
+        let value = match &token.kind {
+            TokenKind::Str(s) => SqlValue::String(s.clone()),
+            TokenKind::Number(n) => number_value(n, false)
+                .ok_or_else(|| SqlSyntaxError::at(format!("Nombre invalide: {}", n), &token))?,
+            TokenKind::Op(sign) if sign == "-" || sign == "+" => {
+                self.advance();
+                let number_token = self.peek().clone();
+                return match &number_token.kind {
+                    TokenKind::Number(n) => {
+                        let value = number_value(n, sign == "-")
+                            .ok_or_else(|| SqlSyntaxError::at(format!("Nombre invalide: {}", n), &number_token))?;
+                        self.advance();
+                        Ok(value)
+                    }
+                    _ => self.error(format!("Nombre attendu après '{}'", sign)),
+                };
+            }
+            TokenKind::Word(w) if w.eq_ignore_ascii_case("NULL") => SqlValue::Null,
+            TokenKind::Word(w) if w.eq_ignore_ascii_case("TRUE") => SqlValue::Boolean(true),
+            TokenKind::Word(w) if w.eq_ignore_ascii_case("FALSE") => SqlValue::Boolean(false),
+            // Un mot nu ou un identifiant entre guillemets n'est pas une valeur
+            _ => return self.error(format!("Valeur attendue, '{}' trouvé", token.describe())),
+        };
+        self.advance();
+        Ok(value)
     }
 }
 
+/// Indique si un mot est un mot-clé réservé
+fn is_reserved(word: &str) -> bool {
+    RESERVED_KEYWORDS.iter().any(|k| k.eq_ignore_ascii_case(word))
+}
+
+/// Convertit le texte d'un nombre en valeur entière, décimale exacte ou flottante
+fn number_value(text: &str, negative: bool) -> Option<SqlValue> {
+    let text = if negative { format!("-{}", text) } else { text.to_string() };
+    if let Ok(i) = text.parse::<i64>() {
+        Some(SqlValue::Integer(i))
+    } else if let Some(decimal) = Decimal::parse(&text) {
+        Some(SqlValue::Decimal(decimal.to_string()))
+    } else {
+        text.parse::<f64>().ok().map(SqlValue::Float)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn where_of(sql: &str) -> Expr {
+        match SqlParser::parse(sql).unwrap() {
+            SqlStatement::Select(select) => select.where_clause.unwrap(),
+            other => panic!("SELECT attendu: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_where_keywords_are_whole_words() {
+        match where_of("SELECT * FROM PRODUIT WHERE BRAND = 'ANDROS'") {
+            Expr::Comparison(c) => {
+                assert_eq!(c.column, "BRAND");
+                assert!(matches!(c.value, SqlValue::String(ref s) if s == "ANDROS"));
+            }
+            other => panic!("Comparaison attendue: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_where_precedence_and_grouping() {
+        let expr = where_of("select * from CLIENT where STATUT in ('A', 'B') or DATE_CREA is null and not (VILLE = 'Paris')");
+        match expr {
+            Expr::Or(left, right) => {
+                assert!(matches!(*left, Expr::In { negated: false, ref values, .. } if values.len() == 2));
+                match *right {
+                    Expr::And(l, r) => {
+                        assert!(matches!(*l, Expr::IsNull { negated: false, .. }));
+                        assert!(matches!(*r, Expr::Not(_)));
+                    }
+                    other => panic!("AND attendu: {:?}", other),
+                }
+            }
+            other => panic!("OR attendu: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_where_between_and_negations() {
+        let expr = where_of("SELECT * FROM T WHERE AGE NOT BETWEEN -5 AND 10 AND NOM NOT LIKE 'x%' AND CODE IS NOT NULL");
+        match expr {
+            Expr::And(left, right) => {
+                assert!(matches!(*right, Expr::IsNull { negated: true, .. }));
+                match *left {
+                    Expr::And(between, like) => {
+                        assert!(matches!(*between, Expr::Between { negated: true, low: SqlValue::Integer(-5), .. }));
+                        assert!(matches!(*like, Expr::Not(_)));
+                    }
+                    other => panic!("AND attendu: {:?}", other),
+                }
+            }
+            other => panic!("AND attendu: {:?}", other),
+        }
+        assert!(SqlParser::parse("SELECT * FROM T WHERE (A = 1").is_err());
+    }
+
+    #[test]
+    fn test_strings_with_separators_and_escapes() {
+        match SqlParser::parse("INSERT INTO CLIENT (NOM, VILLE) VALUES ('L''Épée, SA', 'FROM = x')").unwrap() {
+            SqlStatement::Insert(insert) => {
+                assert_eq!(insert.columns, vec!["NOM", "VILLE"]);
+                assert!(matches!(&insert.values[0], SqlValue::String(s) if s == "L'Épée, SA"));
+                assert!(matches!(&insert.values[1], SqlValue::String(s) if s == "FROM = x"));
+            }
+            other => panic!("INSERT attendu: {:?}", other),
+        }
+        match SqlParser::parse("UPDATE CLIENT SET NOM = 'a=b, c', [CODE POSTAL] = '75001' WHERE ID = 3").unwrap() {
+            SqlStatement::Update(update) => {
+                assert_eq!(update.set_clauses.len(), 2);
+                assert_eq!(update.set_clauses[1].column, "CODE POSTAL");
+            }
+            other => panic!("UPDATE attendu: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_quoted_identifiers_and_comments() {
+        let sql = "-- liste des clients\nSELECT \"NOM\", `VILLE` /* commentaire */ FROM [CLIENT] LIMIT 5;";
+        match SqlParser::parse(sql).unwrap() {
+            SqlStatement::Select(select) => {
+                assert_eq!(select.table, "CLIENT");
+                let columns: Vec<String> = select.columns.iter().map(|c| c.output_name()).collect();
+                assert_eq!(columns, vec!["NOM", "VILLE"]);
+                assert_eq!(select.limit, Some(5));
+            }
+            other => panic!("SELECT attendu: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_syntax_error_position() {
+        let err = SqlParser::parse("SELECT *\nFROM CLIENT\nWHERE NOM = 'x' AND").unwrap_err();
+        let syntax = err.downcast_ref::<SqlSyntaxError>().expect("SqlSyntaxError attendue");
+        assert_eq!((syntax.line, syntax.column), (3, 20));
+
+        let err = SqlParser::parse("SELECT * FROM CLIENT WHERE NOM = 'abc").unwrap_err();
+        let syntax = err.downcast_ref::<SqlSyntaxError>().unwrap();
+        assert_eq!((syntax.line, syntax.column), (1, 34));
+    }
+
+    #[test]
+    fn test_identifiers_are_not_values() {
+        for sql in [
+            "SELECT * FROM CLIENT WHERE VILLE = Paris",
+            "SELECT * FROM CLIENT WHERE VILLE = \"Paris\"",
+            "INSERT INTO CLIENT (NOM) VALUES (Dupont)",
+        ] {
+            let err = SqlParser::parse(sql).unwrap_err();
+            let syntax = err.downcast_ref::<SqlSyntaxError>().expect("SqlSyntaxError attendue");
+            assert!(syntax.message.starts_with("Valeur attendue"), "{}: {}", sql, syntax.message);
+        }
+        let err = SqlParser::parse("UPDATE CLIENT SET VILLE = Lyon WHERE IDCLIENT = 1").unwrap_err();
+        let syntax = err.downcast_ref::<SqlSyntaxError>().unwrap();
+        assert_eq!((syntax.line, syntax.column), (1, 27));
+    }
+
+    #[test]
+    fn test_order_by() {
+        let sql = "SELECT NOM FROM CLIENT WHERE ACTIF = 1 ORDER BY VILLE, NOM DESC NULLS FIRST, ID ASC LIMIT 20 OFFSET 40";
+        match SqlParser::parse(sql).unwrap() {
+            SqlStatement::Select(select) => {
+                let order: Vec<(&str, bool, Option<bool>)> = select.order_by
+                    .iter()
+                    .map(|o| (o.column.as_str(), o.descending, o.nulls_first))
+                    .collect();
+                assert_eq!(order, vec![("VILLE", false, None), ("NOM", true, Some(true)), ("ID", false, None)]);
+                assert_eq!((select.limit, select.offset), (Some(20), Some(40)));
+            }
+            other => panic!("SELECT attendu: {:?}", other),
+        }
+        assert!(SqlParser::parse("SELECT * FROM CLIENT ORDER NOM").is_err());
+        assert!(SqlParser::parse("SELECT * FROM CLIENT ORDER BY NOM NULLS").is_err());
+    }
+
+    #[test]
+    fn test_aggregates_group_by_having() {
+        let sql = "SELECT STATUT, COUNT(*) AS NB, sum(MONTANT), COUNT(DISTINCT CLIENT) clients FROM FACTURE \
+                   WHERE ANNEE = 2024 GROUP BY STATUT HAVING COUNT(*) > 1 AND AVG(MONTANT) >= 10 ORDER BY NB DESC";
+        match SqlParser::parse(sql).unwrap() {
+            SqlStatement::Select(select) => {
+                assert!(select.is_aggregate());
+                let columns: Vec<String> = select.columns.iter().map(|c| c.output_name()).collect();
+                assert_eq!(columns, vec!["STATUT", "NB", "SUM(MONTANT)", "clients"]);
+                assert_eq!(select.group_by, vec!["STATUT"]);
+                let extra: Vec<String> = select.extra_aggregates.iter().map(|a| a.name()).collect();
+                assert_eq!(extra, vec!["COUNT(*)", "AVG(MONTANT)"]);
+                assert_eq!(select.order_by[0].column, "NB");
+            }
+            other => panic!("SELECT attendu: {:?}", other),
+        }
+
+        assert!(SqlParser::parse("SELECT * FROM FACTURE WHERE COUNT(*) > 1").is_err());
+        assert!(SqlParser::parse("SELECT SUM(*) FROM FACTURE").is_err());
+    }
+
+    #[test]
+    fn test_joins_with_aliases() {
+        let sql = "SELECT c.NOM, co.DATE, l.QTE FROM CLIENT c \
+                   INNER JOIN COMMANDE AS co ON co.IDCLIENT = c.IDCLIENT \
+                   LEFT OUTER JOIN LIGNE l ON l.IDCOMMANDE = co.IDCOMMANDE AND l.ANNEE = co.ANNEE \
+                   WHERE c.VILLE = 'Lyon'";
+        match SqlParser::parse(sql).unwrap() {
+            SqlStatement::Select(select) => {
+                assert_eq!(select.table_alias.as_deref(), Some("c"));
+                assert_eq!(select.joins.len(), 2);
+                assert_eq!(select.joins[0].kind, JoinKind::Inner);
+                assert_eq!(select.joins[0].qualifier(), "co");
+                assert_eq!(select.joins[1].kind, JoinKind::Left);
+                assert_eq!(select.joins[1].on.len(), 2);
+                assert_eq!(select.joins[1].on[1].right, "co.ANNEE");
+                assert_eq!(select.columns[1].output_name(), "co.DATE");
+            }
+            other => panic!("SELECT attendu: {:?}", other),
+        }
+        assert!(SqlParser::parse("SELECT * FROM A JOIN B ON A.X > B.X").is_err());
+        assert!(SqlParser::parse("SELECT * FROM A JOIN B").is_err());
+
+        for sql in ["SELECT * FROM A RIGHT JOIN B ON A.X = B.X", "SELECT * FROM A a FULL OUTER JOIN B ON a.X = B.X", "SELECT * FROM A CROSS JOIN B"] {
+            let err = SqlParser::parse(sql).unwrap_err();
+            let syntax = err.downcast_ref::<SqlSyntaxError>().expect("SqlSyntaxError attendue");
+            assert!(syntax.message.contains("non supportée"), "{}", syntax.message);
+        }
+    }
+
+    #[test]
+    fn test_transaction_statements() {
+        assert!(matches!(SqlParser::parse("BEGIN").unwrap(), SqlStatement::Begin));
+        assert!(matches!(SqlParser::parse("begin transaction;").unwrap(), SqlStatement::Begin));
+        assert!(matches!(SqlParser::parse("START TRANSACTION").unwrap(), SqlStatement::Begin));
+        assert!(matches!(SqlParser::parse("COMMIT WORK").unwrap(), SqlStatement::Commit));
+        assert!(matches!(SqlParser::parse("ROLLBACK").unwrap(), SqlStatement::Rollback));
+        assert!(SqlParser::parse("COMMIT NOW").is_err());
+    }
+
+    #[test]
+    fn test_reindex_statement() {
+        assert!(matches!(SqlParser::parse("REINDEX clients;").unwrap(), SqlStatement::Reindex(t) if t == "clients"));
+        assert!(SqlParser::parse("REINDEX").is_err());
+    }
+}
@@ -0,0 +1,214 @@
+/**
+ * Planificateur de requêtes : choix d'un parcours par index .ndx.
+ *
+ * Extrait de la clause WHERE les intervalles de recherche exploitables par
+ * un index : prédicats d'égalité (=), de comparaison (<, <=, >, >=) et
+ * BETWEEN sur une colonne, reliés au reste de la clause par AND. Les
+ * prédicats portant sur une même colonne sont combinés en un seul
+ * intervalle.
+ *
+ * Le plan ne fait que réduire les enregistrements lus : la clause WHERE
+ * complète est toujours réévaluée sur les enregistrements trouvés.
+ *
+ * Liens avec d'autres modules :
+ * - Utilisé par src/sql/executor.rs
+ * - Les intervalles sont parcourus par StorageEngine::select_by_index
+ */
+
+use crate::sql::parser::{ComparisonOperator, Expr, SqlValue};
+use std::cmp::Ordering;
+use std::ops::Bound;
+
+/// Intervalle de valeurs d'une colonne, déduit de la clause WHERE
+#[derive(Debug, Clone)]
+pub struct IndexRange {
+    /// Colonne concernée (telle qu'écrite dans la requête)
+    pub column: String,
+    /// Borne basse
+    pub lower: Bound<SqlValue>,
+    /// Borne haute
+    pub upper: Bound<SqlValue>,
+}
+
+impl IndexRange {
+    /// Indique si l'intervalle se réduit à une égalité
+    pub fn is_equality(&self) -> bool {
+        match (&self.lower, &self.upper) {
+            (Bound::Included(a), Bound::Included(b)) => compare_literals(a, b) == Some(Ordering::Equal),
+            _ => false,
+        }
+    }
+
+    /// Resserre l'intervalle avec une borne basse
+    fn restrict_lower(&mut self, bound: Bound<SqlValue>) {
+        if tighter(&bound, &self.lower, Ordering::Greater) {
+            self.lower = bound;
+        }
+    }
+
+    /// Resserre l'intervalle avec une borne haute
+    fn restrict_upper(&mut self, bound: Bound<SqlValue>) {
+        if tighter(&bound, &self.upper, Ordering::Less) {
+            self.upper = bound;
+        }
+    }
+}
+
+/**
+ * Retourne les intervalles candidats pour un parcours par index, du plus
+ * sélectif au moins sélectif (égalités, puis intervalles bornés des deux
+ * côtés, puis demi-intervalles).
+ *
+ * Seuls les littéraux entiers et chaînes sont retenus ; un prédicat sous
+ * OR ou NOT ne restreint pas la table et est ignoré.
+ *
+ * @param where_clause - Clause WHERE de la requête
+ * @returns Vec<IndexRange> - Un intervalle par colonne contrainte
+ *
+ * Effets de bord : Aucun
+ */
+pub fn candidate_ranges(where_clause: Option<&Expr>) -> Vec<IndexRange> {
+    let mut conjuncts = Vec::new();
+    if let Some(expr) = where_clause {
+        collect_conjuncts(expr, &mut conjuncts);
+    }
+
+    let mut ranges: Vec<IndexRange> = Vec::new();
+    for expr in conjuncts {
+        let (column, lower, upper) = match expr {
+            Expr::Comparison(condition) if is_indexable(&condition.value) => {
+                let value = condition.value.clone();
+                match condition.operator {
+                    ComparisonOperator::Equal => (&condition.column, Bound::Included(value.clone()), Bound::Included(value)),
+                    ComparisonOperator::GreaterThan => (&condition.column, Bound::Excluded(value), Bound::Unbounded),
+                    ComparisonOperator::GreaterThanOrEqual => (&condition.column, Bound::Included(value), Bound::Unbounded),
+                    ComparisonOperator::LessThan => (&condition.column, Bound::Unbounded, Bound::Excluded(value)),
+                    ComparisonOperator::LessThanOrEqual => (&condition.column, Bound::Unbounded, Bound::Included(value)),
+                    ComparisonOperator::NotEqual | ComparisonOperator::Like => continue,
+                }
+            }
+            Expr::Between { column, low, high, negated: false }
+                if is_indexable(low) && compare_literals(low, high).is_some() =>
+            {
+                (column, Bound::Included(low.clone()), Bound::Included(high.clone()))
+            }
+            _ => continue,
+        };
+
+        match ranges.iter_mut().find(|r| r.column.eq_ignore_ascii_case(column)) {
+            Some(range) => {
+                // Littéraux de types différents : on garde la première contrainte
+                if !same_kind(range, &lower) || !same_kind(range, &upper) {
+                    continue;
+                }
+                range.restrict_lower(lower);
+                range.restrict_upper(upper);
+            }
+            None => ranges.push(IndexRange { column: column.clone(), lower, upper }),
+        }
+    }
+
+    ranges.sort_by_key(|range| {
+        if range.is_equality() {
+            0
+        } else if !matches!(range.lower, Bound::Unbounded) && !matches!(range.upper, Bound::Unbounded) {
+            1
+        } else {
+            2
+        }
+    });
+    ranges
+}
+
+/// Aplatit les AND de premier niveau
+fn collect_conjuncts<'a>(expr: &'a Expr, out: &mut Vec<&'a Expr>) {
+    match expr {
+        Expr::And(left, right) => {
+            collect_conjuncts(left, out);
+            collect_conjuncts(right, out);
+        }
+        other => out.push(other),
+    }
+}
+
+/// Littéraux utilisables comme bornes d'index
+fn is_indexable(value: &SqlValue) -> bool {
+    matches!(value, SqlValue::Integer(_) | SqlValue::String(_))
+}
+
+/// Compare deux littéraux de même type
+fn compare_literals(a: &SqlValue, b: &SqlValue) -> Option<Ordering> {
+    match (a, b) {
+        (SqlValue::Integer(a), SqlValue::Integer(b)) => Some(a.cmp(b)),
+        (SqlValue::String(a), SqlValue::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+/// Indique si une borne porte sur le même type de littéral que l'intervalle
+fn same_kind(range: &IndexRange, bound: &Bound<SqlValue>) -> bool {
+    let existing = match (&range.lower, &range.upper) {
+        (Bound::Included(v) | Bound::Excluded(v), _) | (_, Bound::Included(v) | Bound::Excluded(v)) => v,
+        _ => return true,
+    };
+    match bound {
+        Bound::Included(v) | Bound::Excluded(v) => compare_literals(existing, v).is_some(),
+        Bound::Unbounded => true,
+    }
+}
+
+/**
+ * Indique si `candidate` est plus restrictive que `current` ; `direction`
+ * vaut Greater pour une borne basse, Less pour une borne haute.
+ */
+fn tighter(candidate: &Bound<SqlValue>, current: &Bound<SqlValue>, direction: Ordering) -> bool {
+    match (candidate, current) {
+        (Bound::Unbounded, _) => false,
+        (_, Bound::Unbounded) => true,
+        (Bound::Included(a) | Bound::Excluded(a), Bound::Included(b) | Bound::Excluded(b)) => {
+            match compare_literals(a, b) {
+                Some(Ordering::Equal) => matches!(candidate, Bound::Excluded(_)),
+                Some(ordering) => ordering == direction,
+                None => false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql::parser::{SqlParser, SqlStatement};
+
+    fn ranges(sql: &str) -> Vec<IndexRange> {
+        let SqlStatement::Select(select) = SqlParser::parse(sql).unwrap() else {
+            panic!("SELECT attendu");
+        };
+        candidate_ranges(select.where_clause.as_ref())
+    }
+
+    #[test]
+    fn test_candidate_ranges() {
+        let found = ranges("SELECT * FROM client WHERE age > 18 AND code = 'C042' AND age <= 65 AND nom LIKE 'A%'");
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].column, "code");
+        assert!(found[0].is_equality());
+        assert_eq!(found[1].column, "age");
+        assert!(matches!(found[1].lower, Bound::Excluded(SqlValue::Integer(18))));
+        assert!(matches!(found[1].upper, Bound::Included(SqlValue::Integer(65))));
+
+        let found = ranges("SELECT * FROM client WHERE age BETWEEN 10 AND 20 AND AGE >= 15 AND age < 20");
+        assert_eq!(found.len(), 1);
+        assert!(matches!(found[0].lower, Bound::Included(SqlValue::Integer(15))));
+        assert!(matches!(found[0].upper, Bound::Excluded(SqlValue::Integer(20))));
+    }
+
+    #[test]
+    fn test_non_restrictive_predicates_are_ignored() {
+        assert!(ranges("SELECT * FROM client").is_empty());
+        assert!(ranges("SELECT * FROM client WHERE code = 'A' OR code = 'B'").is_empty());
+        assert!(ranges("SELECT * FROM client WHERE NOT code = 'A'").is_empty());
+        assert!(ranges("SELECT * FROM client WHERE age <> 3 AND prix > 1.5").is_empty());
+        assert!(ranges("SELECT * FROM client WHERE age NOT BETWEEN 1 AND 5").is_empty());
+    }
+}
@@ -6,7 +6,7 @@
  * et via ODBC pour interroger d'autres bases de données.
  * 
  * Endpoints :
- * - POST /sql : Exécute une requête SQL
+ * - POST /sql : Exécute une requête SQL (avec transactions BEGIN/COMMIT/ROLLBACK)
  * - POST /odbc/tables : Liste les tables d'une source ODBC
  * - POST /odbc/relations : Liste les relations d'une source ODBC
  * 
@@ -18,7 +18,10 @@
 
 use crate::api::server::AppState;
 use crate::logger::{get_logger, LogLevel};
-use crate::sql::{SqlExecutor, SqlParser};
+use crate::sql::executor::SqlResult;
+use crate::sql::parser::SqlStatement;
+use crate::sql::{SqlExecutor, SqlParser, SqlSyntaxError};
+use crate::storage::Transaction;
 use anyhow::Result;
 use axum::{
     extract::State,
@@ -26,6 +29,9 @@ use axum::{
     response::Json,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, Weak};
+use std::time::{Duration, Instant};
 
 /// Requête SQL reçue via l'API HTTP
 #[derive(Deserialize)]
@@ -35,6 +41,9 @@ pub struct SqlRequest {
     /// DSN ODBC optionnel (si fourni, utilise ODBC au lieu du moteur FIC)
     #[serde(default)]
     pub dsn: Option<String>,
+    /// Jeton de la transaction de session (retourné par BEGIN)
+    #[serde(default)]
+    pub transaction_id: Option<String>,
 }
 
 /// Réponse standardisée pour les requêtes SQL
@@ -48,6 +57,129 @@ pub struct SqlResponse {
     pub error: Option<String>,
     /// Nombre de lignes affectées (pour INSERT/UPDATE/DELETE)
     pub rows_affected: Option<usize>,
+    /// Position de l'erreur de syntaxe (ligne, colonne, longueur) pour surligner le token fautif
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub syntax_error: Option<SqlSyntaxError>,
+    /// Jeton de la transaction de session (ouverte par BEGIN, jusqu'à COMMIT/ROLLBACK)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_id: Option<String>,
+}
+
+/// Durée d'inactivité au-delà de laquelle une transaction est annulée
+const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// Intervalle entre deux purges des transactions expirées
+const SESSION_REAP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Transaction ouverte par une session cliente
+struct Session {
+    txn: Transaction,
+    last_used: Instant,
+}
+
+/**
+ * Sessions SQL : transactions ouvertes par BEGIN, indexées par un jeton.
+ * 
+ * Le client reçoit le jeton en réponse à BEGIN et le renvoie (champ
+ * transaction_id) avec chaque requête de la transaction, jusqu'à COMMIT
+ * ou ROLLBACK. Le jeton (128 bits aléatoires) est la seule preuve
+ * d'appartenance à la transaction : il ne doit pas être devinable, et
+ * l'identifiant interne de la transaction n'est jamais exposé.
+ * 
+ * Une transaction inactive depuis plus de SESSION_IDLE_TIMEOUT est
+ * annulée par reap_sessions, ce qui libère ses tables.
+ */
+#[derive(Default)]
+pub struct SqlSessions {
+    transactions: Mutex<HashMap<String, Session>>,
+}
+
+impl SqlSessions {
+    /// Crée un registre de sessions vide
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enregistre une nouvelle transaction et retourne son jeton
+    fn open(&self, txn: Transaction) -> Result<String> {
+        let token = new_token()?;
+        self.transactions
+            .lock()
+            .unwrap()
+            .insert(token.clone(), Session { txn, last_used: Instant::now() });
+        Ok(token)
+    }
+
+    /// Retire une transaction du registre (COMMIT, ROLLBACK ou exécution)
+    fn take(&self, token: &str) -> Result<Transaction> {
+        let mut transactions = self.transactions.lock().unwrap();
+        match transactions.remove(token) {
+            Some(session) if session.last_used.elapsed() < SESSION_IDLE_TIMEOUT => Ok(session.txn),
+            _ => anyhow::bail!("Transaction inconnue, expirée ou en cours d'utilisation"),
+        }
+    }
+
+    /// Remet une transaction dans le registre après une instruction
+    fn restore(&self, token: &str, txn: Transaction) {
+        self.transactions
+            .lock()
+            .unwrap()
+            .insert(token.to_string(), Session { txn, last_used: Instant::now() });
+    }
+
+    /**
+     * Annule les transactions inactives depuis plus de SESSION_IDLE_TIMEOUT.
+     * 
+     * @returns usize - Nombre de transactions annulées
+     * 
+     * Effets de bord :
+     * - Libère les tables verrouillées par les transactions annulées
+     */
+    pub fn reap_expired(&self) -> usize {
+        self.reap_idle(SESSION_IDLE_TIMEOUT)
+    }
+
+    fn reap_idle(&self, timeout: Duration) -> usize {
+        let mut transactions = self.transactions.lock().unwrap();
+        let before = transactions.len();
+        // La transaction retirée est détruite, ce qui libère ses verrous
+        transactions.retain(|_, session| session.last_used.elapsed() < timeout);
+        before - transactions.len()
+    }
+}
+
+/**
+ * Purge périodiquement les transactions expirées.
+ * 
+ * Tourne en tâche de fond tant que le registre existe, pour que les tables
+ * d'une session abandonnée soient libérées même si aucune autre requête SQL
+ * n'arrive.
+ * 
+ * @param sessions - Registre des sessions (référence faible : la tâche
+ *   s'arrête quand le serveur libère le registre)
+ */
+pub async fn reap_sessions(sessions: Weak<SqlSessions>) {
+    let mut interval = tokio::time::interval(SESSION_REAP_INTERVAL);
+    loop {
+        interval.tick().await;
+        let Some(sessions) = sessions.upgrade() else { break };
+        let reaped = sessions.reap_expired();
+        if reaped > 0 {
+            get_logger().log_with_source(
+                LogLevel::Info,
+                format!("{} transaction(s) inactive(s) annulée(s)", reaped),
+                Some("SQL Server".to_string()),
+            );
+        }
+    }
+}
+
+/// Génère un jeton de session : 128 bits aléatoires, en hexadécimal
+fn new_token() -> Result<String> {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| anyhow::anyhow!("Impossible de générer un jeton de transaction: {}", e))?;
+    Ok(hex::encode(bytes))
 }
 
 /**
@@ -56,12 +188,21 @@ pub struct SqlResponse {
  * Parse et exécute une requête SQL. Si un DSN ODBC est fourni,
  * utilise ODBC, sinon utilise le moteur FIC.
  * 
+ * Transactions (moteur FIC) :
+ * - BEGIN retourne un transaction_id
+ * - Les requêtes portant ce transaction_id s'exécutent dans la
+ *   transaction ; les autres sessions ne voient pas ses modifications
+ * - COMMIT applique toutes les modifications (tout ou rien), ROLLBACK
+ *   les abandonne
+ * - Une instruction en erreur annule toute la transaction
+ * 
  * @param state - État de l'application (injecté par Axum)
- * @param request - Requête SQL avec optionnellement un DSN ODBC
+ * @param request - Requête SQL avec optionnellement un DSN ODBC ou un id de transaction
  * @returns Result<Json<SqlResponse>> - Résultat de l'exécution ou erreur HTTP
  * 
  * Effets de bord :
  * - Peut lire/écrire des données selon la requête SQL
+ * - Peut ouvrir, valider ou annuler une transaction de session
  * - Peut se connecter à une base de données ODBC
  */
 pub async fn execute_sql(
@@ -75,89 +216,164 @@ pub async fn execute_sql(
 
     // Sinon, utiliser le moteur FIC
     let executor = SqlExecutor::new(state.engine.clone());
-    
-    match SqlParser::parse(&request.sql) {
-        Ok(statement) => {
-            match executor.execute(&statement) {
-                Ok(result) => {
-                    match result {
-                        crate::sql::executor::SqlResult::Select { columns, rows } => {
-                            let data: Vec<serde_json::Value> = rows
-                                .into_iter()
-                                .map(|record| {
-                                    let mut row = serde_json::Map::new();
-                                    row.insert("id".to_string(), serde_json::Value::Number(record.id.into()));
-                                    for (key, value) in record.fields {
-                                        row.insert(key, serde_json::to_value(value).unwrap_or(serde_json::Value::Null));
-                                    }
-                                    serde_json::Value::Object(row)
-                                })
-                                .collect();
-                            
-                            let mut response = serde_json::Map::new();
-                            response.insert("columns".to_string(), serde_json::to_value(columns).unwrap());
-                            response.insert("rows".to_string(), serde_json::Value::Array(data));
-                            
-                            Ok(Json(SqlResponse {
-                                success: true,
-                                data: Some(serde_json::Value::Object(response)),
-                                error: None,
-                                rows_affected: None,
-                            }))
-                        }
-                        crate::sql::executor::SqlResult::Insert { id } => {
-                            Ok(Json(SqlResponse {
-                                success: true,
-                                data: Some(serde_json::json!({ "id": id })),
-                                error: None,
-                                rows_affected: Some(1),
-                            }))
-                        }
-                        crate::sql::executor::SqlResult::Update { count } => {
-                            Ok(Json(SqlResponse {
-                                success: true,
-                                data: None,
-                                error: None,
-                                rows_affected: Some(count),
-                            }))
-                        }
-                        crate::sql::executor::SqlResult::Delete { count } => {
-                            Ok(Json(SqlResponse {
-                                success: true,
-                                data: None,
-                                error: None,
-                                rows_affected: Some(count),
-                            }))
-                        }
-                    }
-                }
-                Err(e) => {
-                    Err((
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(SqlResponse {
-                            success: false,
-                            data: None,
-                            error: Some(e.to_string()),
-                            rows_affected: None,
-                        }),
-                    ))
-                }
-            }
-        }
+    let sessions = &state.sql_sessions;
+
+    let statement = match SqlParser::parse(&request.sql) {
+        Ok(statement) => statement,
         Err(e) => {
-            Err((
+            return Err((
                 StatusCode::BAD_REQUEST,
                 Json(SqlResponse {
                     success: false,
                     data: None,
                     error: Some(e.to_string()),
                     rows_affected: None,
+                    syntax_error: e.downcast_ref::<SqlSyntaxError>().cloned(),
+                    transaction_id: request.transaction_id.clone(),
                 }),
-            ))
+            ));
         }
+    };
+
+    match (&statement, request.transaction_id) {
+        (SqlStatement::Begin, Some(id)) => {
+            Err(error_response(StatusCode::BAD_REQUEST, "Transaction déjà ouverte".to_string(), Some(id)))
+        }
+        (SqlStatement::Begin, None) => {
+            let id = sessions.open(state.engine.begin())
+                .map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string(), None))?;
+            Ok(Json(SqlResponse {
+                success: true,
+                data: None,
+                error: None,
+                rows_affected: None,
+                syntax_error: None,
+                transaction_id: Some(id),
+            }))
+        }
+        (SqlStatement::Commit | SqlStatement::Rollback, None) => {
+            Err(error_response(StatusCode::BAD_REQUEST, "Aucune transaction ouverte (transaction_id manquant)".to_string(), None))
+        }
+        (SqlStatement::Commit | SqlStatement::Rollback, Some(id)) => {
+            let txn = sessions.take(&id)
+                .map_err(|e| error_response(StatusCode::NOT_FOUND, e.to_string(), Some(id.clone())))?;
+            let outcome = if matches!(statement, SqlStatement::Commit) {
+                state.engine.commit(txn)
+            } else {
+                state.engine.rollback(txn);
+                Ok(())
+            };
+            match outcome {
+                Ok(()) => Ok(Json(SqlResponse {
+                    success: true,
+                    data: None,
+                    error: None,
+                    rows_affected: None,
+                    syntax_error: None,
+                    transaction_id: Some(id),
+                })),
+                Err(e) => Err(error_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("{} (transaction annulée)", e),
+                    Some(id),
+                )),
+            }
+        }
+        (SqlStatement::Reindex(_), Some(id)) => {
+            Err(error_response(StatusCode::BAD_REQUEST, "REINDEX ne peut pas être exécuté dans une transaction".to_string(), Some(id)))
+        }
+        (_, Some(id)) => {
+            let mut txn = sessions.take(&id)
+                .map_err(|e| error_response(StatusCode::NOT_FOUND, e.to_string(), Some(id.clone())))?;
+            match executor.execute_in(&statement, &mut txn) {
+                Ok(result) => {
+                    sessions.restore(&id, txn);
+                    Ok(Json(result_response(result, Some(id))))
+                }
+                // Tout ou rien : l'échec d'une instruction annule la transaction
+                Err(e) => Err(error_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("{} (transaction annulée)", e),
+                    Some(id),
+                )),
+            }
+        }
+        (_, None) => match executor.execute(&statement) {
+            Ok(result) => Ok(Json(result_response(result, None))),
+            Err(e) => Err(error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string(), None)),
+        },
+    }
+}
+
+/**
+ * Convertit le résultat d'une requête FIC en réponse HTTP.
+ * 
+ * @param result - Résultat de l'exécuteur
+ * @param transaction_id - Jeton de la transaction de session, renvoyé au client
+ * @returns SqlResponse - Réponse de succès
+ */
+fn result_response(result: SqlResult, transaction_id: Option<String>) -> SqlResponse {
+    match result {
+        SqlResult::Select { columns, rows } => {
+            let data: Vec<serde_json::Value> = rows
+                .into_iter()
+                .map(|record| {
+                    let mut row = serde_json::Map::new();
+                    row.insert("id".to_string(), serde_json::Value::Number(record.id.into()));
+                    for (key, value) in record.fields {
+                        row.insert(key, serde_json::to_value(value).unwrap_or(serde_json::Value::Null));
+                    }
+                    serde_json::Value::Object(row)
+                })
+                .collect();
+
+            let mut response = serde_json::Map::new();
+            response.insert("columns".to_string(), serde_json::to_value(columns).unwrap());
+            response.insert("rows".to_string(), serde_json::Value::Array(data));
+
+            SqlResponse {
+                success: true,
+                data: Some(serde_json::Value::Object(response)),
+                error: None,
+                rows_affected: None,
+                syntax_error: None,
+                transaction_id,
+            }
+        }
+        SqlResult::Insert { id } => SqlResponse {
+            success: true,
+            data: Some(serde_json::json!({ "id": id })),
+            error: None,
+            rows_affected: Some(1),
+            syntax_error: None,
+            transaction_id,
+        },
+        SqlResult::Update { count } | SqlResult::Delete { count } | SqlResult::Reindex { count } => SqlResponse {
+            success: true,
+            data: None,
+            error: None,
+            rows_affected: Some(count),
+            syntax_error: None,
+            transaction_id,
+        },
     }
 }
 
+/// Réponse d'erreur HTTP pour le moteur FIC
+fn error_response(status: StatusCode, message: String, transaction_id: Option<String>) -> (StatusCode, Json<SqlResponse>) {
+    (
+        status,
+        Json(SqlResponse {
+            success: false,
+            data: None,
+            error: Some(message),
+            rows_affected: None,
+            syntax_error: None,
+            transaction_id,
+        }),
+    )
+}
+
 /**
  * Exécute une requête SQL via ODBC.
  * 
@@ -211,6 +427,8 @@ async fn execute_sql_odbc(
                 data: Some(serde_json::Value::Object(response)),
                 error: None,
                 rows_affected: odbc_result.rows_affected,
+                syntax_error: None,
+                transaction_id: None,
             }))
         }
         Ok(Ok(Err(e))) => {
@@ -222,6 +440,8 @@ async fn execute_sql_odbc(
                     data: None,
                     error: Some(e.to_string()),
                     rows_affected: None,
+                    syntax_error: None,
+                    transaction_id: None,
                 }),
             ))
         }
@@ -234,6 +454,8 @@ async fn execute_sql_odbc(
                     data: None,
                     error: Some("Erreur interne lors de l'accès ODBC. Vérifiez que les drivers ODBC sont correctement installés.".to_string()),
                     rows_affected: None,
+                    syntax_error: None,
+                    transaction_id: None,
                 }),
             ))
         }
@@ -246,6 +468,8 @@ async fn execute_sql_odbc(
                     data: None,
                     error: Some(format!("Erreur d'exécution: {}", e)),
                     rows_affected: None,
+                    syntax_error: None,
+                    transaction_id: None,
                 }),
             ))
         }
@@ -430,3 +654,43 @@ impl SqlServer {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_tokens_are_random() -> Result<()> {
+        let sessions = SqlSessions::new();
+        let locks = Arc::new(Mutex::new(HashMap::new()));
+        let first = sessions.open(Transaction::new(1, locks.clone()))?;
+        let second = sessions.open(Transaction::new(2, locks))?;
+        assert_eq!(first.len(), 32);
+        assert!(first.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(first, second);
+        // L'identifiant interne ne permet pas de reprendre la transaction
+        assert!(sessions.take("1").is_err());
+        assert!(sessions.take(&first).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_reap_releases_table_locks() -> Result<()> {
+        let sessions = SqlSessions::new();
+        let locks = Arc::new(Mutex::new(HashMap::new()));
+        let txn = Transaction::new(1, locks.clone());
+        txn.lock_table("clients")?;
+        let token = sessions.open(txn)?;
+
+        let other = Transaction::new(2, locks.clone());
+        assert!(other.lock_table("clients").is_err());
+
+        // Sans autre requête sur les sessions, la purge suffit à libérer la table
+        assert_eq!(sessions.reap_idle(Duration::ZERO), 1);
+        assert!(locks.lock().unwrap().is_empty());
+        other.lock_table("clients")?;
+        assert!(sessions.take(&token).is_err());
+        Ok(())
+    }
+}
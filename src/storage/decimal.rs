@@ -0,0 +1,218 @@
+/**
+ * Nombres décimaux exacts (rubriques monétaires et numériques HFSQL).
+ *
+ * Les montants sont stockés par HFSQL sous forme d'entiers mis à l'échelle :
+ * les décoder en f64 introduit des écarts d'arrondi. Ce fichier représente
+ * ces valeurs par un entier (i128) et un nombre de décimales, et fournit les
+ * opérations exactes nécessaires aux comparaisons et aux agrégats.
+ *
+ * Représentations sur disque :
+ * - Monétaire : entier signé little-endian de 8 bytes, en dix-millièmes
+ *   (4 décimales)
+ * - Numérique : entier signé little-endian de 4, 8 ou 16 bytes, à l'échelle
+ *   de la rubrique (FieldInfo::scale, 6 décimales par défaut)
+ *
+ * Les valeurs sont exposées sous forme de chaîne (`FieldValue::Decimal`)
+ * avec toutes leurs décimales : `1234.5600`.
+ *
+ * Liens avec d'autres modules :
+ * - Utilisé par src/storage/engine.rs (décodage et encodage des champs)
+ * - Utilisé par src/storage/filter.rs, src/storage/sort.rs (comparaisons)
+ *   et src/sql/aggregate.rs (SUM, AVG)
+ */
+
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Nombre maximal de décimales accepté
+pub const MAX_SCALE: u32 = 18;
+
+/// Nombre décimal exact : `mantissa / 10^scale`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Decimal {
+    /// Valeur entière mise à l'échelle
+    pub mantissa: i128,
+    /// Nombre de décimales
+    pub scale: u32,
+}
+
+impl Decimal {
+    /// Crée un décimal à partir d'une valeur mise à l'échelle
+    pub fn new(mantissa: i128, scale: u32) -> Self {
+        Self { mantissa, scale }
+    }
+
+    /**
+     * Interprète un texte décimal (`-12.50`, `+3`, `.5`).
+     *
+     * @param text - Texte à interpréter (espaces ignorés)
+     * @returns Option<Decimal> - Valeur avec autant de décimales que le texte
+     */
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let (negative, digits) = match text.as_bytes().first()? {
+            b'-' => (true, &text[1..]),
+            b'+' => (false, &text[1..]),
+            _ => (false, text),
+        };
+        let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        if integer.is_empty() && fraction.is_empty()
+            || !integer.bytes().chain(fraction.bytes()).all(|b| b.is_ascii_digit())
+            || fraction.len() as u32 > MAX_SCALE
+        {
+            return None;
+        }
+        let mut mantissa: i128 = 0;
+        for b in integer.bytes().chain(fraction.bytes()) {
+            mantissa = mantissa.checked_mul(10)?.checked_add((b - b'0') as i128)?;
+        }
+        Some(Self::new(if negative { -mantissa } else { mantissa }, fraction.len() as u32))
+    }
+
+    /**
+     * Décode un entier signé little-endian mis à l'échelle.
+     *
+     * @param bytes - Zone du champ (2, 4, 8 ou 16 bytes)
+     * @param scale - Nombre de décimales
+     * @returns Option<Decimal> - None pour une taille non supportée
+     */
+    pub fn from_le_bytes(bytes: &[u8], scale: u32) -> Option<Self> {
+        let mantissa = match bytes.len() {
+            2 => i16::from_le_bytes(bytes.try_into().ok()?) as i128,
+            4 => i32::from_le_bytes(bytes.try_into().ok()?) as i128,
+            8 => i64::from_le_bytes(bytes.try_into().ok()?) as i128,
+            16 => i128::from_le_bytes(bytes.try_into().ok()?),
+            _ => return None,
+        };
+        Some(Self::new(mantissa, scale))
+    }
+
+    /**
+     * Encode la valeur en entier signé little-endian à l'échelle donnée.
+     *
+     * @param length - Taille de la zone (2, 4, 8 ou 16 bytes)
+     * @param scale - Nombre de décimales de la rubrique
+     * @returns Option<Vec<u8>> - None si des décimales seraient perdues ou
+     *   si la valeur ne tient pas dans la zone
+     */
+    pub fn to_le_bytes(&self, length: usize, scale: u32) -> Option<Vec<u8>> {
+        let mantissa = self.rescale(scale)?.mantissa;
+        Some(match length {
+            2 => i16::try_from(mantissa).ok()?.to_le_bytes().to_vec(),
+            4 => i32::try_from(mantissa).ok()?.to_le_bytes().to_vec(),
+            8 => i64::try_from(mantissa).ok()?.to_le_bytes().to_vec(),
+            16 => mantissa.to_le_bytes().to_vec(),
+            _ => return None,
+        })
+    }
+
+    /// Même valeur avec `scale` décimales (None si des décimales seraient perdues)
+    pub fn rescale(&self, scale: u32) -> Option<Self> {
+        if scale >= self.scale {
+            let factor = 10i128.checked_pow(scale - self.scale)?;
+            Some(Self::new(self.mantissa.checked_mul(factor)?, scale))
+        } else {
+            let factor = 10i128.checked_pow(self.scale - scale)?;
+            (self.mantissa % factor == 0).then(|| Self::new(self.mantissa / factor, scale))
+        }
+    }
+
+    /// Somme exacte (échelle la plus grande des deux)
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        let scale = self.scale.max(other.scale);
+        let (a, b) = (self.rescale(scale)?, other.rescale(scale)?);
+        Some(Self::new(a.mantissa.checked_add(b.mantissa)?, scale))
+    }
+
+    /**
+     * Quotient par un entier, arrondi au plus proche (demi à l'opposé de
+     * zéro) avec `extra` décimales de plus que la valeur.
+     *
+     * @param divisor - Diviseur (non nul)
+     * @param extra - Décimales ajoutées
+     * @returns Option<Decimal> - None en cas de dépassement
+     */
+    pub fn checked_div_round(&self, divisor: i64, extra: u32) -> Option<Self> {
+        let scaled = self.rescale(self.scale + extra)?;
+        let divisor = divisor as i128;
+        if divisor == 0 {
+            return None;
+        }
+        let (quotient, remainder) = (scaled.mantissa / divisor, scaled.mantissa % divisor);
+        let round = if remainder.checked_abs()?.checked_mul(2)? >= divisor.checked_abs()? {
+            scaled.mantissa.signum() * divisor.signum()
+        } else {
+            0
+        };
+        Some(Self::new(quotient + round, scaled.scale))
+    }
+
+    /// Valeur approchée en flottant
+    pub fn to_f64(&self) -> f64 {
+        self.mantissa as f64 / 10f64.powi(self.scale as i32)
+    }
+
+    /// Forme canonique sans zéros décimaux superflus (`10.50` -> `10.5`, `3.00` -> `3`)
+    pub fn normalized(&self) -> Self {
+        let mut value = *self;
+        while value.scale > 0 && value.mantissa % 10 == 0 {
+            value = Self::new(value.mantissa / 10, value.scale - 1);
+        }
+        value
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let scale = self.scale.max(other.scale);
+        match (self.rescale(scale), other.rescale(scale)) {
+            (Some(a), Some(b)) => a.mantissa.cmp(&b.mantissa),
+            // Dépassement lors de la mise à l'échelle : ordre approché
+            _ => self.to_f64().total_cmp(&other.to_f64()),
+        }
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let digits = self.mantissa.unsigned_abs().to_string();
+        if self.scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+        let digits = format!("{:0>width$}", digits, width = self.scale as usize + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - self.scale as usize);
+        write!(f, "{}{}.{}", sign, integer, fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimal_arithmetic() {
+        let price = Decimal::from_le_bytes(&123_456_700i64.to_le_bytes(), 4).unwrap();
+        assert_eq!(price.to_string(), "12345.6700");
+        assert_eq!(Decimal::parse("-0.05").unwrap().to_string(), "-0.05");
+
+        // 0.1 + 0.2 est exact
+        let sum = Decimal::parse("0.1").unwrap().checked_add(&Decimal::parse("0.2").unwrap()).unwrap();
+        assert_eq!(sum, Decimal::parse("0.3").unwrap());
+        assert_eq!(Decimal::parse("10.50").unwrap().cmp(&Decimal::parse("10.5").unwrap()), Ordering::Equal);
+        assert_eq!(Decimal::parse("3.00").unwrap().normalized().to_string(), "3");
+
+        assert_eq!(Decimal::parse("10").unwrap().checked_div_round(3, 2).unwrap().to_string(), "3.33");
+        assert_eq!(Decimal::parse("-0.05").unwrap().checked_div_round(2, 1).unwrap().to_string(), "-0.025");
+
+        assert_eq!(Decimal::parse("1.5").unwrap().to_le_bytes(8, 4).unwrap(), 15_000i64.to_le_bytes());
+        assert!(Decimal::parse("1.23456").unwrap().to_le_bytes(8, 4).is_none());
+        assert!(Decimal::parse("1e5").is_none());
+    }
+}
@@ -5,7 +5,8 @@
  * aux données HFSQL. Il fournit :
  * 
  * - Détection automatique des tables dans un dossier
- * - Lecture et écriture d'enregistrements
+ * - Lecture et écriture d'enregistrements (insertion, mise à jour,
+ *   suppression logique), autorisée selon read_only et enable_write
  * - Conversion des données brutes en structures typées (Record, FieldValue)
  * - Requêtes avec filtres et pagination
  * - Gestion des schémas de tables
@@ -20,24 +21,82 @@
  * - Utilisé par src/sql/executor.rs pour les requêtes SQL
  */
 
-use crate::core::{FicFile, FicRecord, MmoFile, TableFiles, TableSchema};
+use crate::config::StorageSettings;
+use crate::core::schema_file;
+use crate::core::wdd::{self, Analysis, AnalysisLink};
+use crate::core::mmo;
+use crate::core::{FicFile, FicRecord, FieldInfo, FieldType, KeyType, MemoKind, MmoBlock, MmoFile, NdxFile, NdxKey, RawRecord, TableFiles, TableSchema, TextEncoding};
+use crate::sql::parser::{Expr, OrderByItem};
+use crate::logger::{get_logger, LogLevel};
+use crate::storage::filter;
+use crate::storage::journal::{Journal, WriteBatch};
+use crate::storage::transaction::{TableChanges, TableLocks, Transaction};
+use crate::storage::sort::{ExternalSorter, DEFAULT_SORT_RUN_SIZE};
+use crate::storage::decimal::Decimal;
+use crate::storage::temporal;
 use anyhow::{Context, Result};
 use encoding_rs::WINDOWS_1252;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, RwLock};
+use std::collections::BTreeMap;
+use std::ops::Bound;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock, RwLockReadGuard};
 use rayon::prelude::*;
 
+/// Nombre d'enregistrements décodés à la fois par un select filtré sans tri
+const DECODE_CHUNK_SIZE: usize = 1024;
+
 /// Filtres pour les requêtes de sélection
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryFilters {
-    /// Nombre maximum d'enregistrements à retourner
+    /// Nombre maximum d'enregistrements à retourner (None : sans limite ;
+    /// la limite par défaut des requêtes est appliquée par l'appelant)
     pub limit: Option<u32>,
     /// Nombre d'enregistrements à ignorer (pagination)
     pub offset: Option<u32>,
-    /// Filtres par champ (nom_champ -> valeur)
+    /// Filtres par champ (nom_champ -> valeur), évalués comme des égalités
     pub field_filters: HashMap<String, String>,
+    /// Expression WHERE typée (AND, OR, NOT, IN, BETWEEN, IS NULL...)
+    #[serde(default)]
+    pub where_clause: Option<Expr>,
+    /// Critères de tri (ORDER BY), appliqués avant la pagination
+    #[serde(default)]
+    pub order_by: Vec<OrderByItem>,
+    /// Compter toutes les correspondances (QueryResult::total). Sans tri, un
+    /// parcours qui n'a pas besoin du total s'arrête dès la page remplie
+    #[serde(default = "default_count_total")]
+    pub count_total: bool,
+}
+
+fn default_count_total() -> bool {
+    true
+}
+
+/// Filtres vides : tous les enregistrements, sans limite, total compté
+impl Default for QueryFilters {
+    fn default() -> Self {
+        Self {
+            limit: None,
+            offset: None,
+            field_filters: HashMap::new(),
+            where_clause: None,
+            order_by: Vec::new(),
+            count_total: default_count_total(),
+        }
+    }
+}
+
+impl QueryFilters {
+    /**
+     * Indique si des filtres doivent être appliqués aux enregistrements.
+     * 
+     * @returns bool - true si au moins un filtre ou une condition est présent
+     */
+    pub fn has_predicates(&self) -> bool {
+        !self.field_filters.is_empty() || self.where_clause.is_some()
+    }
 }
 
 /// Résultat d'une requête de sélection
@@ -45,12 +104,13 @@ pub struct QueryFilters {
 pub struct QueryResult {
     /// Liste des enregistrements retournés
     pub records: Vec<Record>,
-    /// Nombre total d'enregistrements (avant pagination)
+    /// Nombre total d'enregistrements (avant pagination). Sans count_total,
+    /// seules les correspondances parcourues jusqu'à la fin de la page sont comptées
     pub total: u32,
     /// Offset utilisé
     pub offset: u32,
-    /// Limite utilisée
-    pub limit: u32,
+    /// Limite utilisée (None : sans limite)
+    pub limit: Option<u32>,
 }
 
 /// Représente un enregistrement avec ses données décodées et typées
@@ -60,11 +120,32 @@ pub struct Record {
     pub id: u32,
     /// Champs de l'enregistrement (nom -> valeur typée)
     pub fields: HashMap<String, FieldValue>,
-    /// Données mémo décodées (nom_champ_mémo -> contenu texte)
-    pub memo_data: HashMap<String, String>,
+    /// Données mémo décodées (nom_champ_mémo -> texte, ou type d'un binaire)
+    pub memo_data: HashMap<String, MemoValue>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Contenu d'une rubrique mémo dans un enregistrement décodé
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum MemoValue {
+    /// Mémo texte, décodé avec l'encodage de la rubrique
+    #[serde(rename = "text")]
+    Text { value: String },
+    /// Mémo binaire (photo, document) : contenu lu via StorageEngine::read_memo
+    #[serde(rename = "binary")]
+    Binary { mime_type: String, size: u64 },
+}
+
+/// Contenu brut d'un mémo et son type MIME
+#[derive(Debug, Clone)]
+pub struct MemoContent {
+    /// Type MIME (`text/plain; charset=utf-8` pour un mémo texte)
+    pub mime_type: String,
+    /// Octets du mémo (texte réencodé en UTF-8)
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum FieldValue {
     #[serde(rename = "string")]
@@ -73,6 +154,18 @@ pub enum FieldValue {
     Integer { value: i64 },
     #[serde(rename = "float")]
     Float { value: f64 },
+    #[serde(rename = "boolean")]
+    Boolean { value: bool },
+    #[serde(rename = "decimal")]
+    Decimal { value: String }, // décimal exact : 1234.5600
+    #[serde(rename = "date")]
+    Date { value: String }, // ISO-8601 : 2024-01-15
+    #[serde(rename = "time")]
+    Time { value: String }, // ISO-8601 : 14:30:05.250
+    #[serde(rename = "datetime")]
+    DateTime { value: String }, // ISO-8601 : 2024-01-15T14:30:05
+    #[serde(rename = "duration")]
+    Duration { value: String }, // ISO-8601 : P1DT2H30M
     #[serde(rename = "binary")]
     Binary { value: String }, // hex string
     #[serde(rename = "null")]
@@ -93,6 +186,14 @@ impl FieldValue {
         FieldValue::Float { value: f }
     }
     
+    pub fn boolean(b: bool) -> Self {
+        FieldValue::Boolean { value: b }
+    }
+    
+    pub fn decimal(d: Decimal) -> Self {
+        FieldValue::Decimal { value: d.to_string() }
+    }
+    
     pub fn binary(bytes: Vec<u8>) -> Self {
         let hex_string: String = bytes.iter()
             .map(|b| format!("{:02x}", b))
@@ -123,6 +224,79 @@ pub struct StorageEngine {
     read_only: bool,
     /// Active le multi-threading pour la lecture parallèle
     parallel: bool,
+    /// Projette les .fic en mémoire pour les parcours (storage.mmap)
+    mmap: bool,
+    /// Autorise les écritures (storage.enable_write), en plus de read_only
+    enable_write: bool,
+    /// Dossier des fichiers de schéma (storage.schema_dir)
+    schema_dir: Option<PathBuf>,
+    /// Analyse (.wdd) configurée (storage.analysis)
+    analysis_path: Option<PathBuf>,
+    /// Analyse chargée lors du dernier scan
+    analysis: RwLock<Option<Arc<Analysis>>>,
+    /// Encodage des chaînes par défaut (storage.encoding)
+    encoding: Option<String>,
+    /// Encodage par table (storage.table_encodings)
+    table_encodings: HashMap<String, String>,
+    /// Exclut les lectures pendant l'application d'un COMMIT
+    write_lock: RwLock<()>,
+    /// Verrous d'écriture par table des transactions en cours
+    table_locks: TableLocks,
+    /// Prochain identifiant interne de transaction (jamais exposé aux clients)
+    next_transaction_id: AtomicU64,
+    /// Journal d'écriture garantissant l'atomicité de chaque instruction
+    journal: Journal,
+}
+
+/**
+ * Recherche par index préparée par StorageEngine::index_lookup.
+ *
+ * Garde ouverts l'index et les fichiers de la table, sous le verrou de
+ * lecture du moteur, pour enchaîner les recherches d'une jointure.
+ */
+pub struct IndexLookup<'a> {
+    engine: &'a StorageEngine,
+    _guard: RwLockReadGuard<'a, ()>,
+    schema: TableSchema,
+    field: FieldInfo,
+    ndx: NdxFile,
+    key_no: usize,
+    fic: FicFile,
+    mmo: Option<MmoFile>,
+}
+
+impl IndexLookup<'_> {
+    /**
+     * Recherche les enregistrements dont la clé d'index correspond à une valeur.
+     * 
+     * L'intervalle parcouru peut contenir des valeurs voisines (clé
+     * insensible à la casse, chaînes complétées) : l'appelant compare les
+     * enregistrements retournés selon sa propre règle d'égalité.
+     * 
+     * @param value - Valeur recherchée
+     * @returns Result<Option<Vec<Record>>> - Enregistrements actifs de
+     *   l'intervalle, ou None si l'index ne peut pas servir pour cette
+     *   valeur (type incompatible)
+     * 
+     * Effets de bord :
+     * - Lit les pages du .ndx parcourues puis les enregistrements trouvés dans le .fic
+     */
+    pub fn find(&mut self, value: &FieldValue) -> Result<Option<Vec<Record>>> {
+        let bound = Bound::Included(value);
+        let Some(ids) = index_range_ids(&mut self.ndx, self.key_no, &self.field, bound, bound)? else {
+            return Ok(None);
+        };
+
+        let mut records = Vec::with_capacity(ids.len());
+        for id in ids {
+            let fic_record = self.fic.read_record(id)?;
+            if fic_record.deleted {
+                continue;
+            }
+            records.push(self.engine.record_from_fic(fic_record, &self.schema, &mut self.mmo)?);
+        }
+        Ok(Some(records))
+    }
 }
 
 impl StorageEngine {
@@ -138,6 +312,8 @@ impl StorageEngine {
      * 
      * Effets de bord :
      * - Peut créer le dossier de données s'il n'existe pas
+     * - Annule une écriture interrompue si un journal est présent
+     *   (sauf en lecture seule)
      */
     pub fn new(data_dir: impl AsRef<Path>, read_only: bool) -> Result<Self> {
         Self::new_with_parallel(data_dir, read_only, true)
@@ -159,14 +335,69 @@ impl StorageEngine {
                 .with_context(|| format!("Impossible de créer le dossier: {:?}", data_dir))?;
         }
 
+        // Annulation d'une instruction interrompue (crash pendant une écriture)
+        let journal = Journal::new(&data_dir);
+        if journal.exists() {
+            if read_only {
+                get_logger().log_with_source(
+                    LogLevel::Warn,
+                    format!("Journal d'écriture présent dans {:?} : récupération différée (lecture seule)", data_dir),
+                    Some("Storage".to_string()),
+                );
+            } else if journal.recover()? {
+                get_logger().log_with_source(
+                    LogLevel::Warn,
+                    "Instruction d'écriture interrompue annulée à partir du journal".to_string(),
+                    Some("Storage".to_string()),
+                );
+            }
+        }
+
         Ok(Self {
             data_dir,
             tables: Arc::new(RwLock::new(HashMap::new())),
             read_only,
             parallel,
+            mmap: false,
+            enable_write: true,
+            schema_dir: None,
+            analysis_path: None,
+            analysis: RwLock::new(None),
+            encoding: None,
+            table_encodings: HashMap::new(),
+            write_lock: RwLock::new(()),
+            table_locks: TableLocks::default(),
+            next_transaction_id: AtomicU64::new(1),
+            journal,
         })
     }
 
+    /**
+     * Crée un moteur de stockage à partir de la configuration.
+     * 
+     * Les écritures ne sont possibles que si read_only est faux et
+     * enable_write est vrai.
+     * 
+     * @param data_dir - Chemin vers le dossier contenant les fichiers HFSQL
+     * @param settings - Section [storage] de la configuration
+     * @returns Result<StorageEngine> - Moteur créé ou erreur
+     */
+    pub fn from_settings(data_dir: impl AsRef<Path>, settings: &StorageSettings) -> Result<Self> {
+        let mut engine = Self::new_with_parallel(data_dir, settings.read_only, settings.parallel)?;
+        engine.enable_write = settings.enable_write;
+        engine.mmap = settings.mmap;
+        engine.schema_dir = settings.schema_dir.clone();
+        engine.analysis_path = settings.analysis.clone();
+        for label in settings.encoding.iter().chain(settings.table_encodings.values()) {
+            if TextEncoding::for_label(label).is_none() {
+                anyhow::bail!("Encodage non supporté dans la configuration: {}", label);
+            }
+        }
+        engine.encoding = settings.encoding.clone();
+        engine.table_encodings = settings.table_encodings.clone();
+        Ok(engine)
+    }
+
     /**
      * Retourne le chemin du dossier de données.
      * 
@@ -238,9 +469,52 @@ impl StorageEngine {
             tables.push(name);
         }
 
+        *self.analysis.write().unwrap() = self.load_analysis();
+
         Ok(tables)
     }
 
+    /**
+     * Charge l'analyse (.wdd) du projet (configurée ou trouvée dans le
+     * dossier de données). Une analyse illisible est ignorée avec un
+     * avertissement.
+     */
+    fn load_analysis(&self) -> Option<Arc<Analysis>> {
+        let path = self.analysis_path.clone().or_else(|| wdd::find_analysis(&self.data_dir))?;
+        match Analysis::open(&path) {
+            Ok(analysis) => {
+                get_logger().log_with_source(
+                    LogLevel::Info,
+                    format!("Analyse {:?} chargée: {} fichier(s), {} liaison(s)", path, analysis.files.len(), analysis.links.len()),
+                    Some("Storage Engine".to_string()),
+                );
+                Some(Arc::new(analysis))
+            }
+            Err(e) => {
+                get_logger().log_with_source(LogLevel::Warn, format!("Analyse ignorée: {:#}", e), Some("Storage Engine".to_string()));
+                None
+            }
+        }
+    }
+
+    /**
+     * Retourne l'analyse (.wdd) chargée lors du dernier scan.
+     * 
+     * @returns Option<Arc<Analysis>> - Analyse, None si le projet n'en a pas
+     */
+    pub fn analysis(&self) -> Option<Arc<Analysis>> {
+        self.analysis.read().unwrap().clone()
+    }
+
+    /**
+     * Retourne les liaisons (clés étrangères) décrites par l'analyse.
+     * 
+     * @returns Vec<AnalysisLink> - Liaisons, vide sans analyse
+     */
+    pub fn relations(&self) -> Vec<AnalysisLink> {
+        self.analysis().map(|a| a.links.clone()).unwrap_or_default()
+    }
+
     /**
      * Traite les fichiers associés à une table (.mmo, .ndx).
      * 
@@ -255,7 +529,12 @@ impl StorageEngine {
             None
         };
 
+        // Index principal <table>.ndx, puis éventuels <table>.ndx0, .ndx1, ...
         let mut ndx_paths = Vec::new();
+        let main_ndx = data_dir.join(format!("{}.ndx", name));
+        if main_ndx.exists() {
+            ndx_paths.push(main_ndx);
+        }
         let mut i = 0;
         loop {
             let ndx_path = data_dir.join(format!("{}.ndx{}", name, i));
@@ -291,14 +570,18 @@ impl StorageEngine {
     /**
      * Obtient le schéma complet d'une table.
      * 
-     * Ouvre le fichier .fic, analyse sa structure et retourne le schéma
-     * avec la liste des champs, leurs types, offsets et longueurs.
+     * Un fichier de schéma `<table>.schema.toml/.json` (voir
+     * src/core/schema_file.rs) est prioritaire, puis l'analyse (.wdd) du
+     * projet ; sinon le fichier .fic est analysé. Retourne la liste des champs, leurs types, offsets et longueurs.
+     * Les rubriques texte sans encodage déclaré reçoivent celui configuré
+     * pour la table (storage.table_encodings / storage.encoding).
      * 
      * @param table - Nom de la table
-     * @returns Result<TableSchema> - Schéma de la table ou erreur
+     * @returns Result<TableSchema> - Schéma de la table ou erreur (y compris
+     *   si le fichier de schéma est invalide)
      * 
      * Effets de bord :
-     * - Lit le fichier .fic pour analyser le schéma
+     * - Lit le fichier .fic (et le fichier de schéma) pour analyser le schéma
      */
     pub fn get_schema(&self, table: &str) -> Result<TableSchema> {
         let tables = self.tables.read().unwrap();
@@ -306,7 +589,24 @@ impl StorageEngine {
             .with_context(|| format!("Table '{}' non trouvée", table))?;
 
         let fic = FicFile::open(&table_files.fic_path)?;
-        let fields = fic.analyze_schema();
+        let fields = match schema_file::find_schema_file(table, &table_files.fic_path, self.schema_dir.as_deref()) {
+            Some(path) => {
+                let fields = schema_file::read_schema_file(&path)?;
+                schema_file::validate_fields(&fields, fic.header().record_length)
+                    .with_context(|| format!("Schéma {:?} invalide", path))?;
+                fields
+            }
+            None => self.analysis_fields(table, &fic).unwrap_or_else(|| fic.analyze_schema()),
+        };
+
+        // Encodage configuré pour la table : rubriques texte sans encodage déclaré
+        let mut fields = fields;
+        if let Some(label) = self.table_encoding(table) {
+            fields
+                .iter_mut()
+                .filter(|f| f.encoding.is_none() && matches!(f.field_type, FieldType::String | FieldType::Memo))
+                .for_each(|f| f.encoding = Some(label.to_string()));
+        }
 
         Ok(TableSchema {
             name: table.to_string(),
@@ -316,21 +616,164 @@ impl StorageEngine {
         })
     }
 
+    /**
+     * Encodage configuré pour une table (storage.table_encodings, nom
+     * insensible à la casse, sinon storage.encoding).
+     * 
+     * @returns Option<&str> - Libellé, None sans configuration
+     */
+    fn table_encoding(&self, table: &str) -> Option<&str> {
+        self.table_encodings
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(table))
+            .map(|(_, label)| label.as_str())
+            .or(self.encoding.as_deref())
+    }
+
+    /**
+     * Rubriques d'une table d'après l'analyse (.wdd).
+     * 
+     * Une description incompatible avec le fichier (analyse d'une autre
+     * version) est ignorée avec un avertissement.
+     * 
+     * @returns Option<Vec<FieldInfo>> - Rubriques, None si la table n'est pas décrite
+     */
+    fn analysis_fields(&self, table: &str, fic: &FicFile) -> Option<Vec<FieldInfo>> {
+        let analysis = self.analysis()?;
+        let file = analysis.file(table)?;
+        let record_length = fic.header().record_length;
+        if file.schema.record_length != record_length {
+            get_logger().log_with_source(
+                LogLevel::Warn,
+                format!(
+                    "Analyse ignorée pour {}: enregistrements de {} bytes dans l'analyse, {} dans le fichier",
+                    table, file.schema.record_length, record_length
+                ),
+                Some("Storage Engine".to_string()),
+            );
+            return None;
+        }
+        Some(file.schema.fields.clone())
+    }
+
+    /**
+     * Retourne le fichier de schéma d'une table, s'il existe.
+     * 
+     * @param table - Nom de la table
+     * @returns Result<Option<(PathBuf, Vec<FieldInfo>)>> - Chemin et rubriques
+     *   du fichier, None sans fichier de schéma
+     * 
+     * Effets de bord :
+     * - Lit le fichier de schéma
+     */
+    pub fn schema_override(&self, table: &str) -> Result<Option<(PathBuf, Vec<FieldInfo>)>> {
+        let table_files = self.table_files(table)?;
+        match schema_file::find_schema_file(table, &table_files.fic_path, self.schema_dir.as_deref()) {
+            Some(path) => {
+                let fields = schema_file::read_schema_file(&path)?;
+                Ok(Some((path, fields)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /**
+     * Enregistre le fichier de schéma d'une table.
+     * 
+     * Le fichier existant est remplacé en gardant son format ; sinon un
+     * `<table>.schema.toml` est créé dans storage.schema_dir, ou à côté du
+     * .fic si aucun dossier n'est configuré.
+     * 
+     * @param table - Nom de la table
+     * @param fields - Rubriques (vérifiées contre la longueur d'enregistrement)
+     * @returns Result<PathBuf> - Chemin du fichier écrit
+     * 
+     * Effets de bord :
+     * - Crée ou remplace le fichier de schéma
+     */
+    pub fn save_schema_override(&self, table: &str, fields: Vec<FieldInfo>) -> Result<PathBuf> {
+        if self.read_only {
+            anyhow::bail!("Mode lecture seule activé");
+        }
+        let table_files = self.table_files(table)?;
+        let fic = FicFile::open(&table_files.fic_path)?;
+        schema_file::validate_fields(&fields, fic.header().record_length)?;
+
+        let path = match schema_file::find_schema_file(table, &table_files.fic_path, self.schema_dir.as_deref()) {
+            Some(path) => path,
+            None => {
+                let dir = match &self.schema_dir {
+                    Some(dir) => dir.clone(),
+                    None => table_files.fic_path.parent().map(Path::to_path_buf).unwrap_or_default(),
+                };
+                dir.join(format!("{}.{}", table, schema_file::SCHEMA_EXTENSIONS[0]))
+            }
+        };
+        schema_file::write_schema_file(&path, &fields)?;
+
+        get_logger().log_with_source(LogLevel::Info, format!("Schéma de {} enregistré dans {:?}", table, path), Some("Storage Engine".to_string()));
+        Ok(path)
+    }
+
+    /**
+     * Supprime le fichier de schéma d'une table (retour au schéma détecté).
+     * 
+     * @param table - Nom de la table
+     * @returns Result<bool> - true si un fichier a été supprimé
+     * 
+     * Effets de bord :
+     * - Supprime le fichier de schéma
+     */
+    pub fn delete_schema_override(&self, table: &str) -> Result<bool> {
+        if self.read_only {
+            anyhow::bail!("Mode lecture seule activé");
+        }
+        let table_files = self.table_files(table)?;
+        match schema_file::find_schema_file(table, &table_files.fic_path, self.schema_dir.as_deref()) {
+            Some(path) => {
+                std::fs::remove_file(&path)
+                    .with_context(|| format!("Impossible de supprimer le schéma: {:?}", path))?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
     /**
      * Sélectionne des enregistrements d'une table avec filtres et pagination.
      * 
-     * Lit tous les enregistrements de la table, applique les filtres par champ,
-     * puis retourne une page de résultats avec pagination.
+     * Lit tous les enregistrements de la table, applique les filtres par champ
+     * et la clause WHERE sur les valeurs décodées, trie selon ORDER BY, puis
+     * retourne une page de résultats. Le total compte les correspondances
+     * avant pagination.
      * 
      * @param table - Nom de la table
-     * @param filters - Filtres de requête (limit, offset, filtres par champ)
+     * @param filters - Filtres de requête (limit, offset, filtres, tri)
      * @returns Result<QueryResult> - Résultats de la requête ou erreur
      * 
      * Effets de bord :
      * - Lit les fichiers .fic/.mmo pour récupérer les données
      * - Décode les données selon le schéma de la table
+     * - Peut écrire des fichiers temporaires pour trier les grandes tables
      */
     pub fn select(&self, table: &str, filters: QueryFilters) -> Result<QueryResult> {
+        self.select_in(None, table, filters)
+    }
+
+    /**
+     * Sélectionne des enregistrements en tenant compte d'une transaction.
+     * 
+     * Les modifications en attente de la transaction remplacent les
+     * enregistrements lus sur le disque ; sans transaction, équivalent à
+     * select.
+     * 
+     * @param txn - Transaction de la session (None hors transaction)
+     * @param table - Nom de la table
+     * @param filters - Filtres de requête (limit, offset, filtres, tri)
+     * @returns Result<QueryResult> - Résultats de la requête ou erreur
+     */
+    pub fn select_in(&self, txn: Option<&Transaction>, table: &str, filters: QueryFilters) -> Result<QueryResult> {
+        let _guard = self.write_lock.read().unwrap();
         let tables = self.tables.read().unwrap();
         let table_files = tables.get(table)
             .with_context(|| format!("Table '{}' non trouvée", table))?;
@@ -338,59 +781,220 @@ impl StorageEngine {
         // Obtenir le schéma pour décoder les champs
         let schema = self.get_schema(table)?;
 
+        let mut fic = FicFile::open(&table_files.fic_path)
+            .with_context(|| format!("Impossible d'ouvrir le fichier .fic: {:?}", table_files.fic_path))?;
+        fic.set_mmap(self.mmap);
+        let mut mmo = table_files.mmo_path.as_ref()
+            .map(MmoFile::open)
+            .transpose()
+            .with_context(|| "Erreur lors de l'ouverture du fichier .mmo")?;
+
+        // Les enregistrements sont lus au fil du parcours (par blocs, ou
+        // projection du fichier) : seuls ceux de la page demandée sont décodés
+        let records = fic.records()
+            .with_context(|| format!("Erreur lors de la lecture des enregistrements de la table '{}'", table))?
+            .filter(|raw| !raw.deleted);
+        match txn.and_then(|t| t.changes(table)) {
+            Some(changes) => self.filter_and_paginate(changes.overlay(records), &schema, table_files, &mut mmo, &filters),
+            None => self.filter_and_paginate(records, &schema, table_files, &mut mmo, &filters),
+        }
+    }
+
+    /**
+     * Sélectionne des enregistrements via un index .ndx sur une colonne.
+     * 
+     * Parcourt l'intervalle [lower, upper] de la clé portant sur la colonne,
+     * puis lit uniquement les enregistrements trouvés (FicFile::read_record).
+     * Les filtres complets (WHERE, tri, pagination) sont ensuite appliqués
+     * comme pour select : les bornes ne servent qu'à réduire les lectures.
+     * 
+     * @param table - Nom de la table
+     * @param column - Colonne indexée (insensible à la casse)
+     * @param lower - Borne basse (valeur décodée)
+     * @param upper - Borne haute (valeur décodée)
+     * @param filters - Filtres de requête complets
+     * @returns Result<Option<QueryResult>> - Résultats, ou None si aucun index
+     *   exploitable (l'appelant se replie sur select)
+     * 
+     * Effets de bord :
+     * - Lit le fichier .ndx puis les enregistrements trouvés dans le .fic
+     */
+    pub fn select_by_index(
+        &self,
+        table: &str,
+        column: &str,
+        lower: Bound<&FieldValue>,
+        upper: Bound<&FieldValue>,
+        filters: QueryFilters,
+    ) -> Result<Option<QueryResult>> {
+        let _guard = self.write_lock.read().unwrap();
+        let schema = self.get_schema(table)?;
+        let Some(field) = schema.fields.iter().find(|f| f.name.eq_ignore_ascii_case(column)) else {
+            return Ok(None);
+        };
+        let Some(ids) = self.index_record_ids(table, field, lower, upper)? else {
+            return Ok(None);
+        };
+
+        let table_files = self.table_files(table)?;
         let mut fic = FicFile::open(&table_files.fic_path)
             .with_context(|| format!("Impossible d'ouvrir le fichier .fic: {:?}", table_files.fic_path))?;
         let mut mmo = table_files.mmo_path.as_ref()
-            .map(|p| MmoFile::open(p))
+            .map(MmoFile::open)
             .transpose()
             .with_context(|| "Erreur lors de l'ouverture du fichier .mmo")?;
 
-        let all_records = fic.read_all_records()
-            .with_context(|| format!("Erreur lors de la lecture des enregistrements de la table '{}'", table))?;
-        let total = all_records.len() as u32;
+        let mut records = Vec::with_capacity(ids.len());
+        for id in ids {
+            let record = fic.read_record(id)?;
+            if !record.deleted {
+                records.push(record);
+            }
+        }
+
+        let raw_records = records.iter().map(FicRecord::as_raw);
+        self.filter_and_paginate(raw_records, &schema, &table_files, &mut mmo, &filters).map(Some)
+    }
+
+    /**
+     * Décode, filtre, trie et pagine des enregistrements bruts (select).
+     * 
+     * Les enregistrements sont consommés au fil de l'eau : sans tri, seule la
+     * page est gardée, et le parcours s'arrête dès qu'elle est remplie si le
+     * total n'est pas demandé (count_total).
+     * 
+     * @param all_records - Enregistrements candidats (actifs), dans l'ordre des ids ;
+     *   seuls ceux à décoder sont copiés
+     * @returns Result<QueryResult> - Page de résultats et total des correspondances
+     */
+    fn filter_and_paginate<'a>(
+        &self,
+        mut all_records: impl Iterator<Item = RawRecord<'a>>,
+        schema: &TableSchema,
+        table_files: &TableFiles,
+        mmo: &mut Option<MmoFile>,
+        filters: &QueryFilters,
+    ) -> Result<QueryResult> {
+        // Une colonne inconnue est une erreur (sinon IS NULL retiendrait tout)
+        filter::check_columns(&filters.field_filters, filters.where_clause.as_ref(), |column| {
+            column.eq_ignore_ascii_case("id") || schema.fields.iter().any(|f| f.name.eq_ignore_ascii_case(column))
+        })?;
 
-        // Application des filtres
         let offset = filters.offset.unwrap_or(0);
-        let limit = filters.limit.unwrap_or(100);
+        let limit = filters.limit.map_or(usize::MAX, |limit| limit as usize);
+        let page_full = |page_len: usize| !filters.count_total && page_len >= limit;
 
-        // Paralléliser le décodage des enregistrements si on en a beaucoup et si le multi-threading est activé
-        let records_to_decode: Vec<FicRecord> = all_records
-            .into_iter()
-            .skip(offset as usize)
-            .take(limit as usize)
-            .collect();
+        let (records, total) = if !filters.order_by.is_empty() {
+            // Tri : on décode et filtre par blocs, le trieur externe borne
+            // la mémoire utilisée puis on pagine la sortie triée
+            let mut sorter = ExternalSorter::new(filters.order_by.clone(), DEFAULT_SORT_RUN_SIZE);
+            loop {
+                let chunk: Vec<FicRecord> = all_records.by_ref().take(DEFAULT_SORT_RUN_SIZE).map(|r| r.into_record(None)).collect();
+                if chunk.is_empty() {
+                    break;
+                }
+                for record in self.decode_records(chunk, schema, table_files, mmo)? {
+                    if filter::matches_filters(&record, &filters.field_filters, filters.where_clause.as_ref()) {
+                        sorter.push(record)?;
+                    }
+                }
+            }
+            let total = sorter.len() as u32;
+            let page = sorter
+                .finish()?
+                .skip(offset as usize)
+                .take(limit)
+                .collect::<Result<Vec<_>>>()?;
+            (page, total)
+        } else if filters.has_predicates() {
+            // Les filtres portent sur les valeurs décodées : on décode par
+            // blocs et on ne garde que les correspondances de la page
+            let mut page = Vec::new();
+            let mut total = 0u32;
+            while !page_full(page.len()) {
+                let chunk: Vec<FicRecord> = all_records.by_ref().take(DECODE_CHUNK_SIZE).map(|r| r.into_record(None)).collect();
+                if chunk.is_empty() {
+                    break;
+                }
+                for record in self.decode_records(chunk, schema, table_files, mmo)? {
+                    if filter::matches_filters(&record, &filters.field_filters, filters.where_clause.as_ref()) {
+                        if total >= offset && page.len() < limit {
+                            page.push(record);
+                        }
+                        total += 1;
+                    }
+                }
+            }
+            (page, total)
+        } else {
+            // Sans filtre, on ne décode que la page demandée
+            let mut records_to_decode = Vec::new();
+            let mut total = 0u32;
+            while !page_full(records_to_decode.len()) {
+                let Some(record) = all_records.next() else {
+                    break;
+                };
+                // Les enregistrements hors de la page sont comptés sans être copiés
+                if total >= offset && records_to_decode.len() < limit {
+                    records_to_decode.push(record.into_record(None));
+                }
+                total += 1;
+            }
+            (self.decode_records(records_to_decode, schema, table_files, mmo)?, total)
+        };
+
+        Ok(QueryResult {
+            records,
+            total,
+            offset,
+            limit: filters.limit,
+        })
+    }
 
-        let records: Vec<Record> = if self.parallel && records_to_decode.len() > 50 {
+    /**
+     * Décode une liste d'enregistrements bruts selon le schéma.
+     * 
+     * Parallélise le décodage si on a beaucoup d'enregistrements et si le
+     * multi-threading est activé (chaque thread ouvre son propre .mmo).
+     * 
+     * @param records - Enregistrements bruts à décoder
+     * @param schema - Schéma de la table
+     * @param table_files - Fichiers de la table (pour rouvrir le .mmo)
+     * @param mmo - Handle vers le fichier .mmo pour la version séquentielle
+     * @returns Result<Vec<Record>> - Enregistrements décodés, dans le même ordre
+     * 
+     * Effets de bord :
+     * - Peut lire depuis le fichier .mmo pour les champs mémo
+     */
+    fn decode_records(
+        &self,
+        records: Vec<FicRecord>,
+        schema: &TableSchema,
+        table_files: &TableFiles,
+        mmo: &mut Option<MmoFile>,
+    ) -> Result<Vec<Record>> {
+        if self.parallel && records.len() > 50 {
             // Version parallèle : ouvrir un nouveau fichier MMO pour chaque thread si nécessaire
             let mmo_path = table_files.mmo_path.clone();
-            let schema_clone = schema.clone();
-            
-            records_to_decode
+
+            records
                 .into_par_iter()
                 .map(|r| {
-                    // Ouvrir un nouveau fichier MMO pour ce thread si nécessaire
                     let mut thread_mmo = mmo_path.as_ref()
-                        .map(|p| MmoFile::open(p))
+                        .map(MmoFile::open)
                         .transpose()
                         .with_context(|| "Erreur lors de l'ouverture du fichier .mmo pour le thread")?;
-                    
-                    self.record_from_fic_impl(r, &schema_clone, &mut thread_mmo)
+
+                    self.record_from_fic_impl(r, schema, &mut thread_mmo)
                 })
-                .collect::<Result<Vec<_>>>()?
+                .collect::<Result<Vec<_>>>()
         } else {
             // Version séquentielle pour petits ensembles
-            records_to_decode
+            records
                 .into_iter()
-                .map(|r| self.record_from_fic(r, &schema, &mut mmo))
-                .collect::<Result<Vec<_>>>()?
-        };
-
-        Ok(QueryResult {
-            records,
-            total,
-            offset,
-            limit,
-        })
+                .map(|r| self.record_from_fic(r, schema, mmo))
+                .collect::<Result<Vec<_>>>()
+        }
     }
 
     /**
@@ -414,7 +1018,7 @@ impl StorageEngine {
 
         let mut fic = FicFile::open(&table_files.fic_path)?;
         let mut mmo = table_files.mmo_path.as_ref()
-            .map(|p| MmoFile::open(p))
+            .map(MmoFile::open)
             .transpose()?;
 
         let record = fic.read_record(id)?;
@@ -422,44 +1026,188 @@ impl StorageEngine {
     }
 
     /**
-     * Convertit un FicRecord brut en Record décodé selon le schéma.
+     * Lit le contenu brut d'une rubrique mémo (photo, document ou texte).
      * 
-     * Décode les données brutes de l'enregistrement selon le schéma de la table,
-     * convertit chaque champ selon son type (entier, flottant, chaîne, binaire, mémo),
-     * et récupère les données mémo depuis le fichier .mmo si nécessaire.
-     * 
-     * @param fic_record - Enregistrement brut depuis le fichier .fic
-     * @param schema - Schéma de la table pour le décodage
-     * @param mmo - Handle optionnel vers le fichier .mmo
-     * @returns Result<Record> - Enregistrement décodé ou erreur
+     * @param table - Nom de la table
+     * @param id - Identifiant de l'enregistrement
+     * @param field - Nom de la rubrique mémo (insensible à la casse)
+     * @returns Result<Option<MemoContent>> - Contenu et type MIME, None si la
+     *   rubrique n'a pas de mémo pour cet enregistrement
      * 
      * Effets de bord :
-     * - Peut lire depuis le fichier .mmo pour les champs mémo
+     * - Lit l'enregistrement dans le .fic puis le bloc dans le .mmo
      */
-    fn record_from_fic(
-        &self,
-        fic_record: FicRecord,
-        schema: &TableSchema,
-        mmo: &mut Option<MmoFile>,
-    ) -> Result<Record> {
-        self.record_from_fic_impl(fic_record, schema, mmo)
+    pub fn read_memo(&self, table: &str, id: u32, field: &str) -> Result<Option<MemoContent>> {
+        let schema = self.get_schema(table)?;
+        let info = schema.fields.iter()
+            .find(|f| f.name.eq_ignore_ascii_case(field))
+            .with_context(|| format!("Rubrique '{}' inconnue dans la table '{}'", field, table))?;
+        if !matches!(info.field_type, FieldType::Memo) {
+            anyhow::bail!("La rubrique {} n'est pas un mémo", info.name);
+        }
+
+        let files = self.table_files(table)?;
+        let record = FicFile::open(&files.fic_path)?.read_record(id)?;
+        if record.deleted {
+            anyhow::bail!("L'enregistrement {} est supprimé", id);
+        }
+        let Some(pointer) = FicFile::memo_pointers(&record.data, std::slice::from_ref(info)).pop() else {
+            return Ok(None);
+        };
+        let mmo_path = files.mmo_path.as_ref()
+            .with_context(|| format!("Aucun fichier .mmo pour la table '{}'", table))?;
+        let block = MmoFile::open(mmo_path)?.read_block(pointer.offset)?;
+
+        Ok(Some(match memo_value(info, &block) {
+            MemoValue::Binary { mime_type, .. } => MemoContent { mime_type, data: block.data },
+            MemoValue::Text { value } => MemoContent {
+                mime_type: "text/plain; charset=utf-8".to_string(),
+                data: value.into_bytes(),
+            },
+        }))
     }
 
     /**
-     * Implémentation interne de record_from_fic (utilisée aussi en parallèle).
+     * Prépare des recherches répétées sur une colonne via un index .ndx
+     * couvrant cette colonne (jointure par index).
+     * 
+     * Le schéma, l'index et les fichiers .fic/.mmo sont ouverts une seule
+     * fois. Le verrou de lecture du moteur est conservé tant que la
+     * recherche existe : un COMMIT attend donc sa libération.
+     * 
+     * @param table - Nom de la table
+     * @param column - Colonne recherchée (insensible à la casse)
+     * @returns Result<Option<IndexLookup>> - Recherche prête, ou None si aucun
+     *   index ne couvre la colonne (l'appelant parcourt alors la table)
+     * 
+     * Effets de bord :
+     * - Ouvre les fichiers .ndx, .fic et .mmo de la table
      */
-    fn record_from_fic_impl(
-        &self,
-        fic_record: FicRecord,
-        schema: &TableSchema,
-        mmo: &mut Option<MmoFile>,
-    ) -> Result<Record> {
-        use crate::core::FieldType;
-        use byteorder::{LittleEndian, ReadBytesExt};
-        use std::io::Cursor;
-
-        let mut fields = HashMap::new();
+    pub fn index_lookup(&self, table: &str, column: &str) -> Result<Option<IndexLookup<'_>>> {
+        let guard = self.write_lock.read().unwrap();
+        let schema = self.get_schema(table)?;
+        let Some(field) = schema.fields.iter().find(|f| f.name.eq_ignore_ascii_case(column)).cloned() else {
+            return Ok(None);
+        };
+        let Some((ndx, key_no)) = self.index_for_field(table, &field)? else {
+            return Ok(None);
+        };
+
+        let table_files = self.table_files(table)?;
+        let fic = FicFile::open(&table_files.fic_path)?;
+        let mmo = table_files.mmo_path.as_ref()
+            .map(MmoFile::open)
+            .transpose()?;
+
+        Ok(Some(IndexLookup { engine: self, _guard: guard, schema, field, ndx, key_no, fic, mmo }))
+    }
+
+    /**
+     * Retourne les ids des enregistrements dont la rubrique est comprise
+     * dans [lower, upper] selon un index .ndx, triés par id.
+     * 
+     * L'intervalle parcouru peut être plus large que demandé : les chaînes
+     * sont décodées jusqu'au premier zéro puis sans espaces de fin, donc la
+     * valeur "AB" couvre toutes les clés commençant par "AB". L'appelant
+     * réapplique ses filtres sur les enregistrements décodés.
+     * 
+     * L'index n'est utilisé que si son ordre correspond à celui des
+     * comparaisons SQL : entiers de 1 à 8 bytes (clé signée pour Integer,
+     * non signée pour Unsigned) et chaînes ASCII, sensibles à la casse pour
+     * un intervalle.
+     * 
+     * @returns Result<Option<Vec<u32>>> - Ids, ou None si aucun index exploitable
+     * 
+     * Effets de bord :
+     * - Lit les pages du .ndx parcourues
+     */
+    fn index_record_ids(
+        &self,
+        table: &str,
+        field: &FieldInfo,
+        lower: Bound<&FieldValue>,
+        upper: Bound<&FieldValue>,
+    ) -> Result<Option<Vec<u32>>> {
+        let Some((mut ndx, key_no)) = self.index_for_field(table, field)? else {
+            return Ok(None);
+        };
+        let Some(ids) = index_range_ids(&mut ndx, key_no, field, lower, upper)? else {
+            return Ok(None);
+        };
+        get_logger().log_with_source(
+            LogLevel::Debug,
+            format!("Index utilisé pour {}.{} : {} enregistrement(s) à lire", table, field.name, ids.len()),
+            Some("Storage Engine".to_string()),
+        );
+        Ok(Some(ids))
+    }
+
+    /**
+     * Retourne l'index .ndx dont une clé porte uniquement sur la rubrique donnée.
+     * 
+     * Les fichiers .ndx illisibles (format inconnu, corrompus) sont ignorés :
+     * la recherche se replie alors sur un parcours de la table.
+     * 
+     * @returns Result<Option<(NdxFile, usize)>> - Index ouvert et numéro de la clé
+     */
+    fn index_for_field(&self, table: &str, field: &FieldInfo) -> Result<Option<(NdxFile, usize)>> {
+        if matches!(field.field_type, FieldType::Memo) {
+            return Ok(None);
+        }
+        for ndx_path in &self.table_files(table)?.ndx_paths {
+            let ndx = match NdxFile::open(ndx_path) {
+                Ok(ndx) => ndx,
+                Err(e) => {
+                    get_logger().log_with_source(LogLevel::Debug, format!("Index ignoré {:?}: {:#}", ndx_path, e), Some("Storage Engine".to_string()));
+                    continue;
+                }
+            };
+            if let Some(key_no) = ndx.key_for_field(field.offset, field.length).map(|k| k.number) {
+                return Ok(Some((ndx, key_no)));
+            }
+        }
+        Ok(None)
+    }
+
+    /**
+     * Convertit un FicRecord brut en Record décodé selon le schéma.
+     * 
+     * Décode les données brutes de l'enregistrement selon le schéma de la table,
+     * convertit chaque champ selon son type (entier, flottant, chaîne, binaire, mémo),
+     * et récupère les données mémo depuis le fichier .mmo si nécessaire.
+     * 
+     * @param fic_record - Enregistrement brut depuis le fichier .fic
+     * @param schema - Schéma de la table pour le décodage
+     * @param mmo - Handle optionnel vers le fichier .mmo
+     * @returns Result<Record> - Enregistrement décodé ou erreur
+     * 
+     * Effets de bord :
+     * - Peut lire depuis le fichier .mmo pour les champs mémo
+     */
+    fn record_from_fic(
+        &self,
+        fic_record: FicRecord,
+        schema: &TableSchema,
+        mmo: &mut Option<MmoFile>,
+    ) -> Result<Record> {
+        self.record_from_fic_impl(fic_record, schema, mmo)
+    }
+
+    /**
+     * Implémentation interne de record_from_fic (utilisée aussi en parallèle).
+     */
+    fn record_from_fic_impl(
+        &self,
+        fic_record: FicRecord,
+        schema: &TableSchema,
+        mmo: &mut Option<MmoFile>,
+    ) -> Result<Record> {
+        use byteorder::{LittleEndian, ReadBytesExt};
+        use std::io::Cursor;
+
+        let mut fields = HashMap::new();
         let mut memo_data = HashMap::new();
+        let memo_pointers = FicFile::memo_pointers(&fic_record.data, &schema.fields);
 
         // Ajouter l'ID
         fields.insert("id".to_string(), FieldValue::integer(fic_record.id as i64));
@@ -494,24 +1242,16 @@ impl StorageEngine {
             let field_data = &full_data[offset..offset + length];
 
             let value = match field.field_type {
-                FieldType::Integer => {
-                    if length == 1 {
-                        FieldValue::integer(field_data[0] as i64)
-                    } else if length == 2 {
-                        let mut cursor = Cursor::new(field_data);
-                        FieldValue::integer(cursor.read_i16::<LittleEndian>().unwrap_or(0) as i64)
-                    } else if length == 4 {
-                        let mut cursor = Cursor::new(field_data);
-                        FieldValue::integer(cursor.read_i32::<LittleEndian>().unwrap_or(0) as i64)
-                    } else if length == 8 {
-                        let mut cursor = Cursor::new(field_data);
-                        FieldValue::integer(cursor.read_i64::<LittleEndian>().unwrap_or(0))
+                FieldType::Integer | FieldType::Unsigned => {
+                    if (1..=8).contains(&length) {
+                        decode_integer(field_data, matches!(field.field_type, FieldType::Integer))
                     } else {
                         // Pour les entiers de taille non standard, on lit comme u32
                         let mut cursor = Cursor::new(field_data);
                         FieldValue::integer(cursor.read_u32::<LittleEndian>().unwrap_or(0) as i64)
                     }
                 }
+                FieldType::Boolean => FieldValue::boolean(field_data.iter().any(|&b| b != 0)),
                 FieldType::Float => {
                     if length == 4 {
                         let mut cursor = Cursor::new(field_data);
@@ -523,22 +1263,27 @@ impl StorageEngine {
                         FieldValue::null()
                     }
                 }
-                FieldType::String => {
+                FieldType::Currency | FieldType::Numeric => {
+                    // Entier mis à l'échelle : décodé sans passer par un flottant
+                    match Decimal::from_le_bytes(field_data, field.decimal_scale()) {
+                        Some(decimal) => FieldValue::decimal(decimal),
+                        None => FieldValue::null(),
+                    }
+                }
+                FieldType::String | FieldType::Unicode => {
                     // Les chaînes sont généralement null-terminated ou padded
-                    // Trouver la fin de la chaîne (premier byte null ou fin des données)
-                    let null_pos = field_data.iter().position(|&b| b == 0).unwrap_or(field_data.len());
-                    let string_bytes = &field_data[..null_pos];
+                    // Trouver la fin de la chaîne (premier byte null, ou unité UTF-16 nulle)
+                    let encoding = field.text_encoding();
+                    let string_bytes = encoding.until_terminator(field_data);
                     
-                    // Décoder en utilisant Windows-1252 (CP1252) pour gérer les accents français
-                    // Essayer d'abord Windows-1252, puis UTF-8 si ça échoue
+                    // Décoder avec l'encodage du champ (Windows-1252 par défaut pour les
+                    // fichiers français, UTF-16LE pour les rubriques Unicode)
                     let string_value = if string_bytes.is_empty() {
                         String::new()
                     } else {
-                        // Essayer Windows-1252 d'abord (encodage standard pour fichiers français Windows)
-                        let (decoded, _, had_errors) = WINDOWS_1252.decode(string_bytes);
-                        let mut result = decoded.into_owned();
+                        let (mut result, had_errors) = encoding.decode(string_bytes);
                         
-                        // Si Windows-1252 a produit des erreurs, essayer UTF-8
+                        // Si le décodage a produit des erreurs, essayer UTF-8
                         if had_errors {
                             if let Ok(utf8_str) = std::str::from_utf8(string_bytes) {
                                 result = utf8_str.to_string();
@@ -562,48 +1307,22 @@ impl StorageEngine {
                     }
                 }
                 FieldType::Memo => {
-                    // Les mémos sont des pointeurs vers le fichier .mmo
-                    if length >= 4 {
-                        let mut cursor = Cursor::new(field_data);
-                        let pointer = cursor.read_u32::<LittleEndian>().unwrap_or(0);
-                        if pointer != 0 && pointer < 0xFFFFFFFF {
-                            // Lire le contenu du mémo
-                            if let Some(ref mut mmo_file) = mmo {
-                                if let Ok(text) = mmo_file.read_text(pointer) {
-                                    memo_data.insert(field.name.clone(), text);
-                                }
-                            }
+                    // Les mémos sont des pointeurs vers le fichier .mmo : texte ou
+                    // binaire (photo, document), selon le contenu du bloc
+                    let pointer = memo_pointers.iter().find(|p| p.field == field.name);
+                    if let (Some(pointer), Some(mmo_file)) = (pointer, mmo.as_mut()) {
+                        if let Ok(block) = mmo_file.read_block(pointer.offset) {
+                            memo_data.insert(field.name.clone(), memo_value(field, &block));
                         }
                     }
                     FieldValue::null()
                 }
-                FieldType::Date | FieldType::Unknown => {
-                    // Pour les dates et types inconnus, on affiche comme binaire ou string
-                    // Essayer de décoder comme chaîne (Windows-1252 ou UTF-8)
-                    let null_pos = field_data.iter().position(|&b| b == 0).unwrap_or(field_data.len());
-                    let string_bytes = &field_data[..null_pos];
-                    
-                    if string_bytes.is_empty() {
-                        FieldValue::binary(field_data.to_vec())
-                    } else {
-                        // Essayer Windows-1252 d'abord
-                        let (decoded, _, had_errors) = WINDOWS_1252.decode(string_bytes);
-                        let mut result = decoded.into_owned();
-                        
-                        // Si Windows-1252 a produit des erreurs, essayer UTF-8
-                        if had_errors {
-                            if let Ok(utf8_str) = std::str::from_utf8(string_bytes) {
-                                result = utf8_str.to_string();
-                                FieldValue::string(result.trim_end().to_string())
-                            } else {
-                                // Si les deux échouent, traiter comme binaire
-                                FieldValue::binary(field_data.to_vec())
-                            }
-                        } else {
-                            FieldValue::string(result.trim_end().to_string())
-                        }
-                    }
+                FieldType::Date | FieldType::Time | FieldType::DateTime | FieldType::Duration => {
+                    // Valeur ISO-8601 ; une zone illisible est affichée comme pour un type inconnu
+                    temporal::decode(&field.field_type, field_data)
+                        .unwrap_or_else(|| decode_text_or_binary(field_data))
                 }
+                FieldType::Unknown => decode_text_or_binary(field_data),
             };
 
             fields.insert(field.name.clone(), value);
@@ -619,60 +1338,1338 @@ impl StorageEngine {
     /**
      * Insère un nouvel enregistrement dans une table.
      * 
-     * Note : Non implémenté pour le moment. Retourne une erreur.
+     * L'insertion est appliquée immédiatement (transaction implicite).
      * 
-     * @param _table - Nom de la table
-     * @param _record - Données de l'enregistrement à insérer
+     * @param table - Nom de la table
+     * @param record - Données de l'enregistrement à insérer (id ignoré)
      * @returns Result<u32> - ID de l'enregistrement créé ou erreur
      * 
      * Effets de bord :
-     * - Devrait écrire dans le fichier .fic (non implémenté)
+     * - Écrit à la fin du fichier .fic et met à jour son header
+     * - Peut ajouter des blocs au fichier .mmo
      */
-    pub fn insert(&self, _table: &str, _record: Record) -> Result<u32> {
-        if self.read_only {
-            anyhow::bail!("Mode lecture seule activé");
+    pub fn insert(&self, table: &str, record: Record) -> Result<u32> {
+        let mut txn = self.begin();
+        let id = self.insert_in(&mut txn, table, record)?;
+        self.commit(txn)?;
+        Ok(id)
+    }
+
+    /**
+     * Met à jour un enregistrement existant (transaction implicite).
+     * 
+     * @param table - Nom de la table
+     * @param id - Identifiant de l'enregistrement à mettre à jour
+     * @param record - Nouvelles données de l'enregistrement
+     * @returns Result<()> - Succès ou erreur
+     * 
+     * Effets de bord :
+     * - Réécrit l'enregistrement dans le fichier .fic
+     * - Peut ajouter des blocs au fichier .mmo
+     */
+    pub fn update(&self, table: &str, id: u32, record: Record) -> Result<()> {
+        let mut txn = self.begin();
+        self.update_in(&mut txn, table, id, record)?;
+        self.commit(txn)
+    }
+
+    /**
+     * Supprime un enregistrement (transaction implicite).
+     * 
+     * @param table - Nom de la table
+     * @param id - Identifiant de l'enregistrement à supprimer
+     * @returns Result<()> - Succès ou erreur
+     * 
+     * Effets de bord :
+     * - Modifie le byte de flags de l'enregistrement et le header du .fic
+     */
+    pub fn delete(&self, table: &str, id: u32) -> Result<()> {
+        let mut txn = self.begin();
+        self.delete_in(&mut txn, table, id)?;
+        self.commit(txn)
+    }
+
+    /**
+     * Ouvre une transaction.
+     * 
+     * Les écritures faites avec insert_in / update_in / delete_in ne
+     * touchent pas aux fichiers avant commit.
+     * 
+     * @returns Transaction - Transaction vide avec un nouvel identifiant interne
+     */
+    pub fn begin(&self) -> Transaction {
+        let id = self.next_transaction_id.fetch_add(1, Ordering::Relaxed);
+        Transaction::new(id, self.table_locks.clone())
+    }
+
+    /**
+     * Valide une transaction : toutes ses écritures sont appliquées en un
+     * seul lot journalisé (tout ou rien).
+     * 
+     * @param txn - Transaction à valider (les verrous de tables sont libérés)
+     * @returns Result<()> - Succès ou erreur (aucune modification appliquée)
+     * 
+     * Effets de bord :
+     * - Écrit les fichiers .fic/.mmo modifiés par la transaction
+     */
+    pub fn commit(&self, mut txn: Transaction) -> Result<()> {
+        if txn.is_empty() {
+            return Ok(());
         }
-        // TODO: Implémenter l'écriture
-        anyhow::bail!("Écriture non implémentée pour le moment")
+        self.ensure_writable()?;
+        let batch = txn.take_batch();
+        // Les lectures attendent la fin de l'application du lot
+        let _guard = self.write_lock.write().unwrap();
+        self.journal.commit(&batch)
+    }
+
+    /**
+     * Annule une transaction : ses écritures sont abandonnées.
+     * 
+     * @param txn - Transaction à annuler (les verrous de tables sont libérés)
+     */
+    pub fn rollback(&self, txn: Transaction) {
+        drop(txn);
+    }
+
+    /**
+     * Insère un enregistrement dans une transaction.
+     * 
+     * Encode les champs fournis selon le schéma (les champs absents sont
+     * remplis de zéros ; une colonne inconnue est une erreur) et prépare l'ajout à la fin du .fic. Les champs
+     * mémo fournis sous forme de chaîne sont préparés dans le .mmo.
+     * 
+     * @param txn - Transaction recevant les écritures
+     * @param table - Nom de la table
+     * @param record - Données de l'enregistrement à insérer (id ignoré)
+     * @returns Result<u32> - ID de l'enregistrement créé ou erreur
+     * 
+     * Effets de bord :
+     * - Verrouille la table en écriture pour la transaction
+     */
+    pub fn insert_in(&self, txn: &mut Transaction, table: &str, record: Record) -> Result<u32> {
+        let schema = self.get_schema(table)?;
+        let record = resolve_columns(&schema, record)?;
+        let changes = self.table_changes(txn, table)?;
+
+        let mut slot = vec![0u8; changes.fic.header().record_length as usize];
+        let fields: Vec<&FieldInfo> = schema.fields
+            .iter()
+            .filter(|f| f.name != "id" && record.fields.contains_key(&f.name))
+            .collect();
+        encode_fields(changes, &fields, &record, &mut slot)?;
+
+        let (id, writes) = changes.fic.plan_append(&slot)
+            .with_context(|| format!("Erreur lors de l'insertion dans la table '{}'", table))?;
+        update_indexes(changes, id, None, Some(&slot))?;
+        changes.batch.push(&changes.files.fic_path, writes);
+        changes.slots.insert(id, slot);
+        Ok(id)
     }
 
     /**
-     * Met à jour un enregistrement existant.
+     * Met à jour un enregistrement dans une transaction.
      * 
-     * Note : Non implémenté pour le moment. Retourne une erreur.
+     * Les colonnes sont associées au schéma sans tenir compte de la casse
+     * (une colonne inconnue est une erreur). Seuls les champs dont la valeur
+     * diffère de la valeur actuellement décodée sont réencodés : les autres octets de l'enregistrement sont
+     * conservés tels quels.
      * 
-     * @param _table - Nom de la table
-     * @param _id - Identifiant de l'enregistrement à mettre à jour
-     * @param _record - Nouvelles données de l'enregistrement
+     * @param txn - Transaction recevant les écritures
+     * @param table - Nom de la table
+     * @param id - Identifiant de l'enregistrement à mettre à jour
+     * @param record - Nouvelles données de l'enregistrement
      * @returns Result<()> - Succès ou erreur
      * 
      * Effets de bord :
-     * - Devrait modifier le fichier .fic (non implémenté)
+     * - Verrouille la table en écriture pour la transaction
      */
-    pub fn update(&self, _table: &str, _id: u32, _record: Record) -> Result<()> {
-        if self.read_only {
-            anyhow::bail!("Mode lecture seule activé");
+    pub fn update_in(&self, txn: &mut Transaction, table: &str, id: u32, record: Record) -> Result<()> {
+        let schema = self.get_schema(table)?;
+        let record = resolve_columns(&schema, record)?;
+        let changes = self.table_changes(txn, table)?;
+
+        let previous = current_slot(changes, id)?;
+        let mut slot = previous.clone();
+        if slot[0] & 0x01 != 0 {
+            anyhow::bail!("L'enregistrement {} de la table '{}' est supprimé", id, table);
         }
-        anyhow::bail!("Écriture non implémentée pour le moment")
+
+        let current = self.record_from_fic(
+            FicRecord { id, deleted: false, data: slot[1..].to_vec(), memo_pointers: Vec::new() },
+            &schema,
+            &mut None,
+        )?;
+        let fields: Vec<&FieldInfo> = schema.fields
+            .iter()
+            .filter(|f| f.name != "id")
+            .filter(|f| match record.fields.get(&f.name) {
+                Some(value) => current.fields.get(&f.name) != Some(value),
+                None => false,
+            })
+            .collect();
+        if fields.is_empty() {
+            return Ok(());
+        }
+        encode_fields(changes, &fields, &record, &mut slot)?;
+
+        let writes = changes.fic.plan_write(id, &slot)
+            .with_context(|| format!("Erreur lors de la mise à jour de l'enregistrement {}", id))?;
+        update_indexes(changes, id, Some(&previous), Some(&slot))?;
+        changes.batch.push(&changes.files.fic_path, writes);
+        changes.slots.insert(id, slot);
+        Ok(())
     }
 
     /**
-     * Supprime un enregistrement (marque comme supprimé).
+     * Supprime un enregistrement dans une transaction.
      * 
-     * Note : Non implémenté pour le moment. Retourne une erreur.
+     * Positionne le flag de suppression de l'enregistrement et incrémente
+     * le nombre d'enregistrements supprimés du header.
      * 
-     * @param _table - Nom de la table
-     * @param _id - Identifiant de l'enregistrement à supprimer
+     * @param txn - Transaction recevant les écritures
+     * @param table - Nom de la table
+     * @param id - Identifiant de l'enregistrement à supprimer
      * @returns Result<()> - Succès ou erreur
      * 
      * Effets de bord :
-     * - Devrait marquer l'enregistrement comme supprimé dans le fichier .fic (non implémenté)
+     * - Verrouille la table en écriture pour la transaction
      */
-    pub fn delete(&self, _table: &str, _id: u32) -> Result<()> {
+    pub fn delete_in(&self, txn: &mut Transaction, table: &str, id: u32) -> Result<()> {
+        let changes = self.table_changes(txn, table)?;
+
+        let mut slot = current_slot(changes, id)?;
+        let writes = changes.fic.plan_delete(id, &slot)
+            .with_context(|| format!("Erreur lors de la suppression de l'enregistrement {}", id))?;
+        update_indexes(changes, id, Some(&slot), None)?;
+        changes.batch.push(&changes.files.fic_path, writes);
+        slot[0] |= 0x01;
+        changes.slots.insert(id, slot);
+        Ok(())
+    }
+
+    /**
+     * Reconstruit entièrement les index .ndx d'une table à partir du .fic.
+     * 
+     * Les descripteurs de clés de chaque index sont conservés ; les entrées
+     * sont recalculées à partir des enregistrements actifs. Chaque index est
+     * écrit dans un fichier temporaire puis renommé, de sorte qu'un index
+     * est toujours soit l'ancien, soit le nouveau.
+     * 
+     * @param table - Nom de la table
+     * @returns Result<usize> - Nombre total d'entrées écrites
+     * 
+     * Effets de bord :
+     * - Verrouille la table en écriture pendant la reconstruction
+     * - Remplace les fichiers .ndx de la table
+     */
+    pub fn reindex(&self, table: &str) -> Result<usize> {
+        self.ensure_writable()?;
+        let files = self.table_files(table)?;
+        let ndx_paths = own_indexes(&files.ndx_paths)?;
+        if ndx_paths.is_empty() {
+            anyhow::bail!("Aucun index .ndx de ce moteur pour la table '{}'", table);
+        }
+
+        // Réserve la table comme une transaction, puis bloque les lectures
+        let txn = self.begin();
+        txn.lock_table(table)?;
+        let _guard = self.write_lock.write().unwrap();
+
+        let mut fic = FicFile::open(&files.fic_path)?;
+        let slots: Vec<(u32, Vec<u8>)> = fic.read_all_records()?
+            .into_iter()
+            .filter(|r| !r.deleted)
+            .map(|r| {
+                let mut slot = vec![0u8];
+                slot.extend_from_slice(&r.data);
+                (r.id, slot)
+            })
+            .collect();
+
+        let mut total = 0;
+        for ndx_path in &ndx_paths {
+            let ndx = NdxFile::open(ndx_path)
+                .with_context(|| format!("Index {:?} illisible : descripteurs de clés introuvables", ndx_path))?;
+            let mut keys = Vec::new();
+            for key in ndx.keys() {
+                let entries = slots
+                    .iter()
+                    .map(|(id, slot)| Ok((key.encode(slot)?, *id)))
+                    .collect::<Result<Vec<_>>>()?;
+                total += entries.len();
+                keys.push((key.clone(), entries));
+            }
+            let bytes = NdxFile::build(ndx.page_size(), keys)
+                .with_context(|| format!("Reconstruction de {:?} impossible", ndx_path))?;
+
+            let mut temp_name = ndx_path.as_os_str().to_owned();
+            temp_name.push(".tmp");
+            let temp_path = PathBuf::from(temp_name);
+            let mut file = std::fs::File::create(&temp_path)?;
+            std::io::Write::write_all(&mut file, &bytes)?;
+            file.sync_all()?;
+            std::fs::rename(&temp_path, ndx_path)?;
+            get_logger().log_with_source(
+                LogLevel::Info,
+                format!("Index {:?} reconstruit ({} enregistrements)", ndx_path, slots.len()),
+                Some("Storage Engine".to_string()),
+            );
+        }
+        Ok(total)
+    }
+
+    /**
+     * Modifications en attente d'une table dans une transaction, créées à
+     * la première écriture (verrouillage de la table, ouverture des fichiers).
+     */
+    fn table_changes<'t>(&self, txn: &'t mut Transaction, table: &str) -> Result<&'t mut TableChanges> {
+        self.ensure_writable()?;
+        if !txn.touches(table) {
+            let files = self.table_files(table)?;
+            txn.lock_table(table)?;
+            let fic = FicFile::open(&files.fic_path)?;
+            // Header d'organisation non confirmée : compteurs à des offsets inconnus
+            if !fic.is_writable() {
+                anyhow::bail!("Header de {:?} non reconnu, écriture refusée", files.fic_path);
+            }
+            let mmo = files.mmo_path.as_ref().map(MmoFile::open).transpose()?;
+            // Un index de ce moteur illisible ne pourrait pas être tenu à jour :
+            // écriture refusée. Les index d'un autre format sont laissés intacts.
+            let indexes = own_indexes(&files.ndx_paths)?
+                .iter()
+                .map(|p| NdxFile::open(p).with_context(|| format!("Index {:?} illisible, écriture refusée", p)))
+                .collect::<Result<Vec<_>>>()?;
+            txn.tables.push((table.to_string(), TableChanges {
+                files,
+                fic,
+                mmo,
+                indexes,
+                slots: BTreeMap::new(),
+                batch: WriteBatch::new(),
+            }));
+        }
+        Ok(txn.changes_mut(table).unwrap())
+    }
+
+    /**
+     * Vérifie que les écritures sont autorisées.
+     * 
+     * @returns Result<()> - Erreur si le moteur est en lecture seule ou si
+     *   l'écriture est désactivée (storage.enable_write)
+     */
+    fn ensure_writable(&self) -> Result<()> {
         if self.read_only {
             anyhow::bail!("Mode lecture seule activé");
         }
-        anyhow::bail!("Écriture non implémentée pour le moment")
+        if !self.enable_write {
+            anyhow::bail!("Écriture désactivée (storage.enable_write = false)");
+        }
+        Ok(())
+    }
+
+    /// Fichiers d'une table (copie depuis le cache)
+    fn table_files(&self, table: &str) -> Result<TableFiles> {
+        self.tables.read().unwrap()
+            .get(table)
+            .cloned()
+            .with_context(|| format!("Table '{}' non trouvée", table))
     }
 }
 
+/**
+ * Associe les champs fournis aux rubriques du schéma, sans tenir compte de
+ * la casse (`nom` désigne la rubrique `NOM`).
+ * 
+ * @param schema - Schéma de la table
+ * @param record - Enregistrement reçu (INSERT, UPDATE, API)
+ * @returns Result<Record> - Champs renommés selon le schéma ; erreur pour une
+ *   colonne inconnue ou fournie deux fois
+ */
+fn resolve_columns(schema: &TableSchema, record: Record) -> Result<Record> {
+    let mut fields = HashMap::with_capacity(record.fields.len());
+    for (name, value) in record.fields {
+        let canonical = if name.eq_ignore_ascii_case("id") {
+            "id".to_string()
+        } else {
+            schema.fields.iter()
+                .find(|f| f.name.eq_ignore_ascii_case(&name))
+                .map(|f| f.name.clone())
+                .with_context(|| format!("Colonne inconnue dans la table '{}': {}", schema.name, name))?
+        };
+        if fields.insert(canonical, value).is_some() {
+            anyhow::bail!("Colonne {} fournie plusieurs fois", name);
+        }
+    }
+    Ok(Record { fields, ..record })
+}
+
+/**
+ * Index .ndx d'une table écrits par ce moteur.
+ * 
+ * Les fichiers d'un autre format (index WinDev) ne sont ni tenus à jour ni
+ * reconstruits : ils sont ignorés, avec un avertissement.
+ * 
+ * @param ndx_paths - Index trouvés pour la table
+ * @returns Result<Vec<PathBuf>> - Index de ce moteur, erreur si un fichier
+ *   ne peut pas être lu
+ */
+fn own_indexes(ndx_paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut own = Vec::with_capacity(ndx_paths.len());
+    for path in ndx_paths {
+        if NdxFile::is_own_format(path)? {
+            own.push(path.clone());
+        } else {
+            get_logger().log_with_source(
+                LogLevel::Warn,
+                format!("Index {:?} d'un format inconnu ignoré (laissé intact)", path),
+                Some("Storage Engine".to_string()),
+            );
+        }
+    }
+    Ok(own)
+}
+
+/**
+ * Emplacement actuel d'un enregistrement vu par une transaction (version
+ * modifiée dans la transaction, sinon contenu du fichier).
+ */
+fn current_slot(changes: &mut TableChanges, id: u32) -> Result<Vec<u8>> {
+    match changes.slots.get(&id) {
+        Some(slot) => Ok(slot.clone()),
+        None => changes.fic.read_slot(id),
+    }
+}
+
+/**
+ * Répercute la modification d'un enregistrement sur tous les index de la
+ * table : retrait de l'ancienne clé, ajout de la nouvelle (seulement si
+ * elle change).
+ * 
+ * @param changes - Modifications en attente de la table (index ouverts)
+ * @param id - ID de l'enregistrement
+ * @param old - Emplacement avant modification (None pour un ajout)
+ * @param new - Emplacement après modification (None pour une suppression)
+ * @returns Result<()> - Erreur si une clé unique est déjà utilisée
+ */
+fn update_indexes(changes: &mut TableChanges, id: u32, old: Option<&[u8]>, new: Option<&[u8]>) -> Result<()> {
+    // Un emplacement supprimé n'a pas d'entrée dans les index
+    let old = old.filter(|s| s[0] & 0x01 == 0);
+    let new = new.filter(|s| s[0] & 0x01 == 0);
+    for ndx in &mut changes.indexes {
+        for key in ndx.keys().to_vec() {
+            let old_key = old.map(|s| key.encode(s)).transpose()?;
+            let new_key = new.map(|s| key.encode(s)).transpose()?;
+            if old_key == new_key {
+                continue;
+            }
+            if let Some(old_key) = old_key {
+                ndx.plan_remove(key.number, &old_key, id)?;
+            }
+            if let Some(new_key) = new_key {
+                ndx.plan_insert(key.number, &new_key, id)
+                    .with_context(|| format!("Index {:?} : impossible d'ajouter l'enregistrement {}", ndx.path(), id))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/**
+ * Encode des champs d'un enregistrement dans son emplacement brut.
+ * 
+ * Inverse de record_from_fic_impl : les offsets du schéma incluent le
+ * byte de flags. Les mémos fournis sous forme de chaîne sont préparés en
+ * fin de .mmo (écritures ajoutées au lot de la table) et leur pointeur est
+ * écrit dans le champ.
+ * 
+ * @param changes - Modifications en attente de la table (.mmo et lot)
+ * @param fields - Champs à encoder
+ * @param record - Valeurs à encoder
+ * @param slot - Emplacement complet de l'enregistrement (modifié)
+ */
+fn encode_fields(changes: &mut TableChanges, fields: &[&FieldInfo], record: &Record, slot: &mut [u8]) -> Result<()> {
+    for field in fields {
+        let offset = field.offset as usize;
+        let length = field.length as usize;
+        if offset + length > slot.len() {
+            anyhow::bail!("Le champ {} dépasse la longueur de l'enregistrement", field.name);
+        }
+        let target = &mut slot[offset..offset + length];
+        let value = &record.fields[&field.name];
+
+        if matches!(field.field_type, FieldType::Memo) {
+            let (bytes, kind) = match value {
+                FieldValue::String { value } => {
+                    let encoding = field.text_encoding();
+                    let bytes = encoding.encode(value).with_context(|| format!(
+                        "Le mémo {} contient des caractères non représentables en {}", field.name, encoding.name()
+                    ))?;
+                    (bytes, MemoKind::Text)
+                }
+                // Mémo binaire : valeur hexadécimale
+                FieldValue::Binary { value } => {
+                    let bytes = hex::decode(value)
+                        .with_context(|| format!("Valeur hexadécimale invalide pour le mémo {}", field.name))?;
+                    (bytes, MemoKind::Binary)
+                }
+                // Le décodage retourne NULL pour les mémos : pointeur conservé
+                FieldValue::Null { .. } => continue,
+                _ => anyhow::bail!("Valeur invalide pour le mémo {}: texte ou binaire attendu", field.name),
+            };
+            let (mmo, mmo_path) = match (changes.mmo.as_mut(), changes.files.mmo_path.as_ref()) {
+                (Some(mmo), Some(path)) => (mmo, path),
+                _ => anyhow::bail!("Aucun fichier .mmo pour écrire le mémo {}", field.name),
+            };
+            if length < 4 {
+                anyhow::bail!("Champ mémo {} trop court pour un pointeur", field.name);
+            }
+            let (pointer, writes) = mmo.plan_append(&bytes, kind)?;
+            changes.batch.push(mmo_path, writes);
+            target.fill(0);
+            target[..4].copy_from_slice(&pointer.to_le_bytes());
+            continue;
+        }
+
+        encode_field_value(field, value, target)?;
+    }
+    Ok(())
+}
+
+/**
+ * Décode un entier little-endian de 1 à 8 bytes.
+ *
+ * Un entier non signé de 8 bytes au-delà de i64::MAX devient un décimal
+ * pour ne pas changer de signe.
+ *
+ * @param field_data - Zone du champ (1 à 8 bytes)
+ * @param signed - true pour étendre le bit de signe
+ * @returns FieldValue - Entier (ou décimal)
+ */
+fn decode_integer(field_data: &[u8], signed: bool) -> FieldValue {
+    let mut bytes = [0u8; 8];
+    bytes[..field_data.len()].copy_from_slice(field_data);
+    let raw = u64::from_le_bytes(bytes);
+    if signed {
+        let shift = 64 - 8 * field_data.len() as u32;
+        FieldValue::integer(((raw << shift) as i64) >> shift)
+    } else {
+        match i64::try_from(raw) {
+            Ok(value) => FieldValue::integer(value),
+            Err(_) => FieldValue::decimal(Decimal::new(raw as i128, 0)),
+        }
+    }
+}
+
+/**
+ * Décode une zone de type inconnu : chaîne (Windows-1252 puis UTF-8) si
+ * possible, sinon valeur binaire.
+ *
+ * @param field_data - Zone du champ dans l'enregistrement
+ * @returns FieldValue - Chaîne sans espaces de fin, ou binaire
+ */
+fn decode_text_or_binary(field_data: &[u8]) -> FieldValue {
+    let null_pos = field_data.iter().position(|&b| b == 0).unwrap_or(field_data.len());
+    let string_bytes = &field_data[..null_pos];
+
+    if string_bytes.is_empty() {
+        return FieldValue::binary(field_data.to_vec());
+    }
+    // Essayer Windows-1252 d'abord
+    let (decoded, _, had_errors) = WINDOWS_1252.decode(string_bytes);
+    if !had_errors {
+        return FieldValue::string(decoded.trim_end().to_string());
+    }
+    // Si Windows-1252 a produit des erreurs, essayer UTF-8
+    match std::str::from_utf8(string_bytes) {
+        Ok(utf8_str) => FieldValue::string(utf8_str.trim_end().to_string()),
+        // Si les deux échouent, traiter comme binaire
+        Err(_) => FieldValue::binary(field_data.to_vec()),
+    }
+}
+
+/**
+ * Encode une valeur dans la zone d'un champ (inverse du décodage).
+ * 
+ * - Entiers : little-endian sur 1 à 8 bytes, signés ou non (vérification des bornes)
+ * - Booléens : 1 ou 0 dans le premier byte
+ * - Flottants : f32 ou f64 little-endian
+ * - Monétaires, numériques : entier mis à l'échelle (voir decimal.rs)
+ * - Chaînes : encodage du champ (Windows-1252 par défaut, UTF-16LE pour
+ *   Unicode) complétées par des zéros
+ * - Dates, heures, durées : représentation HFSQL (voir temporal.rs)
+ * - Binaires : valeur hexadécimale décodée, complétée par des zéros
+ * - NULL : zone remplie de zéros
+ * 
+ * @param field - Description du champ
+ * @param value - Valeur à encoder
+ * @param target - Zone du champ dans l'enregistrement (field.length bytes)
+ * @returns Result<()> - Erreur si la valeur ne convient pas au champ
+ */
+fn encode_field_value(field: &FieldInfo, value: &FieldValue, target: &mut [u8]) -> Result<()> {
+    let length = target.len();
+    let invalid = || anyhow::anyhow!("Valeur {:?} invalide pour le champ {} ({:?})", value, field.name, field.field_type);
+
+    if let FieldValue::Null { .. } = value {
+        target.fill(0);
+        return Ok(());
+    }
+
+    match field.field_type {
+        FieldType::Integer | FieldType::Unsigned => {
+            let number: i128 = match value {
+                FieldValue::Integer { value } => *value as i128,
+                FieldValue::Boolean { value } => *value as i128,
+                FieldValue::Float { value } if value.fract() == 0.0 => *value as i128,
+                FieldValue::String { value } => value.trim().parse::<i128>().map_err(|_| invalid())?,
+                FieldValue::Decimal { value } => Decimal::parse(value)
+                    .and_then(|d| d.rescale(0))
+                    .map(|d| d.mantissa)
+                    .ok_or_else(invalid)?,
+                _ => return Err(invalid()),
+            };
+            let signed = matches!(field.field_type, FieldType::Integer);
+            let fits = match length {
+                1..=8 => {
+                    let bits = 8 * length as u32;
+                    if signed {
+                        let limit = 1i128 << (bits - 1);
+                        (-limit..limit).contains(&number)
+                    } else {
+                        (0..1i128 << bits).contains(&number)
+                    }
+                }
+                // Largeur non standard décodée comme u32
+                _ => u32::try_from(number).is_ok(),
+            };
+            if !fits {
+                anyhow::bail!("Valeur {} hors limites pour le champ {} ({} bytes)", number, field.name, length);
+            }
+            target.fill(0);
+            // Complément à deux : les bytes de poids faible suffisent
+            let bytes = (number as u64).to_le_bytes();
+            let width = length.min(8);
+            target[..width].copy_from_slice(&bytes[..width]);
+        }
+        FieldType::Boolean => {
+            let flag = match value {
+                FieldValue::Boolean { value } => *value,
+                FieldValue::Integer { value: 0 } => false,
+                FieldValue::Integer { value: 1 } => true,
+                FieldValue::String { value } => filter::parse_boolean(value).ok_or_else(invalid)?,
+                _ => return Err(invalid()),
+            };
+            target.fill(0);
+            target[0] = flag as u8;
+        }
+        FieldType::Float => {
+            let number = match value {
+                FieldValue::Float { value } => *value,
+                FieldValue::Integer { value } => *value as f64,
+                FieldValue::String { value } | FieldValue::Decimal { value } => value.trim().parse::<f64>().map_err(|_| invalid())?,
+                _ => return Err(invalid()),
+            };
+            match length {
+                4 => target.copy_from_slice(&(number as f32).to_le_bytes()),
+                8 => target.copy_from_slice(&number.to_le_bytes()),
+                _ => anyhow::bail!("Longueur de flottant non supportée pour le champ {}: {}", field.name, length),
+            }
+        }
+        FieldType::Currency | FieldType::Numeric => {
+            let number = match value {
+                FieldValue::Decimal { value } | FieldValue::String { value } => Decimal::parse(value),
+                FieldValue::Integer { value } => Some(Decimal::new(*value as i128, 0)),
+                // Un flottant passe par sa représentation décimale la plus courte
+                FieldValue::Float { value } => Decimal::parse(&value.to_string()),
+                _ => None,
+            }
+            .ok_or_else(invalid)?;
+            let scale = field.decimal_scale();
+            let bytes = number.to_le_bytes(length, scale).ok_or_else(|| anyhow::anyhow!(
+                "Valeur {} non représentable dans le champ {} ({} bytes, {} décimales)",
+                number, field.name, length, scale
+            ))?;
+            target.copy_from_slice(&bytes);
+        }
+        FieldType::Date | FieldType::Time | FieldType::DateTime | FieldType::Duration
+            if !matches!(value, FieldValue::Binary { .. }) =>
+        {
+            let bytes = temporal::encode(&field.field_type, value, length).ok_or_else(invalid)?;
+            target.copy_from_slice(&bytes);
+        }
+        FieldType::String | FieldType::Unicode | FieldType::Unknown | FieldType::Binary
+        | FieldType::Date | FieldType::Time | FieldType::DateTime | FieldType::Duration => {
+            let bytes = match value {
+                FieldValue::Binary { value } => hex::decode(value).map_err(|_| invalid())?,
+                FieldValue::String { value }
+                | FieldValue::Decimal { value }
+                | FieldValue::Date { value }
+                | FieldValue::Time { value }
+                | FieldValue::DateTime { value }
+                | FieldValue::Duration { value } => {
+                    let encoding = field.text_encoding();
+                    encoding.encode(value).with_context(|| format!(
+                        "Le champ {} contient des caractères non représentables en {}", field.name, encoding.name()
+                    ))?
+                }
+                FieldValue::Integer { value } => value.to_string().into_bytes(),
+                FieldValue::Boolean { value } => (*value as u8).to_string().into_bytes(),
+                FieldValue::Float { value } => value.to_string().into_bytes(),
+                FieldValue::Null { .. } => Vec::new(),
+            };
+            if bytes.len() > length {
+                anyhow::bail!("Valeur trop longue pour le champ {} ({} bytes, maximum {})", field.name, bytes.len(), length);
+            }
+            target.fill(0);
+            target[..bytes.len()].copy_from_slice(&bytes);
+        }
+        FieldType::Memo => unreachable!("les mémos sont encodés par encode_fields"),
+    }
+    Ok(())
+}
+
+/**
+ * Encode une borne de recherche sous la forme d'une clé d'index.
+ * 
+ * Les chaînes sont complétées par des zéros (borne basse) ou par 0xFF
+ * (borne haute) pour couvrir toutes les clés commençant par la valeur.
+ * 
+ * @param high - true pour une borne haute
+ * @returns Option<Vec<u8>> - Clé encodée, None si la valeur ne peut pas être encodée
+ */
+fn index_bound(key: &NdxKey, field: &FieldInfo, value: &FieldValue, high: bool) -> Option<Vec<u8>> {
+    let start = field.offset as usize;
+    let mut slot = vec![0u8; start + field.length as usize];
+    let target = &mut slot[start..];
+    match (&field.field_type, value) {
+        (FieldType::String, FieldValue::String { value }) => {
+            if value.len() > target.len() {
+                return None;
+            }
+            target.fill(if high { 0xFF } else { 0 });
+            target[..value.len()].copy_from_slice(value.as_bytes());
+        }
+        (FieldType::Integer | FieldType::Unsigned, FieldValue::Integer { .. }) => encode_field_value(field, value, target).ok()?,
+        _ => return None,
+    }
+    key.encode(&slot).ok()
+}
+
+/**
+ * Parcourt une clé d'index sur l'intervalle [lower, upper] d'une rubrique
+ * (voir StorageEngine::index_record_ids).
+ *
+ * @returns Result<Option<Vec<u32>>> - Ids triés, ou None si l'ordre de la clé
+ *   ne correspond pas aux comparaisons SQL pour ces bornes
+ */
+fn index_range_ids(
+    ndx: &mut NdxFile,
+    key_no: usize,
+    field: &FieldInfo,
+    lower: Bound<&FieldValue>,
+    upper: Bound<&FieldValue>,
+) -> Result<Option<Vec<u32>>> {
+    let key = ndx.keys()[key_no].clone();
+    let is_equality = matches!((lower, upper), (Bound::Included(a), Bound::Included(b)) if a == b);
+    if !index_order_matches(&key, field, [lower, upper], is_equality) {
+        return Ok(None);
+    }
+
+    // Bornes encodées (toujours incluses) ; une clé décroissante inverse l'intervalle
+    let encode = |bound: Bound<&FieldValue>, high: bool| -> Option<Bound<Vec<u8>>> {
+        Some(match bound {
+            Bound::Included(v) | Bound::Excluded(v) => Bound::Included(index_bound(&key, field, v, high)?),
+            Bound::Unbounded => Bound::Unbounded,
+        })
+    };
+    let (Some(mut low), Some(mut high)) = (encode(lower, false), encode(upper, true)) else {
+        return Ok(None);
+    };
+    if key.components[0].descending {
+        std::mem::swap(&mut low, &mut high);
+    }
+
+    let mut ids: Vec<u32> = ndx.range(key_no, as_slice_bound(&low), as_slice_bound(&high), None)?
+        .into_iter()
+        .map(|entry| entry.record_id)
+        .collect();
+    ids.sort_unstable();
+    ids.dedup();
+    Ok(Some(ids))
+}
+
+/**
+ * Indique si une clé d'index peut servir à rechercher des valeurs de la
+ * rubrique (types des bornes, ordre de la clé pour un intervalle).
+ * 
+ * Pour des chaînes ASCII, l'ordre Windows-1252 des clés est celui des
+ * chaînes Rust utilisé par les comparaisons SQL.
+ */
+fn index_order_matches(key: &NdxKey, field: &FieldInfo, bounds: [Bound<&FieldValue>; 2], is_equality: bool) -> bool {
+    let [component] = key.components.as_slice() else {
+        return false;
+    };
+    let bounds_ok = |check: fn(&FieldValue) -> bool| {
+        bounds.iter().all(|b| match b {
+            Bound::Included(v) | Bound::Excluded(v) => check(v),
+            Bound::Unbounded => true,
+        })
+    };
+    match field.field_type {
+        FieldType::Integer | FieldType::Unsigned => {
+            let expected = if matches!(field.field_type, FieldType::Unsigned) { KeyType::Unsigned } else { KeyType::Integer };
+            (1..=8).contains(&field.length)
+                && (is_equality || component.key_type == expected)
+                && bounds_ok(|v| matches!(v, FieldValue::Integer { .. }))
+        }
+        FieldType::String => {
+            field.text_encoding().is_ascii_compatible()
+                && matches!(component.key_type, KeyType::String | KeyType::Binary)
+                && (is_equality || !component.case_insensitive)
+                && bounds_ok(|v| matches!(v, FieldValue::String { value } if value.is_ascii()))
+        }
+        _ => false,
+    }
+}
+
+/// Contenu typé d'un mémo : texte décodé, ou type MIME et taille d'un binaire
+fn memo_value(field: &FieldInfo, block: &MmoBlock) -> MemoValue {
+    let size = block.data.len() as u64;
+    match (mmo::binary_mime_type(&block.data, field.text_encoding()), block.kind) {
+        (Some(mime), _) => MemoValue::Binary { mime_type: mime.to_string(), size },
+        // Mémo noté binaire dans son bloc, sans signature reconnue
+        (None, Some(MemoKind::Binary)) => MemoValue::Binary { mime_type: "application/octet-stream".to_string(), size },
+        (None, _) => MemoValue::Text { value: mmo::decode_text(&block.data, field.text_encoding()) },
+    }
+}
+
+/// Convertit une borne possédée en borne empruntée
+fn as_slice_bound(bound: &Bound<Vec<u8>>) -> Bound<&[u8]> {
+    match bound {
+        Bound::Included(k) => Bound::Included(k.as_slice()),
+        Bound::Excluded(k) => Bound::Excluded(k.as_slice()),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Settings;
+    use crate::core::fic::encode_header;
+
+    /// Crée un dossier contenant une table "clients" (header PCS, 2 enregistrements de 16 bytes)
+    fn create_data_dir() -> Result<tempfile::TempDir> {
+        let dir = tempfile::tempdir()?;
+        let mut bytes = encode_header(16, 2);
+        for i in 0..2u8 {
+            let mut slot = vec![0u8; 16];
+            slot[4] = i;
+            slot[5] = b'a' + i;
+            bytes.extend_from_slice(&slot);
+        }
+        std::fs::write(dir.path().join("clients.fic"), bytes)?;
+        Ok(dir)
+    }
+
+    fn all_records(engine: &StorageEngine) -> Result<Vec<Record>> {
+        let filters = QueryFilters::default();
+        Ok(engine.select("clients", filters)?.records)
+    }
+
+    fn record(fields: &[(&str, FieldValue)]) -> Record {
+        Record {
+            id: 0,
+            fields: fields.iter().map(|(k, v)| (k.to_string(), v.clone())).collect(),
+            memo_data: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_insert_update_delete() -> Result<()> {
+        let dir = create_data_dir()?;
+        let engine = StorageEngine::new(dir.path(), false)?;
+        engine.scan_tables()?;
+
+        let id = engine.insert("clients", record(&[
+            ("flags", FieldValue::Integer { value: 7 }),
+            ("data", FieldValue::Binary { value: "4142".to_string() }),
+        ]))?;
+        assert_eq!(id, 2);
+
+        engine.update("clients", 0, record(&[("flags", FieldValue::Integer { value: 9 })]))?;
+        engine.delete("clients", 1)?;
+        assert!(engine.delete("clients", 1).is_err());
+        assert!(engine.update("clients", 1, record(&[("flags", FieldValue::Integer { value: 1 })])).is_err());
+
+        let records = all_records(&engine)?;
+        let ids: Vec<u32> = records.iter().map(|r| r.id).collect();
+        assert_eq!(ids, vec![0, 2]);
+        assert_eq!(records[0].fields["flags"], FieldValue::Integer { value: 9 });
+        assert!(matches!(&records[1].fields["data"], FieldValue::Binary { value } if value.starts_with("4142")));
+
+        let fic = FicFile::open(dir.path().join("clients.fic"))?;
+        assert_eq!(fic.header().record_count, 3);
+        assert_eq!(fic.header().deleted_count, 1);
+
+        // Valeur hors limites pour un champ d'un byte
+        assert!(engine.insert("clients", record(&[("flags", FieldValue::Integer { value: 300 })])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_select_stops_when_total_not_needed() -> Result<()> {
+        let dir = create_data_dir()?;
+        let engine = StorageEngine::new(dir.path(), false)?;
+        engine.scan_tables()?;
+        engine.insert("clients", record(&[("flags", FieldValue::Integer { value: 7 })]))?;
+
+        let page = |count_total| QueryFilters {
+            limit: Some(1),
+            offset: Some(1),
+            count_total,
+            ..Default::default()
+        };
+        let result = engine.select("clients", page(true))?;
+        assert_eq!((result.records[0].id, result.total), (1, 3));
+
+        // Le parcours s'arrête après la page : seules 2 lignes sont vues
+        let result = engine.select("clients", page(false))?;
+        assert_eq!((result.records[0].id, result.total), (1, 2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_select_in_large_sparse_file() -> Result<()> {
+        use std::io::{Seek, SeekFrom, Write};
+
+        // 80 000 enregistrements de 70 000 bytes (5,6 Go, fichier creux)
+        let dir = tempfile::tempdir()?;
+        let (record_length, record_count) = (70_000u32, 80_000u32);
+        let mut file = std::fs::File::create(dir.path().join("big.fic"))?;
+        file.write_all(&encode_header(record_length, record_count))?;
+        file.set_len(0x20 + record_count as u64 * record_length as u64)?;
+        file.seek(SeekFrom::Start(0x20 + (record_count - 1) as u64 * record_length as u64))?;
+        file.write_all(b"\0LAST")?;
+        drop(file);
+        std::fs::write(dir.path().join("big.schema.toml"), "[[fields]]\nname = \"CODE\"\noffset = 1\nlength = 4\nfield_type = \"String\"\n")?;
+
+        let engine = StorageEngine::new(dir.path(), false)?;
+        engine.scan_tables()?;
+        let result = engine.select("big", QueryFilters {
+            limit: Some(1),
+            offset: Some(record_count - 1),
+            ..Default::default()
+        })?;
+        assert_eq!(result.total, record_count);
+        assert_eq!(result.records[0].fields["CODE"], FieldValue::String { value: "LAST".to_string() });
+        Ok(())
+    }
+
+    #[test]
+    fn test_columns_resolved_case_insensitively() -> Result<()> {
+        let dir = create_data_dir()?;
+        let engine = StorageEngine::new(dir.path(), false)?;
+        engine.scan_tables()?;
+
+        let id = engine.insert("clients", record(&[("FLAGS", FieldValue::Integer { value: 7 })]))?;
+        engine.update("clients", 0, record(&[("Flags", FieldValue::Integer { value: 9 })]))?;
+        let records = all_records(&engine)?;
+        assert_eq!(records[id as usize].fields["flags"], FieldValue::Integer { value: 7 });
+        assert_eq!(records[0].fields["flags"], FieldValue::Integer { value: 9 });
+
+        let error = engine.insert("clients", record(&[("flagz", FieldValue::Integer { value: 1 })])).unwrap_err();
+        assert!(error.to_string().contains("flagz"));
+        assert!(engine.update("clients", 0, record(&[("flagz", FieldValue::Integer { value: 1 })])).is_err());
+        assert!(engine.update("clients", 0, record(&[
+            ("flags", FieldValue::Integer { value: 1 }),
+            ("FLAGS", FieldValue::Integer { value: 2 }),
+        ])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_schema_from_descriptor() -> Result<()> {
+        use crate::core::descriptor::{encode_descriptor, TYPE_INTEGER, TYPE_TEXT};
+
+        let dir = tempfile::tempdir()?;
+        let mut bytes = encode_header(11, 1);
+        bytes.extend_from_slice(&encode_descriptor(&[("CODE", TYPE_TEXT, 1, 6), ("AGE", TYPE_INTEGER, 7, 4)]));
+        bytes.extend_from_slice(&[0, b'C', b'0', b'4', b'2', b' ', b' ', 42, 0, 0, 0]);
+        std::fs::write(dir.path().join("CLIENT.FIC"), bytes)?;
+
+        let engine = StorageEngine::new(dir.path(), false)?;
+        engine.scan_tables()?;
+        let schema = engine.get_schema("CLIENT")?;
+        let names: Vec<&str> = schema.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["CODE", "AGE"]);
+
+        let filters = QueryFilters::default();
+        let records = engine.select("CLIENT", filters)?.records;
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].fields["CODE"], FieldValue::String { value: "C042".to_string() });
+        assert_eq!(records[0].fields["AGE"], FieldValue::Integer { value: 42 });
+        Ok(())
+    }
+
+    #[test]
+    fn test_table_and_unicode_encodings() -> Result<()> {
+        let dir = create_data_dir()?;
+        let mut settings = Settings::default().storage;
+        settings.table_encodings.insert("CLIENTS".to_string(), "cp850".to_string());
+        let engine = StorageEngine::from_settings(dir.path(), &settings)?;
+        engine.scan_tables()?;
+
+        let field = |name: &str, offset, length, field_type| FieldInfo {
+            name: name.to_string(), offset, length, field_type, encoding: None, scale: None,
+        };
+        engine.save_schema_override("clients", vec![
+            field("nom", 5, 4, FieldType::String),
+            field("grec", 9, 6, FieldType::Unicode),
+        ])?;
+        let schema = engine.get_schema("clients")?;
+        assert_eq!(schema.fields[0].encoding.as_deref(), Some("cp850"));
+        assert_eq!(schema.fields[1].encoding, None);
+
+        engine.update("clients", 1, record(&[
+            ("nom", FieldValue::string("Ñu".to_string())),
+            ("grec", FieldValue::string("Σωκ".to_string())),
+        ]))?;
+        let updated = &all_records(&engine)?[1];
+        assert_eq!(updated.fields["nom"], FieldValue::String { value: "Ñu".to_string() });
+        assert_eq!(updated.fields["grec"], FieldValue::String { value: "Σωκ".to_string() });
+        let bytes = std::fs::read(dir.path().join("clients.fic"))?;
+        assert_eq!(bytes[0x20 + 16 + 5], 0xA5);
+        assert!(engine.update("clients", 1, record(&[("nom", FieldValue::string("€".to_string()))])).is_err());
+
+        settings.encoding = Some("x-inconnu".to_string());
+        assert!(StorageEngine::from_settings(dir.path(), &settings).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_text_and_binary_memos() -> Result<()> {
+        let dir = create_data_dir()?;
+        std::fs::write(dir.path().join("clients.mmo"), b"")?;
+        let engine = StorageEngine::new(dir.path(), false)?;
+        engine.scan_tables()?;
+
+        let memo = |name: &str, offset| FieldInfo {
+            name: name.to_string(), offset, length: 4, field_type: FieldType::Memo, encoding: None, scale: None,
+        };
+        engine.save_schema_override("clients", vec![memo("notes", 6), memo("photo", 10)])?;
+
+        let png = b"\x89PNG\r\n\x1A\n\0\0\0\rIHDR".to_vec();
+        let id = engine.insert("clients", record(&[
+            ("notes", FieldValue::string("Client fidèle".to_string())),
+            ("photo", FieldValue::binary(png.clone())),
+        ]))?;
+        let inserted = engine.get_by_id("clients", id)?;
+        assert_eq!(inserted.memo_data["notes"], MemoValue::Text { value: "Client fidèle".to_string() });
+        assert_eq!(inserted.memo_data["photo"], MemoValue::Binary { mime_type: "image/png".to_string(), size: png.len() as u64 });
+
+        let photo = engine.read_memo("clients", id, "PHOTO")?.expect("mémo photo");
+        assert_eq!((photo.mime_type.as_str(), photo.data), ("image/png", png));
+        assert!(engine.read_memo("clients", 0, "photo")?.is_none());
+        assert!(engine.read_memo("clients", id, "absent").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_integer_widths_and_booleans() -> Result<()> {
+        let field = |field_type: FieldType, length: u32| FieldInfo {
+            name: "x".to_string(), offset: 1, length, field_type, encoding: None, scale: None,
+        };
+        let round_trip = |info: &FieldInfo, value: FieldValue| -> Result<Vec<u8>> {
+            let mut target = vec![0u8; info.length as usize];
+            encode_field_value(info, &value, &mut target)?;
+            Ok(target)
+        };
+
+        // Clé de 3 bytes signée et non signée
+        let signed = field(FieldType::Integer, 3);
+        assert_eq!(round_trip(&signed, FieldValue::integer(-2))?, vec![0xFE, 0xFF, 0xFF]);
+        assert_eq!(decode_integer(&[0xFE, 0xFF, 0xFF], true), FieldValue::integer(-2));
+        assert_eq!(decode_integer(&[0xFE, 0xFF, 0xFF], false), FieldValue::integer(0xFFFFFE));
+        assert!(round_trip(&signed, FieldValue::integer(1 << 23)).is_err());
+        assert!(round_trip(&field(FieldType::Unsigned, 2), FieldValue::integer(-1)).is_err());
+        assert_eq!(round_trip(&field(FieldType::Unsigned, 1), FieldValue::integer(255))?, vec![255]);
+        assert_eq!(decode_integer(&u64::MAX.to_le_bytes(), false), FieldValue::Decimal { value: u64::MAX.to_string() });
+
+        let flag = field(FieldType::Boolean, 1);
+        assert_eq!(round_trip(&flag, FieldValue::boolean(true))?, vec![1]);
+        assert_eq!(round_trip(&flag, FieldValue::string("Faux".to_string()))?, vec![0]);
+        assert_eq!(round_trip(&field(FieldType::Integer, 4), FieldValue::boolean(true))?, vec![1, 0, 0, 0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_schema_override() -> Result<()> {
+        let dir = create_data_dir()?;
+        let engine = StorageEngine::new(dir.path(), false)?;
+        engine.scan_tables()?;
+        assert!(engine.schema_override("clients")?.is_none());
+
+        let fields = vec![
+            FieldInfo { name: "num".to_string(), offset: 4, length: 1, field_type: FieldType::Integer, encoding: None, scale: None },
+            FieldInfo { name: "lettre".to_string(), offset: 5, length: 1, field_type: FieldType::String, encoding: Some("utf-8".to_string()), scale: None },
+        ];
+        let path = engine.save_schema_override("clients", fields)?;
+        assert_eq!(path, dir.path().join("clients.schema.toml"));
+
+        let names: Vec<String> = engine.get_schema("clients")?.fields.into_iter().map(|f| f.name).collect();
+        assert_eq!(names, vec!["num", "lettre"]);
+        let records = all_records(&engine)?;
+        assert_eq!(records[1].fields["num"], FieldValue::Integer { value: 1 });
+        assert_eq!(records[1].fields["lettre"], FieldValue::String { value: "b".to_string() });
+
+        // Rubrique hors de l'enregistrement : refusée
+        let invalid = vec![FieldInfo { name: "x".to_string(), offset: 10, length: 8, field_type: FieldType::Binary, encoding: None, scale: None }];
+        assert!(engine.save_schema_override("clients", invalid).is_err());
+
+        assert!(engine.delete_schema_override("clients")?);
+        assert_eq!(engine.get_schema("clients")?.fields[0].name, "id");
+        Ok(())
+    }
+
+    #[test]
+    fn test_schema_from_analysis() -> Result<()> {
+        use crate::core::descriptor::{TYPE_INTEGER, TYPE_TEXT};
+
+        let dir = create_data_dir()?;
+        let ana_dir = dir.path().join("Projet.ana");
+        std::fs::create_dir(&ana_dir)?;
+        let analysis = crate::core::wdd::encode_analysis(
+            &[
+                ("Clients", "CLIENTS.FIC", 16, &[("NUM", TYPE_INTEGER, 4, 1), ("LETTRE", TYPE_TEXT, 5, 1)], &[(0, 2)]),
+                ("Commande", "COMMANDE.FIC", 16, &[("IDCMD", TYPE_INTEGER, 1, 4), ("NUM", TYPE_INTEGER, 5, 1)], &[]),
+            ],
+            &[[1, 1, 0, 0]],
+        );
+        std::fs::write(ana_dir.join("Projet.wdd"), analysis)?;
+
+        let engine = StorageEngine::new(dir.path(), false)?;
+        engine.scan_tables()?;
+        let names: Vec<String> = engine.get_schema("clients")?.fields.into_iter().map(|f| f.name).collect();
+        assert_eq!(names, vec!["NUM", "LETTRE"]);
+        assert_eq!(all_records(&engine)?[1].fields["LETTRE"], FieldValue::String { value: "b".to_string() });
+
+        let relations = engine.relations();
+        assert_eq!(relations.len(), 1);
+        assert_eq!((relations[0].from_table.as_str(), relations[0].to_table.as_str()), ("COMMANDE", "CLIENTS"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_lookup() -> Result<()> {
+        let dir = create_data_dir()?;
+        let component = crate::core::KeyComponent {
+            offset: 4,
+            length: 1,
+            key_type: crate::core::KeyType::Unsigned,
+            case_insensitive: false,
+            descending: false,
+        };
+        NdxFile::create(
+            dir.path().join("clients.ndx"),
+            crate::core::ndx::DEFAULT_PAGE_SIZE,
+            vec![(NdxKey::new(vec![component], true), vec![(vec![0], 0), (vec![1], 1)])],
+        )?;
+        let engine = StorageEngine::new(dir.path(), false)?;
+        engine.scan_tables()?;
+
+        let mut lookup = engine.index_lookup("clients", "FLAGS")?.expect("index sur flags");
+        let found = lookup.find(&FieldValue::Integer { value: 1 })?.expect("valeur entière");
+        assert_eq!(found.iter().map(|r| r.id).collect::<Vec<_>>(), vec![1]);
+        assert_eq!(lookup.find(&FieldValue::Integer { value: 5 })?.map(|r| r.len()), Some(0));
+        assert!(lookup.find(&FieldValue::String { value: "1".to_string() })?.is_none());
+        drop(lookup);
+        assert!(engine.index_lookup("clients", "data")?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_select_by_index_matches_scan() -> Result<()> {
+        let dir = create_data_dir()?;
+        let engine = StorageEngine::new(dir.path(), false)?;
+        engine.scan_tables()?;
+        for flags in [7, 3, 9, 3] {
+            engine.insert("clients", record(&[("flags", FieldValue::Integer { value: flags })]))?;
+        }
+        engine.delete("clients", 5)?;
+
+        // Index sur flags construit à partir des enregistrements actifs
+        let entries = all_records(&engine)?
+            .iter()
+            .map(|r| match r.fields["flags"] {
+                FieldValue::Integer { value } => (vec![value as u8], r.id),
+                _ => unreachable!(),
+            })
+            .collect();
+        let component = crate::core::KeyComponent {
+            offset: 4,
+            length: 1,
+            key_type: KeyType::Unsigned,
+            case_insensitive: false,
+            descending: false,
+        };
+        NdxFile::create(dir.path().join("clients.ndx"), 256, vec![(NdxKey::new(vec![component], false), entries)])?;
+        engine.scan_tables()?;
+
+        let where_clause = |sql: &str| match crate::sql::SqlParser::parse(sql).unwrap() {
+            crate::sql::parser::SqlStatement::Select(select) => select.where_clause,
+            _ => unreachable!(),
+        };
+        let filters = |sql: &str| QueryFilters {
+            where_clause: where_clause(sql),
+            ..Default::default()
+        };
+        let ids = |records: &[Record]| records.iter().map(|r| r.id).collect::<Vec<_>>();
+
+        let sql = "SELECT * FROM clients WHERE flags > 1 AND flags <= 7";
+        let indexed = engine
+            .select_by_index("clients", "flags", Bound::Excluded(&FieldValue::Integer { value: 1 }), Bound::Included(&FieldValue::Integer { value: 7 }), filters(sql))?
+            .expect("index sur flags");
+        assert_eq!(ids(&indexed.records), vec![2, 3]);
+        assert_eq!(ids(&indexed.records), ids(&engine.select("clients", filters(sql))?.records));
+
+        let three = FieldValue::Integer { value: 3 };
+        let sql = "SELECT * FROM clients WHERE flags = 3";
+        let indexed = engine.select_by_index("clients", "flags", Bound::Included(&three), Bound::Included(&three), filters(sql))?.unwrap();
+        assert_eq!(ids(&indexed.records), vec![3]);
+
+        // Types incompatibles avec la clé : repli sur un parcours complet
+        let text = FieldValue::String { value: "3".to_string() };
+        assert!(engine.select_by_index("clients", "flags", Bound::Included(&text), Bound::Unbounded, filters(sql))?.is_none());
+        Ok(())
+    }
+
+    /// Entrées (clé, id) de la clé 0 d'un index
+    fn index_entries(path: &Path) -> Result<Vec<(Vec<u8>, u32)>> {
+        Ok(NdxFile::open(path)?.entries(0)?.into_iter().map(|e| (e.key, e.record_id)).collect())
+    }
+
+    #[test]
+    fn test_index_maintenance_and_reindex() -> Result<()> {
+        let dir = create_data_dir()?;
+        let ndx_path = dir.path().join("clients.ndx");
+        let component = crate::core::KeyComponent {
+            offset: 4,
+            length: 1,
+            key_type: KeyType::Unsigned,
+            case_insensitive: false,
+            descending: false,
+        };
+        NdxFile::create(&ndx_path, 128, vec![(NdxKey::new(vec![component], true), vec![(vec![0], 0), (vec![1], 1)])])?;
+        let engine = StorageEngine::new(dir.path(), false)?;
+        engine.scan_tables()?;
+
+        engine.insert("clients", record(&[("flags", FieldValue::Integer { value: 7 })]))?;
+        engine.update("clients", 0, record(&[("flags", FieldValue::Integer { value: 5 })]))?;
+        engine.delete("clients", 1)?;
+        assert_eq!(index_entries(&ndx_path)?, vec![(vec![5], 0), (vec![7], 2)]);
+
+        // Clé unique : l'écriture entière est refusée
+        assert!(engine.insert("clients", record(&[("flags", FieldValue::Integer { value: 5 })])).is_err());
+        assert_eq!(all_records(&engine)?.len(), 2);
+
+        // Une transaction annulée ne modifie pas l'index
+        let mut txn = engine.begin();
+        engine.update_in(&mut txn, "clients", 2, record(&[("flags", FieldValue::Integer { value: 9 })]))?;
+        engine.rollback(txn);
+        assert_eq!(index_entries(&ndx_path)?, vec![(vec![5], 0), (vec![7], 2)]);
+
+        // REINDEX reconstruit l'index depuis le .fic
+        let stale = NdxFile::build(128, vec![(NdxKey::new(NdxFile::open(&ndx_path)?.keys()[0].components.clone(), true), Vec::new())])?;
+        std::fs::write(&ndx_path, stale)?;
+        assert_eq!(engine.reindex("clients")?, 2);
+        assert_eq!(index_entries(&ndx_path)?, vec![(vec![5], 0), (vec![7], 2)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_foreign_index_left_untouched() -> Result<()> {
+        let dir = create_data_dir()?;
+        let ndx_path = dir.path().join("clients.ndx");
+        let foreign = b"PCS\0\x0a\x00 index WinDev".to_vec();
+        std::fs::write(&ndx_path, &foreign)?;
+        let engine = StorageEngine::new(dir.path(), false)?;
+        engine.scan_tables()?;
+
+        engine.insert("clients", record(&[("flags", FieldValue::Integer { value: 7 })]))?;
+        engine.delete("clients", 0)?;
+        assert_eq!(all_records(&engine)?.len(), 2);
+        assert!(engine.reindex("clients").is_err());
+        assert_eq!(std::fs::read(&ndx_path)?, foreign);
+        Ok(())
+    }
+
+    #[test]
+    fn test_writes_rejected_when_disabled() -> Result<()> {
+        let dir = create_data_dir()?;
+        let read_only = StorageEngine::new(dir.path(), true)?;
+        read_only.scan_tables()?;
+        assert!(read_only.insert("clients", record(&[])).is_err());
+
+        let settings = StorageSettings {
+            read_only: false, enable_write: false, parallel: false, mmap: false, schema_dir: None, analysis: None,
+            encoding: None, table_encodings: HashMap::new(),
+        };
+        let disabled = StorageEngine::from_settings(dir.path(), &settings)?;
+        disabled.scan_tables()?;
+        assert!(disabled.delete("clients", 0).is_err());
+        assert_eq!(all_records(&disabled)?.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_writes_rejected_for_unknown_header() -> Result<()> {
+        let dir = create_data_dir()?;
+        let path = dir.path().join("clients.fic");
+        let mut bytes = std::fs::read(&path)?;
+        bytes[4] = 2; // Version inconnue
+        std::fs::write(&path, &bytes)?;
+
+        let engine = StorageEngine::new(dir.path(), false)?;
+        engine.scan_tables()?;
+        assert_eq!(all_records(&engine)?.len(), 2);
+        assert!(engine.insert("clients", record(&[])).is_err());
+        assert!(engine.delete("clients", 0).is_err());
+        assert_eq!(std::fs::read(&path)?, bytes);
+        Ok(())
+    }
+
+    #[test]
+    fn test_transaction_isolation_and_commit() -> Result<()> {
+        let dir = create_data_dir()?;
+        let engine = StorageEngine::new(dir.path(), false)?;
+        engine.scan_tables()?;
+        let mut txn = engine.begin();
+        let id = engine.insert_in(&mut txn, "clients", record(&[("flags", FieldValue::Integer { value: 5 })]))?;
+        engine.update_in(&mut txn, "clients", id, record(&[("flags", FieldValue::Integer { value: 6 })]))?;
+        engine.delete_in(&mut txn, "clients", 0)?;
+
+        // La transaction voit ses modifications, les autres lectures non
+        let seen: Vec<u32> = engine.select_in(Some(&txn), "clients", QueryFilters::default())?.records.iter().map(|r| r.id).collect();
+        assert_eq!(seen, vec![1, 2]);
+        assert_eq!(all_records(&engine)?.len(), 2);
+        assert_eq!(all_records(&engine)?[0].id, 0);
+
+        // Table verrouillée pour les autres écritures jusqu'à la fin de la transaction
+        assert!(engine.delete("clients", 1).is_err());
+
+        engine.commit(txn)?;
+        let records = all_records(&engine)?;
+        assert_eq!(records.iter().map(|r| r.id).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(records[1].fields["flags"], FieldValue::Integer { value: 6 });
+
+        // ROLLBACK : rien n'est écrit et la table est libérée
+        let mut txn = engine.begin();
+        engine.delete_in(&mut txn, "clients", 1)?;
+        engine.rollback(txn);
+        assert_eq!(all_records(&engine)?.len(), 2);
+        engine.delete("clients", 1)?;
+        assert_eq!(all_records(&engine)?.len(), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,488 @@
+/**
+ * Évaluation des prédicats WHERE sur les enregistrements décodés.
+ *
+ * Ce fichier contient l'évaluateur typé utilisé par le StorageEngine pour
+ * appliquer les conditions d'une clause WHERE aux enregistrements (Record)
+ * après décodage. Les comparaisons tiennent compte du type des valeurs :
+ *
+ * - Entiers et flottants comparés numériquement, décimaux (monétaire,
+ *   numérique) exactement
+ * - Chaînes comparées lexicographiquement (ou numériquement si la valeur
+ *   littérale est un nombre et que la chaîne en est un aussi)
+ * - Booléens comparés à TRUE/FALSE, 1/0 ou 'vrai'/'faux'
+ * - Dates, heures et durées comparées chronologiquement à un littéral
+ *   ISO-8601 (`'2024-01-15'`, `'14:30'`, `'PT2H'`) ou compact
+ * - LIKE avec les jokers `%` (n caractères) et `_` (un caractère),
+ *   insensible à la casse comme sous HFSQL
+ * - NULL : toute comparaison avec NULL est inconnue (logique à trois
+ *   valeurs de SQL) ; seul IS [NOT] NULL teste la nullité
+ *
+ * Les expressions (AND, OR, NOT, IN, BETWEEN) sont évaluées en logique
+ * à trois valeurs : un enregistrement n'est retenu que si l'expression
+ * vaut vrai. Les colonnes citées sont vérifiées au préalable
+ * (check_columns) : une colonne inconnue est une erreur, pas un NULL.
+ *
+ * Liens avec d'autres modules :
+ * - Utilisé par src/storage/engine.rs dans StorageEngine::select
+ * - Utilise les structures de src/sql/parser.rs (Expr, Condition)
+ */
+
+use crate::sql::parser::{ComparisonOperator, Expr, SqlValue};
+use crate::storage::decimal::Decimal;
+use crate::storage::engine::{FieldValue, Record};
+use crate::storage::temporal;
+use anyhow::Result;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/**
+ * Indique si un enregistrement satisfait l'ensemble des filtres.
+ *
+ * Les filtres simples par champ (field_filters) sont traités comme des
+ * égalités et combinés par AND avec la clause WHERE.
+ *
+ * @param record - Enregistrement décodé
+ * @param field_filters - Filtres d'égalité par champ (nom -> valeur texte)
+ * @param where_clause - Expression WHERE optionnelle
+ * @returns bool - true si l'enregistrement est retenu
+ *
+ * Effets de bord : Aucun
+ */
+pub fn matches_filters(
+    record: &Record,
+    field_filters: &HashMap<String, String>,
+    where_clause: Option<&Expr>,
+) -> bool {
+    let simple_ok = field_filters.iter().all(|(column, value)| {
+        let literal = SqlValue::String(value.clone());
+        compare_field(record, column, &ComparisonOperator::Equal, &literal) == Some(true)
+    });
+
+    simple_ok && where_clause.is_none_or(|expr| matches_expr(record, expr))
+}
+
+/**
+ * Vérifie que toutes les colonnes citées par des filtres existent.
+ *
+ * À appeler avant l'évaluation : une faute de frappe dans un nom de
+ * colonne ne doit ni tout sélectionner (IS NULL) ni ne rien sélectionner.
+ *
+ * @param field_filters - Filtres d'égalité par champ
+ * @param where_clause - Expression WHERE optionnelle
+ * @param is_known - Indique si une colonne existe (sans tenir compte de la casse)
+ * @returns Result<()> - Erreur nommant la première colonne inconnue
+ */
+pub fn check_columns(
+    field_filters: &HashMap<String, String>,
+    where_clause: Option<&Expr>,
+    is_known: impl Fn(&str) -> bool,
+) -> Result<()> {
+    let mut columns: Vec<&str> = field_filters.keys().map(String::as_str).collect();
+    if let Some(expr) = where_clause {
+        collect_columns(expr, &mut columns);
+    }
+    match columns.into_iter().find(|column| !is_known(column)) {
+        Some(column) => anyhow::bail!("Colonne inconnue: {}", column),
+        None => Ok(()),
+    }
+}
+
+/// Ajoute les colonnes citées par une expression
+fn collect_columns<'a>(expr: &'a Expr, columns: &mut Vec<&'a str>) {
+    match expr {
+        Expr::Comparison(condition) => columns.push(&condition.column),
+        Expr::And(left, right) | Expr::Or(left, right) => {
+            collect_columns(left, columns);
+            collect_columns(right, columns);
+        }
+        Expr::Not(inner) => collect_columns(inner, columns),
+        Expr::In { column, .. } | Expr::Between { column, .. } | Expr::IsNull { column, .. } => columns.push(column),
+    }
+}
+
+/**
+ * Indique si un enregistrement satisfait une expression WHERE.
+ *
+ * @param record - Enregistrement décodé
+ * @param expr - Expression à évaluer
+ * @returns bool - true uniquement si l'expression vaut vrai (inconnu = rejeté)
+ *
+ * Effets de bord : Aucun
+ */
+pub fn matches_expr(record: &Record, expr: &Expr) -> bool {
+    evaluate(record, expr) == Some(true)
+}
+
+/**
+ * Évalue une expression en logique à trois valeurs.
+ *
+ * @param record - Enregistrement décodé
+ * @param expr - Expression à évaluer
+ * @returns Option<bool> - Some(vrai/faux) ou None si le résultat est inconnu (NULL)
+ *
+ * Effets de bord : Aucun
+ */
+pub fn evaluate(record: &Record, expr: &Expr) -> Option<bool> {
+    match expr {
+        Expr::Comparison(condition) => {
+            compare_field(record, &condition.column, &condition.operator, &condition.value)
+        }
+        Expr::And(left, right) => match (evaluate(record, left), evaluate(record, right)) {
+            (Some(false), _) | (_, Some(false)) => Some(false),
+            (Some(true), Some(true)) => Some(true),
+            _ => None,
+        },
+        Expr::Or(left, right) => match (evaluate(record, left), evaluate(record, right)) {
+            (Some(true), _) | (_, Some(true)) => Some(true),
+            (Some(false), Some(false)) => Some(false),
+            _ => None,
+        },
+        Expr::Not(inner) => evaluate(record, inner).map(|b| !b),
+        Expr::In { column, values, negated } => {
+            let value = lookup_field(record, column)?;
+            let mut unknown = false;
+            for literal in values {
+                match compare_values(value, literal) {
+                    Some(Ordering::Equal) => return Some(!negated),
+                    Some(_) => {}
+                    None => unknown = true,
+                }
+            }
+            // Sans correspondance, un NULL dans la liste rend le résultat inconnu
+            if unknown { None } else { Some(*negated) }
+        }
+        Expr::Between { column, low, high, negated } => {
+            let value = lookup_field(record, column)?;
+            let above_low = compare_values(value, low).map(|o| o != Ordering::Less);
+            let below_high = compare_values(value, high).map(|o| o != Ordering::Greater);
+            let inside = match (above_low, below_high) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            };
+            inside.map(|b| b != *negated)
+        }
+        Expr::IsNull { column, negated } => {
+            // Colonnes vérifiées par check_columns : un champ absent de
+            // l'enregistrement (mémo non renseigné) vaut NULL
+            let is_null = lookup_field(record, column)
+                .is_none_or(|v| matches!(v, FieldValue::Null { .. }));
+            Some(is_null != *negated)
+        }
+    }
+}
+
+/// Compare le champ `column` de l'enregistrement à une valeur littérale
+fn compare_field(record: &Record, column: &str, operator: &ComparisonOperator, literal: &SqlValue) -> Option<bool> {
+    // Champ absent de l'enregistrement : résultat inconnu
+    let value = lookup_field(record, column)?;
+
+    if matches!(operator, ComparisonOperator::Like) {
+        let text = field_value_text(value)?;
+        let pattern = literal_text(literal)?;
+        return Some(like_match(&text, &pattern));
+    }
+
+    let ordering = compare_values(value, literal)?;
+
+    Some(match operator {
+        ComparisonOperator::Equal => ordering == Ordering::Equal,
+        ComparisonOperator::NotEqual => ordering != Ordering::Equal,
+        ComparisonOperator::GreaterThan => ordering == Ordering::Greater,
+        ComparisonOperator::LessThan => ordering == Ordering::Less,
+        ComparisonOperator::GreaterThanOrEqual => ordering != Ordering::Less,
+        ComparisonOperator::LessThanOrEqual => ordering != Ordering::Greater,
+        ComparisonOperator::Like => unreachable!(),
+    })
+}
+
+/**
+ * Recherche un champ dans l'enregistrement, sans tenir compte de la casse.
+ *
+ * Les noms de rubriques HFSQL sont insensibles à la casse : `nom` et `NOM`
+ * désignent le même champ.
+ */
+pub fn lookup_field<'a>(record: &'a Record, column: &str) -> Option<&'a FieldValue> {
+    record.fields.get(column).or_else(|| {
+        record.fields
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(column))
+            .map(|(_, value)| value)
+    })
+}
+
+/**
+ * Compare une valeur décodée à une valeur littérale SQL.
+ *
+ * Retourne None si la comparaison n'a pas de sens (NULL d'un côté ou de
+ * l'autre, types incompatibles).
+ *
+ * @param value - Valeur du champ
+ * @param literal - Valeur littérale de la requête
+ * @returns Option<Ordering> - Ordre de `value` par rapport à `literal`
+ *
+ * Effets de bord : Aucun
+ */
+pub fn compare_values(value: &FieldValue, literal: &SqlValue) -> Option<Ordering> {
+    match (value, literal) {
+        (FieldValue::Null { .. }, _) | (_, SqlValue::Null) => None,
+        (FieldValue::Integer { value }, SqlValue::Integer(i)) => Some(value.cmp(i)),
+        (FieldValue::Integer { value }, SqlValue::Float(f)) => (*value as f64).partial_cmp(f),
+        (FieldValue::Integer { value }, SqlValue::Boolean(b)) => Some(value.cmp(&(*b as i64))),
+        (FieldValue::Boolean { value }, SqlValue::Boolean(b)) => Some(value.cmp(b)),
+        (FieldValue::Boolean { value }, SqlValue::Integer(i)) => Some((*value as i64).cmp(i)),
+        (FieldValue::Boolean { value }, SqlValue::String(s)) => Some(value.cmp(&parse_boolean(s)?)),
+        (FieldValue::Float { value }, SqlValue::Integer(i)) => value.partial_cmp(&(*i as f64)),
+        (FieldValue::Float { value }, SqlValue::Float(f)) => value.partial_cmp(f),
+        (FieldValue::Decimal { value }, SqlValue::Integer(i)) => {
+            Some(Decimal::parse(value)?.cmp(&Decimal::new(*i as i128, 0)))
+        }
+        (FieldValue::Decimal { value }, SqlValue::Float(f)) => Decimal::parse(value)?.to_f64().partial_cmp(f),
+        // Littéral à virgule : comparaison exacte avec les entiers et les décimaux
+        (FieldValue::Decimal { value }, SqlValue::Decimal(d)) => {
+            Some(Decimal::parse(value)?.cmp(&Decimal::parse(d)?))
+        }
+        (FieldValue::Integer { value }, SqlValue::Decimal(d)) => {
+            Some(Decimal::new(*value as i128, 0).cmp(&Decimal::parse(d)?))
+        }
+        (FieldValue::Float { value }, SqlValue::Decimal(d)) => value.partial_cmp(&Decimal::parse(d)?.to_f64()),
+        (FieldValue::String { value }, SqlValue::Decimal(d)) => {
+            parse_number(value).and_then(|n| n.partial_cmp(&Decimal::parse(d)?.to_f64()))
+        }
+        (FieldValue::Decimal { value }, SqlValue::String(s)) => {
+            Some(Decimal::parse(value)?.cmp(&Decimal::parse(s)?))
+        }
+        (FieldValue::Integer { value }, SqlValue::String(s)) => {
+            parse_number(s).and_then(|n| (*value as f64).partial_cmp(&n))
+        }
+        (FieldValue::Float { value }, SqlValue::String(s)) => {
+            parse_number(s).and_then(|n| value.partial_cmp(&n))
+        }
+        (FieldValue::String { value }, SqlValue::Integer(i)) => {
+            parse_number(value).and_then(|n| n.partial_cmp(&(*i as f64)))
+        }
+        (FieldValue::String { value }, SqlValue::Float(f)) => {
+            parse_number(value).and_then(|n| n.partial_cmp(f))
+        }
+        (FieldValue::String { value }, SqlValue::String(s)) => Some(value.as_str().cmp(s.as_str())),
+        (FieldValue::Binary { value }, SqlValue::String(s)) => {
+            Some(value.as_str().cmp(s.to_lowercase().as_str()))
+        }
+        // Dates, heures et durées : ordre chronologique, littéral ISO-8601
+        // ou compact (un entier est une date AAAAMMJJ ou des millisecondes)
+        (FieldValue::Date { .. } | FieldValue::Duration { .. }, SqlValue::Integer(i)) => {
+            Some(temporal::key(value)?.cmp(&temporal::key_for_text(value, &i.to_string())?))
+        }
+        (FieldValue::Date { .. } | FieldValue::Time { .. } | FieldValue::DateTime { .. } | FieldValue::Duration { .. }, SqlValue::String(s)) => {
+            Some(temporal::key(value)?.cmp(&temporal::key_for_text(value, s)?))
+        }
+        _ => None,
+    }
+}
+
+/// Interprète une chaîne comme un nombre (espaces ignorés)
+fn parse_number(s: &str) -> Option<f64> {
+    s.trim().parse::<f64>().ok()
+}
+
+/**
+ * Interprète une chaîne comme un booléen.
+ *
+ * @param s - Texte (`true`/`false`, `vrai`/`faux`, `oui`/`non`, `1`/`0`,
+ *   casse et espaces ignorés)
+ * @returns Option<bool> - None si le texte n'est pas un booléen
+ */
+pub fn parse_boolean(s: &str) -> Option<bool> {
+    match s.trim().to_lowercase().as_str() {
+        "true" | "vrai" | "oui" | "1" => Some(true),
+        "false" | "faux" | "non" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Représentation texte d'une valeur de champ pour LIKE
+fn field_value_text(value: &FieldValue) -> Option<String> {
+    match value {
+        FieldValue::String { value } => Some(value.clone()),
+        FieldValue::Integer { value } => Some(value.to_string()),
+        FieldValue::Float { value } => Some(value.to_string()),
+        FieldValue::Boolean { value } => Some(value.to_string()),
+        FieldValue::Decimal { value }
+        | FieldValue::Date { value }
+        | FieldValue::Time { value }
+        | FieldValue::DateTime { value }
+        | FieldValue::Duration { value } => Some(value.clone()),
+        FieldValue::Binary { value } => Some(value.clone()),
+        FieldValue::Null { .. } => None,
+    }
+}
+
+/// Représentation texte d'une valeur littérale pour LIKE
+fn literal_text(literal: &SqlValue) -> Option<String> {
+    match literal {
+        SqlValue::String(s) => Some(s.clone()),
+        SqlValue::Integer(i) => Some(i.to_string()),
+        SqlValue::Float(f) => Some(f.to_string()),
+        SqlValue::Decimal(d) => Some(d.clone()),
+        SqlValue::Boolean(b) => Some(b.to_string()),
+        SqlValue::Null => None,
+    }
+}
+
+/**
+ * Teste une chaîne contre un motif LIKE.
+ *
+ * `%` correspond à une suite quelconque de caractères (éventuellement vide),
+ * `_` à exactement un caractère. La comparaison est insensible à la casse.
+ * L'algorithme glouton avec retour arrière sur le dernier `%` est linéaire
+ * dans la plupart des cas et ne récurse pas.
+ *
+ * @param text - Texte à tester
+ * @param pattern - Motif LIKE
+ * @returns bool - true si le texte correspond au motif
+ *
+ * Effets de bord : Aucun
+ */
+pub fn like_match(text: &str, pattern: &str) -> bool {
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let pattern: Vec<char> = pattern.chars().flat_map(char::to_lowercase).collect();
+
+    let (mut t, mut p) = (0usize, 0usize);
+    // Position du dernier '%' rencontré et position du texte associée
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '_' || (pattern[p] != '%' && pattern[p] == text[t])) {
+            t += 1;
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == '%' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            // Le '%' absorbe un caractère de plus
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '%')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql::parser::Condition;
+
+    fn record(fields: Vec<(&str, FieldValue)>) -> Record {
+        Record {
+            id: 0,
+            fields: fields.into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
+            memo_data: HashMap::new(),
+        }
+    }
+
+    fn condition(column: &str, operator: ComparisonOperator, value: SqlValue) -> Expr {
+        Expr::Comparison(Condition { column: column.to_string(), operator, value })
+    }
+
+    #[test]
+    fn test_like_match() {
+        assert!(like_match("DUPONT", "dup%"));
+        assert!(like_match("DUPONT", "%PON_"));
+        assert!(like_match("abc", "a_c"));
+        assert!(like_match("", "%"));
+        assert!(!like_match("abc", "a_"));
+        assert!(!like_match("abc", "%d%"));
+        assert!(like_match("aXbXc", "%x%c"));
+    }
+
+    #[test]
+    fn test_typed_comparisons() {
+        let r = record(vec![
+            ("NOM", FieldValue::string("Martin".to_string())),
+            ("AGE", FieldValue::integer(42)),
+            ("SOLDE", FieldValue::float(10.5)),
+            ("CP", FieldValue::string("75010".to_string())),
+            ("VIDE", FieldValue::null()),
+            ("NAISSANCE", FieldValue::Date { value: "1985-11-02".to_string() }),
+            ("DUREE", FieldValue::Duration { value: "PT1H30M".to_string() }),
+        ]);
+
+        assert!(matches_expr(&r, &condition("nom", ComparisonOperator::Equal, SqlValue::String("Martin".into()))));
+        assert!(matches_expr(&r, &condition("AGE", ComparisonOperator::GreaterThan, SqlValue::Integer(9))));
+        assert!(matches_expr(&r, &condition("AGE", ComparisonOperator::LessThanOrEqual, SqlValue::Float(42.0))));
+        assert!(matches_expr(&r, &condition("SOLDE", ComparisonOperator::GreaterThanOrEqual, SqlValue::Integer(10))));
+        assert!(matches_expr(&r, &condition("CP", ComparisonOperator::LessThan, SqlValue::Integer(80000))));
+        assert!(matches_expr(&r, &condition("NOM", ComparisonOperator::Like, SqlValue::String("mar%".into()))));
+        assert!(!matches_expr(&r, &condition("NOM", ComparisonOperator::NotEqual, SqlValue::String("Martin".into()))));
+        assert!(!matches_expr(&r, &condition("VIDE", ComparisonOperator::Equal, SqlValue::Null)));
+        assert!(!matches_expr(&r, &condition("INCONNU", ComparisonOperator::NotEqual, SqlValue::Integer(1))));
+        assert!(matches_expr(&r, &condition("NAISSANCE", ComparisonOperator::LessThan, SqlValue::String("1985-12-01".into()))));
+        assert!(matches_expr(&r, &condition("NAISSANCE", ComparisonOperator::Equal, SqlValue::Integer(19851102))));
+        assert!(matches_expr(&r, &condition("DUREE", ComparisonOperator::GreaterThan, SqlValue::String("PT45M".into()))));
+    }
+
+    #[test]
+    fn test_decimal_literals_are_exact() {
+        use crate::sql::parser::{SqlParser, SqlStatement};
+
+        let where_clause = |sql: &str| match SqlParser::parse(sql).unwrap() {
+            SqlStatement::Select(select) => select.where_clause.unwrap(),
+            other => panic!("SELECT attendu: {:?}", other),
+        };
+        let r = record(vec![
+            ("MONTANT", FieldValue::Decimal { value: "0.1000".to_string() }),
+            ("TOTAL", FieldValue::Decimal { value: "12345678901234.5679".to_string() }),
+            ("QTE", FieldValue::integer(3)),
+        ]);
+
+        assert!(matches_expr(&r, &where_clause("SELECT * FROM T WHERE MONTANT = 0.1")));
+        assert!(matches_expr(&r, &where_clause("SELECT * FROM T WHERE MONTANT > -0.1")));
+        // Indiscernables en f64
+        assert!(!matches_expr(&r, &where_clause("SELECT * FROM T WHERE TOTAL = 12345678901234.5678")));
+        assert!(matches_expr(&r, &where_clause("SELECT * FROM T WHERE TOTAL > 12345678901234.5678")));
+        assert!(matches_expr(&r, &where_clause("SELECT * FROM T WHERE QTE = 3.00")));
+        assert!(!matches_expr(&r, &where_clause("SELECT * FROM T WHERE QTE < 2.99")));
+    }
+
+    #[test]
+    fn test_three_valued_logic() {
+        let r = record(vec![
+            ("STATUT", FieldValue::string("B".to_string())),
+            ("DATE_CREA", FieldValue::null()),
+        ]);
+        let in_list = Expr::In {
+            column: "STATUT".to_string(),
+            values: vec![SqlValue::String("A".into()), SqlValue::String("B".into())],
+            negated: false,
+        };
+        let is_null = Expr::IsNull { column: "DATE_CREA".to_string(), negated: false };
+        let null_cmp = condition("DATE_CREA", ComparisonOperator::Equal, SqlValue::String("x".into()));
+
+        assert!(matches_expr(&r, &in_list));
+        assert!(matches_expr(&r, &is_null));
+        // NOT (NULL = 'x') reste inconnu, donc rejeté
+        assert!(!matches_expr(&r, &Expr::Not(Box::new(null_cmp.clone()))));
+        // inconnu OR vrai = vrai
+        assert!(matches_expr(&r, &Expr::Or(Box::new(null_cmp), Box::new(in_list))));
+    }
+
+    #[test]
+    fn test_unknown_columns_are_rejected() {
+        let is_known = |column: &str| ["NOM", "id"].iter().any(|c| c.eq_ignore_ascii_case(column));
+        let where_clause = Expr::And(
+            Box::new(condition("nom", ComparisonOperator::Equal, SqlValue::String("A".to_string()))),
+            Box::new(Expr::IsNull { column: "NOM_TYPO".to_string(), negated: false }),
+        );
+        let error = check_columns(&HashMap::new(), Some(&where_clause), is_known).unwrap_err();
+        assert!(error.to_string().contains("NOM_TYPO"));
+
+        let filters = HashMap::from([("ID".to_string(), "1".to_string())]);
+        assert!(check_columns(&filters, None, is_known).is_ok());
+        let filters = HashMap::from([("PRENOM".to_string(), "A".to_string())]);
+        assert!(check_columns(&filters, None, is_known).is_err());
+    }
+}
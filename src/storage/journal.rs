@@ -0,0 +1,494 @@
+/**
+ * Journal d'écriture (write-ahead) pour les modifications des fichiers HFSQL.
+ *
+ * Ce fichier garantit qu'une instruction d'écriture (INSERT, UPDATE, DELETE)
+ * est appliquée entièrement ou pas du tout, y compris lorsqu'elle touche
+ * plusieurs fichiers d'une table (.fic et .mmo).
+ *
+ * Principe (journal d'annulation) :
+ * 1. Les écritures sont préparées (PendingWrite) sans toucher aux fichiers
+ * 2. Les images « avant » des zones touchées et la taille d'origine de
+ *    chaque fichier sont écrites dans le journal, puis synchronisées
+ * 3. Les écritures sont appliquées puis synchronisées
+ * 4. La suppression du journal valide l'instruction
+ *
+ * À l'ouverture, un journal complet (somme de contrôle valide) signifie
+ * qu'une instruction a été interrompue : les images avant sont réécrites
+ * et les fichiers ramenés à leur taille d'origine. Un journal incomplet
+ * signifie que l'interruption a eu lieu avant toute modification des
+ * données : il est simplement supprimé.
+ *
+ * Format du fichier (little-endian) :
+ * - "FJNL", version (u32), nombre de fichiers (u32)
+ * - Par fichier : chemin (u16 + UTF-8), existait (u8), taille d'origine
+ *   (u64), nombre de zones (u32), puis par zone : offset (u64),
+ *   longueur (u32), bytes d'origine
+ * - Somme de contrôle FNV-1a 64 bits de tout ce qui précède
+ *
+ * Liens avec d'autres modules :
+ * - Utilisé par src/storage/engine.rs pour toutes les écritures
+ * - Les écritures sont préparées par src/core/fic.rs et src/core/mmo.rs
+ */
+
+use crate::core::PendingWrite;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Nom du fichier journal dans le dossier de données
+pub const JOURNAL_FILE_NAME: &str = "fic-engine.journal";
+
+const JOURNAL_MAGIC: &[u8; 4] = b"FJNL";
+const JOURNAL_VERSION: u32 = 1;
+
+/// Ensemble d'écritures formant une seule unité atomique
+#[derive(Debug, Clone, Default)]
+pub struct WriteBatch {
+    /// Écritures dans l'ordre d'application (fichier, écriture)
+    writes: Vec<(PathBuf, PendingWrite)>,
+}
+
+impl WriteBatch {
+    /// Crée un lot d'écritures vide
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * Ajoute des écritures préparées pour un fichier.
+     *
+     * @param path - Fichier concerné
+     * @param writes - Écritures, appliquées dans l'ordre donné
+     */
+    pub fn push(&mut self, path: &Path, writes: Vec<PendingWrite>) {
+        self.writes.extend(writes.into_iter().map(|w| (path.to_path_buf(), w)));
+    }
+
+    /// Ajoute toutes les écritures d'un autre lot à la suite
+    pub fn append(&mut self, other: WriteBatch) {
+        self.writes.extend(other.writes);
+    }
+
+    /// Indique si le lot ne contient aucune écriture
+    pub fn is_empty(&self) -> bool {
+        self.writes.is_empty()
+    }
+
+    /// Nombre d'écritures du lot
+    pub fn len(&self) -> usize {
+        self.writes.len()
+    }
+}
+
+/// État d'origine d'un fichier touché par un lot
+#[derive(Debug, Clone, PartialEq)]
+struct FileImage {
+    path: PathBuf,
+    existed: bool,
+    original_len: u64,
+    /// Zones d'origine (offset, bytes) recouvertes par les écritures
+    ranges: Vec<(u64, Vec<u8>)>,
+}
+
+/// Journal d'écriture associé à un dossier de données
+#[derive(Debug, Clone)]
+pub struct Journal {
+    /// Dossier de données (les chemins y sont enregistrés en relatif)
+    dir: PathBuf,
+    /// Chemin du fichier journal
+    path: PathBuf,
+}
+
+impl Journal {
+    /**
+     * Crée le journal d'un dossier de données.
+     *
+     * @param data_dir - Dossier contenant les fichiers HFSQL
+     * @returns Journal - Journal (aucun fichier n'est créé)
+     */
+    pub fn new(data_dir: impl AsRef<Path>) -> Self {
+        let dir = data_dir.as_ref().to_path_buf();
+        let path = dir.join(JOURNAL_FILE_NAME);
+        Self { dir, path }
+    }
+
+    /// Indique si un journal est présent (instruction interrompue)
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    /**
+     * Annule une instruction interrompue si un journal est présent.
+     *
+     * L'opération est idempotente : une interruption pendant la
+     * récupération est rattrapée à la récupération suivante.
+     *
+     * @returns Result<bool> - true si des fichiers ont été restaurés
+     *
+     * Effets de bord :
+     * - Réécrit les zones modifiées, tronque ou supprime les fichiers
+     * - Supprime le journal
+     */
+    pub fn recover(&self) -> Result<bool> {
+        let bytes = match std::fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e).with_context(|| format!("Impossible de lire le journal: {:?}", self.path)),
+        };
+
+        // Journal incomplet : les données n'ont pas encore été modifiées
+        let images = match self.decode(&bytes) {
+            Some(images) => images,
+            None => {
+                self.remove()?;
+                return Ok(false);
+            }
+        };
+
+        for image in &images {
+            if !image.existed {
+                if image.path.exists() {
+                    std::fs::remove_file(&image.path)
+                        .with_context(|| format!("Impossible de supprimer {:?}", image.path))?;
+                }
+                continue;
+            }
+            let mut file = OpenOptions::new()
+                .write(true)
+                .open(&image.path)
+                .with_context(|| format!("Impossible de restaurer {:?}", image.path))?;
+            for (offset, data) in &image.ranges {
+                file.seek(SeekFrom::Start(*offset))?;
+                file.write_all(data)?;
+            }
+            file.set_len(image.original_len)?;
+            file.sync_all()?;
+        }
+
+        self.remove()?;
+        Ok(true)
+    }
+
+    /**
+     * Applique un lot d'écritures de manière atomique.
+     *
+     * @param batch - Écritures à appliquer
+     * @returns Result<()> - Succès ou erreur (les fichiers sont alors
+     *   restaurés dans leur état d'origine lorsque c'est possible)
+     *
+     * Effets de bord :
+     * - Crée puis supprime le fichier journal
+     * - Modifie les fichiers du lot
+     */
+    pub fn commit(&self, batch: &WriteBatch) -> Result<()> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+        if let Err(e) = self.apply(batch, None) {
+            // Retour arrière immédiat ; à défaut, il aura lieu à la prochaine ouverture
+            let _ = self.recover();
+            return Err(e);
+        }
+        self.remove()
+    }
+
+    /**
+     * Écrit le journal puis les données du lot.
+     *
+     * @param batch - Écritures à appliquer
+     * @param crash_at - Simulation d'interruption (tests) : arrêt à
+     *   l'écriture n° .0 après en avoir écrit .1 bytes
+     */
+    fn apply(&self, batch: &WriteBatch, crash_at: Option<(usize, usize)>) -> Result<()> {
+        let images = capture_images(batch)?;
+        let journal_bytes = self.encode(&images);
+        {
+            let mut journal = File::create(&self.path)
+                .with_context(|| format!("Impossible de créer le journal: {:?}", self.path))?;
+            journal.write_all(&journal_bytes)?;
+            journal.sync_all()?;
+        }
+        sync_dir(&self.dir);
+
+        let mut files: HashMap<&Path, File> = HashMap::new();
+        for (step, (path, write)) in batch.writes.iter().enumerate() {
+            if !files.contains_key(path.as_path()) {
+                let file = OpenOptions::new()
+                    .write(true)
+                    .create(true)
+                    .truncate(false)
+                    .open(path)
+                    .with_context(|| format!("Impossible d'ouvrir le fichier en écriture: {:?}", path))?;
+                files.insert(path.as_path(), file);
+            }
+            let file = files.get_mut(path.as_path()).unwrap();
+            file.seek(SeekFrom::Start(write.offset))?;
+
+            match crash_at {
+                Some((crash_step, written)) if crash_step == step => {
+                    file.write_all(&write.data[..written.min(write.data.len())])?;
+                    return Ok(());
+                }
+                _ => file.write_all(&write.data)?,
+            }
+        }
+
+        for file in files.values_mut() {
+            file.sync_all()?;
+        }
+        Ok(())
+    }
+
+    /// Supprime le journal (validation de l'instruction)
+    fn remove(&self) -> Result<()> {
+        match std::fs::remove_file(&self.path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e).with_context(|| format!("Impossible de supprimer le journal: {:?}", self.path)),
+        }
+        sync_dir(&self.dir);
+        Ok(())
+    }
+
+    /// Sérialise les images avant (format décrit en tête de fichier)
+    fn encode(&self, images: &[FileImage]) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(JOURNAL_MAGIC);
+        out.extend_from_slice(&JOURNAL_VERSION.to_le_bytes());
+        out.extend_from_slice(&(images.len() as u32).to_le_bytes());
+        for image in images {
+            let path = image.path.strip_prefix(&self.dir).unwrap_or(&image.path);
+            let path = path.to_string_lossy();
+            out.extend_from_slice(&(path.len() as u16).to_le_bytes());
+            out.extend_from_slice(path.as_bytes());
+            out.push(image.existed as u8);
+            out.extend_from_slice(&image.original_len.to_le_bytes());
+            out.extend_from_slice(&(image.ranges.len() as u32).to_le_bytes());
+            for (offset, data) in &image.ranges {
+                out.extend_from_slice(&offset.to_le_bytes());
+                out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+                out.extend_from_slice(data);
+            }
+        }
+        let checksum = fnv1a(&out);
+        out.extend_from_slice(&checksum.to_le_bytes());
+        out
+    }
+
+    /// Relit un journal ; None s'il est incomplet ou corrompu
+    fn decode(&self, bytes: &[u8]) -> Option<Vec<FileImage>> {
+        if bytes.len() < 8 {
+            return None;
+        }
+        let (body, checksum) = bytes.split_at(bytes.len() - 8);
+        if fnv1a(body) != u64::from_le_bytes(checksum.try_into().ok()?) {
+            return None;
+        }
+
+        let mut reader = body;
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic).ok()?;
+        if &magic != JOURNAL_MAGIC || read_u32(&mut reader)? != JOURNAL_VERSION {
+            return None;
+        }
+        let file_count = read_u32(&mut reader)?;
+        let mut images = Vec::new();
+        for _ in 0..file_count {
+            let path_len = read_u16(&mut reader)? as usize;
+            let path = String::from_utf8(read_bytes(&mut reader, path_len)?).ok()?;
+            let existed = read_bytes(&mut reader, 1)?[0] != 0;
+            let original_len = read_u64(&mut reader)?;
+            let range_count = read_u32(&mut reader)?;
+            let mut ranges = Vec::new();
+            for _ in 0..range_count {
+                let offset = read_u64(&mut reader)?;
+                let len = read_u32(&mut reader)? as usize;
+                ranges.push((offset, read_bytes(&mut reader, len)?));
+            }
+            images.push(FileImage { path: self.dir.join(path), existed, original_len, ranges });
+        }
+        Some(images)
+    }
+}
+
+/**
+ * Capture l'état d'origine des fichiers touchés par un lot.
+ *
+ * Seules les zones existantes sont sauvegardées : les ajouts au-delà de la
+ * taille d'origine sont annulés par troncature.
+ */
+fn capture_images(batch: &WriteBatch) -> Result<Vec<FileImage>> {
+    let mut images: Vec<FileImage> = Vec::new();
+    let mut handles: HashMap<PathBuf, File> = HashMap::new();
+
+    for (path, write) in &batch.writes {
+        let index = match images.iter().position(|i| &i.path == path) {
+            Some(index) => index,
+            None => {
+                let (existed, original_len) = match std::fs::metadata(path) {
+                    Ok(meta) => (true, meta.len()),
+                    Err(_) => (false, 0),
+                };
+                if existed {
+                    let file = File::open(path)
+                        .with_context(|| format!("Impossible d'ouvrir le fichier: {:?}", path))?;
+                    handles.insert(path.clone(), file);
+                }
+                images.push(FileImage { path: path.clone(), existed, original_len, ranges: Vec::new() });
+                images.len() - 1
+            }
+        };
+
+        let image = &mut images[index];
+        let end = (write.offset + write.data.len() as u64).min(image.original_len);
+        if write.offset >= end {
+            continue;
+        }
+        let file = handles.get_mut(path).context("Fichier non ouvert")?;
+        file.seek(SeekFrom::Start(write.offset))?;
+        let mut data = vec![0u8; (end - write.offset) as usize];
+        file.read_exact(&mut data)?;
+        image.ranges.push((write.offset, data));
+    }
+    Ok(images)
+}
+
+/// Synchronise les entrées d'un dossier (création/suppression de fichiers)
+fn sync_dir(dir: &Path) {
+    // Non supporté sur toutes les plateformes (Windows) : erreur ignorée
+    if let Ok(handle) = File::open(dir) {
+        let _ = handle.sync_all();
+    }
+}
+
+/// Somme de contrôle FNV-1a 64 bits
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325u64, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn read_bytes(reader: &mut &[u8], len: usize) -> Option<Vec<u8>> {
+    if reader.len() < len {
+        return None;
+    }
+    let (head, tail) = reader.split_at(len);
+    *reader = tail;
+    Some(head.to_vec())
+}
+
+fn read_u16(reader: &mut &[u8]) -> Option<u16> {
+    Some(u16::from_le_bytes(read_bytes(reader, 2)?.try_into().ok()?))
+}
+
+fn read_u32(reader: &mut &[u8]) -> Option<u32> {
+    Some(u32::from_le_bytes(read_bytes(reader, 4)?.try_into().ok()?))
+}
+
+fn read_u64(reader: &mut &[u8]) -> Option<u64> {
+    Some(u64::from_le_bytes(read_bytes(reader, 8)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fichiers d'une table : .fic existant et .mmo créé par l'instruction
+    fn setup() -> Result<(tempfile::TempDir, WriteBatch)> {
+        let dir = tempfile::tempdir()?;
+        let fic = dir.path().join("t.fic");
+        std::fs::write(&fic, b"HEADER--0123456789")?;
+
+        let mut batch = WriteBatch::new();
+        batch.push(&dir.path().join("t.mmo"), vec![
+            PendingWrite { offset: 0, data: vec![0; 4] },
+            PendingWrite { offset: 4, data: b"\x03\0\0\0abc".to_vec() },
+        ]);
+        batch.push(&fic, vec![
+            PendingWrite { offset: 8, data: b"AB".to_vec() },
+            PendingWrite { offset: 16, data: b"XYZW".to_vec() },
+            PendingWrite { offset: 6, data: b"!!".to_vec() },
+        ]);
+        Ok((dir, batch))
+    }
+
+    fn snapshot(dir: &Path) -> Vec<(String, Vec<u8>)> {
+        let mut files: Vec<(String, Vec<u8>)> = std::fs::read_dir(dir).unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|p| p.file_name().unwrap() != JOURNAL_FILE_NAME)
+            .map(|p| (p.file_name().unwrap().to_string_lossy().to_string(), std::fs::read(&p).unwrap()))
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn test_commit_applies_all_writes() -> Result<()> {
+        let (dir, batch) = setup()?;
+        let journal = Journal::new(dir.path());
+        journal.commit(&batch)?;
+
+        assert!(!journal.exists());
+        assert_eq!(std::fs::read(dir.path().join("t.fic"))?, b"HEADER!!AB234567XYZW");
+        assert_eq!(std::fs::read(dir.path().join("t.mmo"))?, b"\0\0\0\0\x03\0\0\0abc");
+        assert!(!journal.recover()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_recover_after_crash_at_every_write_step() -> Result<()> {
+        let (dir, batch) = setup()?;
+        let original = snapshot(dir.path());
+
+        for step in 0..batch.len() {
+            let written = batch.writes[step].1.data.len();
+            for partial in [0, written / 2, written] {
+                let (dir, batch) = setup()?;
+                let journal = Journal::new(dir.path());
+                journal.apply(&batch, Some((step, partial)))?;
+                assert!(journal.exists());
+
+                assert!(journal.recover()?);
+                assert!(!journal.exists());
+                assert_eq!(snapshot(dir.path()), original, "interruption à l'écriture {} ({} bytes)", step, partial);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_recover_after_crash_while_writing_journal() -> Result<()> {
+        let (dir, batch) = setup()?;
+        let original = snapshot(dir.path());
+        let journal = Journal::new(dir.path());
+        let bytes = journal.encode(&capture_images(&batch)?);
+
+        // Journal tronqué à chaque longueur possible : données intactes, journal ignoré
+        for len in 0..bytes.len() {
+            std::fs::write(&journal.path, &bytes[..len])?;
+            assert!(!journal.recover()?);
+            assert!(!journal.exists());
+            assert_eq!(snapshot(dir.path()), original);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_recover_is_idempotent() -> Result<()> {
+        let (dir, batch) = setup()?;
+        let original = snapshot(dir.path());
+        let journal = Journal::new(dir.path());
+        journal.apply(&batch, None)?;
+
+        // Interruption pendant la récupération : le journal est toujours là
+        let bytes = std::fs::read(&journal.path)?;
+        journal.recover()?;
+        std::fs::write(dir.path().join("t.fic"), b"HEADER!!AB234567XY")?;
+        std::fs::write(&journal.path, bytes)?;
+        assert!(journal.recover()?);
+        assert_eq!(snapshot(dir.path()), original);
+        Ok(())
+    }
+}
@@ -7,19 +7,33 @@
  * - La détection et le scan des tables dans un dossier
  * - La lecture et l'écriture d'enregistrements
  * - La conversion des données brutes en structures typées
- * - Les requêtes avec filtres et pagination
+ * - Les requêtes avec filtres, tri et pagination
  * - La gestion des schémas de tables
  * 
  * Structure :
  * - engine.rs : Moteur de stockage principal (StorageEngine)
+ * - filter.rs : Évaluation des clauses WHERE sur les enregistrements décodés
+ * - sort.rs : Tri typé (ORDER BY) avec collation française et tri externe
+ * - decimal.rs : Décimaux exacts (monétaire, numérique)
+ * - temporal.rs : Dates, heures et durées HFSQL (ISO-8601, ordre chronologique)
+ * - journal.rs : Journal d'écriture garantissant l'atomicité des modifications
+ * - transaction.rs : Transactions multi-instructions (BEGIN/COMMIT/ROLLBACK)
  * 
  * Exports :
  * - StorageEngine : Moteur principal de stockage
+ * - Transaction : Transaction ouverte par StorageEngine::begin
  * - QueryFilters, QueryResult : Structures pour les requêtes
  * - Record, FieldValue : Structures pour les données
  */
 
+pub mod decimal;
 pub mod engine;
+pub mod filter;
+pub mod journal;
+pub mod sort;
+pub mod temporal;
+pub mod transaction;
 
-pub use engine::{QueryFilters, QueryResult, Record, StorageEngine};
+pub use engine::{IndexLookup, QueryFilters, QueryResult, Record, StorageEngine};
+pub use transaction::Transaction;
 
@@ -0,0 +1,491 @@
+/**
+ * Tri des enregistrements pour les clauses ORDER BY.
+ *
+ * Ce fichier contient la comparaison typée des valeurs de champs et le tri
+ * externe utilisé par StorageEngine::select :
+ *
+ * - Entiers, flottants et décimaux comparés numériquement (exactement entre
+ *   entiers et décimaux) ; faux < vrai, comme 0 < 1
+ * - Chaînes comparées avec une collation adaptée au français : les accents
+ *   et la casse ne départagent que des chaînes par ailleurs identiques
+ *   (« ecole » < « École » < « étude »), les espaces de fin sont ignorés
+ * - Dates, heures et durées dans l'ordre chronologique
+ * - NULL : plus petite valeur par défaut (en tête en ASC, en fin en DESC),
+ *   modifiable par NULLS FIRST / NULLS LAST
+ *
+ * Le tri externe découpe l'entrée en séquences triées en mémoire ; au-delà
+ * d'une séquence, chacune est écrite dans un fichier temporaire puis les
+ * fichiers sont fusionnés à la lecture. La mémoire utilisée reste bornée
+ * par la taille d'une séquence, quel que soit le nombre d'enregistrements.
+ *
+ * Liens avec d'autres modules :
+ * - Utilisé par src/storage/engine.rs dans StorageEngine::select
+ * - Utilise OrderByItem de src/sql/parser.rs
+ */
+
+use crate::sql::parser::OrderByItem;
+use crate::storage::decimal::Decimal;
+use crate::storage::engine::{FieldValue, Record};
+use crate::storage::filter;
+use crate::storage::temporal;
+use anyhow::{Context, Result};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Lines, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+/// Nombre d'enregistrements triés en mémoire avant écriture sur disque
+pub const DEFAULT_SORT_RUN_SIZE: usize = 50_000;
+
+/// Compteur pour nommer les fichiers temporaires de tri
+static RUN_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/**
+ * Compare deux chaînes selon une collation adaptée au français.
+ *
+ * Trois niveaux : lettres de base (sans accents ni casse, ligatures
+ * développées), puis accents, puis casse (minuscule avant majuscule).
+ *
+ * @param a - Première chaîne
+ * @param b - Seconde chaîne
+ * @returns Ordering - Ordre de `a` par rapport à `b`
+ *
+ * Effets de bord : Aucun
+ */
+pub fn collate(a: &str, b: &str) -> Ordering {
+    let (a, b) = (a.trim_end(), b.trim_end());
+    primary_key(a)
+        .cmp(&primary_key(b))
+        .then_with(|| a.to_lowercase().cmp(&b.to_lowercase()))
+        .then_with(|| case_key(a).cmp(case_key(b)))
+}
+
+/// Clé tertiaire : à lettres égales, la minuscule précède la majuscule
+fn case_key(s: &str) -> impl Iterator<Item = (bool, char)> + '_ {
+    s.chars().map(|c| (c.is_uppercase(), c))
+}
+
+/// Clé primaire de collation : minuscules sans accents, ligatures développées
+fn primary_key(s: &str) -> String {
+    let mut key = String::with_capacity(s.len());
+    for c in s.chars().flat_map(char::to_lowercase) {
+        match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => key.push('a'),
+            'ç' => key.push('c'),
+            'è' | 'é' | 'ê' | 'ë' => key.push('e'),
+            'ì' | 'í' | 'î' | 'ï' => key.push('i'),
+            'ñ' => key.push('n'),
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => key.push('o'),
+            'ù' | 'ú' | 'û' | 'ü' => key.push('u'),
+            'ý' | 'ÿ' => key.push('y'),
+            'æ' => key.push_str("ae"),
+            'œ' => key.push_str("oe"),
+            'ß' => key.push_str("ss"),
+            _ => key.push(c),
+        }
+    }
+    key
+}
+
+/**
+ * Compare deux valeurs non nulles de façon typée.
+ *
+ * Les nombres sont comparés numériquement (entier contre flottant compris),
+ * les dates, heures et durées dans l'ordre chronologique, les chaînes avec
+ * collate(). Entre types différents, l'ordre est :
+ * nombres < dates et durées < chaînes < binaires.
+ *
+ * @param a - Première valeur
+ * @param b - Seconde valeur
+ * @returns Ordering - Ordre total de `a` par rapport à `b`
+ *
+ * Effets de bord : Aucun
+ */
+pub fn compare_field_values(a: &FieldValue, b: &FieldValue) -> Ordering {
+    match (a, b) {
+        (FieldValue::Integer { value: x }, FieldValue::Integer { value: y }) => x.cmp(y),
+        (FieldValue::Integer { value: x }, FieldValue::Float { value: y }) => (*x as f64).total_cmp(y),
+        (FieldValue::Float { value: x }, FieldValue::Integer { value: y }) => x.total_cmp(&(*y as f64)),
+        (FieldValue::Float { value: x }, FieldValue::Float { value: y }) => x.total_cmp(y),
+        (FieldValue::Boolean { value: x }, FieldValue::Boolean { value: y }) => x.cmp(y),
+        (FieldValue::Decimal { .. } | FieldValue::Boolean { .. }, FieldValue::Integer { .. } | FieldValue::Float { .. } | FieldValue::Decimal { .. } | FieldValue::Boolean { .. })
+        | (FieldValue::Integer { .. } | FieldValue::Float { .. }, FieldValue::Decimal { .. } | FieldValue::Boolean { .. }) => {
+            compare_numbers(a, b)
+        }
+        (FieldValue::String { value: x }, FieldValue::String { value: y }) => collate(x, y),
+        (FieldValue::Binary { value: x }, FieldValue::Binary { value: y }) => x.cmp(y),
+        _ if type_rank(a) == 2 && type_rank(b) == 2 => {
+            // Même type temporel : ordre chronologique, sinon ordre des types
+            match (temporal::key(a), temporal::key(b)) {
+                (Some(x), Some(y)) if std::mem::discriminant(a) == std::mem::discriminant(b) => x.cmp(&y),
+                _ => temporal_rank(a).cmp(&temporal_rank(b)),
+            }
+        }
+        _ => type_rank(a).cmp(&type_rank(b)),
+    }
+}
+
+/// Rang d'un type de valeur pour les comparaisons entre types différents
+fn type_rank(value: &FieldValue) -> u8 {
+    match value {
+        FieldValue::Null { .. } => 0,
+        FieldValue::Integer { .. } | FieldValue::Float { .. } | FieldValue::Decimal { .. } | FieldValue::Boolean { .. } => 1,
+        FieldValue::Date { .. } | FieldValue::Time { .. } | FieldValue::DateTime { .. } | FieldValue::Duration { .. } => 2,
+        FieldValue::String { .. } => 3,
+        FieldValue::Binary { .. } => 4,
+    }
+}
+
+/// Compare deux nombres dont l'un au moins est décimal ou booléen (exactement sauf face à un flottant)
+fn compare_numbers(a: &FieldValue, b: &FieldValue) -> Ordering {
+    let exact = |value: &FieldValue| match value {
+        FieldValue::Decimal { value } => Decimal::parse(value),
+        FieldValue::Integer { value } => Some(Decimal::new(*value as i128, 0)),
+        FieldValue::Boolean { value } => Some(Decimal::new(*value as i128, 0)),
+        _ => None,
+    };
+    let approx = |value: &FieldValue| match value {
+        FieldValue::Float { value } => *value,
+        FieldValue::Integer { value } => *value as f64,
+        other => exact(other).map_or(f64::NAN, |d| d.to_f64()),
+    };
+    match (exact(a), exact(b)) {
+        (Some(x), Some(y)) => x.cmp(&y),
+        _ => approx(a).total_cmp(&approx(b)),
+    }
+}
+
+/// Rang d'un type temporel pour les comparaisons entre types temporels différents
+fn temporal_rank(value: &FieldValue) -> u8 {
+    match value {
+        FieldValue::Date { .. } => 0,
+        FieldValue::DateTime { .. } => 1,
+        FieldValue::Time { .. } => 2,
+        _ => 3,
+    }
+}
+
+/**
+ * Compare deux enregistrements selon les critères ORDER BY.
+ *
+ * Les noms de colonnes sont insensibles à la casse ; une colonne absente
+ * vaut NULL, et `id` désigne l'identifiant de l'enregistrement s'il n'existe
+ * pas de champ de ce nom. À critères égaux, l'ordre physique (id) est
+ * conservé pour que le tri soit déterministe.
+ *
+ * @param a - Premier enregistrement
+ * @param b - Second enregistrement
+ * @param order_by - Critères de tri
+ * @returns Ordering - Ordre de `a` par rapport à `b`
+ *
+ * Effets de bord : Aucun
+ */
+pub fn compare_records(a: &Record, b: &Record, order_by: &[OrderByItem]) -> Ordering {
+    for item in order_by {
+        let ordering = compare_keys(sort_key(a, &item.column), sort_key(b, &item.column), item);
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    a.id.cmp(&b.id)
+}
+
+/// Compare deux valeurs pour un critère, en plaçant les NULL selon le critère
+fn compare_keys(a: Option<Cow<FieldValue>>, b: Option<Cow<FieldValue>>, item: &OrderByItem) -> Ordering {
+    let nulls_first = item.nulls_first.unwrap_or(!item.descending);
+    match (a, b) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => if nulls_first { Ordering::Less } else { Ordering::Greater },
+        (Some(_), None) => if nulls_first { Ordering::Greater } else { Ordering::Less },
+        (Some(x), Some(y)) => {
+            let ordering = compare_field_values(&x, &y);
+            if item.descending { ordering.reverse() } else { ordering }
+        }
+    }
+}
+
+/// Valeur de tri d'une colonne (None pour NULL ou colonne absente)
+fn sort_key<'a>(record: &'a Record, column: &str) -> Option<Cow<'a, FieldValue>> {
+    match filter::lookup_field(record, column) {
+        Some(FieldValue::Null { .. }) => None,
+        Some(value) => Some(Cow::Borrowed(value)),
+        None if column.eq_ignore_ascii_case("id") => Some(Cow::Owned(FieldValue::integer(record.id as i64))),
+        None => None,
+    }
+}
+
+/**
+ * Trieur externe d'enregistrements.
+ *
+ * Les enregistrements sont accumulés en mémoire jusqu'à `run_size` ; chaque
+ * séquence pleine est triée et écrite dans un fichier temporaire (JSON, une
+ * ligne par enregistrement). finish() fusionne les séquences.
+ */
+pub struct ExternalSorter {
+    order_by: Vec<OrderByItem>,
+    run_size: usize,
+    buffer: Vec<Record>,
+    runs: Vec<RunFile>,
+    len: usize,
+}
+
+impl ExternalSorter {
+    /**
+     * Crée un trieur externe.
+     *
+     * @param order_by - Critères de tri
+     * @param run_size - Nombre maximum d'enregistrements gardés en mémoire
+     * @returns ExternalSorter - Trieur vide
+     */
+    pub fn new(order_by: Vec<OrderByItem>, run_size: usize) -> Self {
+        Self {
+            order_by,
+            run_size: run_size.max(1),
+            buffer: Vec::new(),
+            runs: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /**
+     * Ajoute un enregistrement à trier.
+     *
+     * Effets de bord :
+     * - Écrit une séquence triée dans un fichier temporaire quand le
+     *   tampon mémoire est plein
+     */
+    pub fn push(&mut self, record: Record) -> Result<()> {
+        self.buffer.push(record);
+        self.len += 1;
+        if self.buffer.len() >= self.run_size {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    /// Nombre total d'enregistrements ajoutés
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Indique si aucun enregistrement n'a été ajouté
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /**
+     * Termine l'ajout et retourne les enregistrements triés.
+     *
+     * Si tout tient en mémoire, aucun fichier n'est écrit. Sinon les
+     * séquences sont fusionnées au fil de l'itération ; les fichiers
+     * temporaires sont supprimés quand l'itérateur est libéré.
+     *
+     * @returns Result<SortedRecords> - Itérateur sur les enregistrements triés
+     */
+    pub fn finish(mut self) -> Result<SortedRecords> {
+        if self.runs.is_empty() {
+            let order_by = self.order_by;
+            self.buffer.sort_by(|a, b| compare_records(a, b, &order_by));
+            return Ok(SortedRecords { inner: SortedInner::Memory(self.buffer.into_iter()) });
+        }
+
+        if !self.buffer.is_empty() {
+            self.spill()?;
+        }
+
+        let mut readers = Vec::with_capacity(self.runs.len());
+        let mut heads = Vec::with_capacity(self.runs.len());
+        for run in &self.runs {
+            let file = File::open(&run.path)
+                .with_context(|| format!("Impossible de relire le fichier de tri {:?}", run.path))?;
+            let mut lines = BufReader::new(file).lines();
+            heads.push(read_run_record(&mut lines)?);
+            readers.push(lines);
+        }
+
+        Ok(SortedRecords {
+            inner: SortedInner::Merge {
+                order_by: self.order_by,
+                readers,
+                heads,
+                _runs: self.runs,
+            },
+        })
+    }
+
+    /// Trie le tampon et l'écrit dans un nouveau fichier temporaire
+    fn spill(&mut self) -> Result<()> {
+        let order_by = &self.order_by;
+        self.buffer.sort_by(|a, b| compare_records(a, b, order_by));
+
+        let path = std::env::temp_dir().join(format!(
+            "fic-engine-sort-{}-{}.jsonl",
+            std::process::id(),
+            RUN_COUNTER.fetch_add(1, AtomicOrdering::Relaxed)
+        ));
+        let run = RunFile { path };
+        let file = File::create(&run.path)
+            .with_context(|| format!("Impossible de créer le fichier de tri {:?}", run.path))?;
+        let mut writer = BufWriter::new(file);
+        for record in self.buffer.drain(..) {
+            serde_json::to_writer(&mut writer, &record)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+
+        self.runs.push(run);
+        Ok(())
+    }
+}
+
+/// Fichier temporaire contenant une séquence triée, supprimé à la libération
+struct RunFile {
+    path: PathBuf,
+}
+
+impl Drop for RunFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Lit l'enregistrement suivant d'une séquence (None en fin de fichier)
+fn read_run_record(lines: &mut Lines<BufReader<File>>) -> Result<Option<Record>> {
+    match lines.next() {
+        Some(line) => Ok(Some(serde_json::from_str(&line?).context("Fichier de tri corrompu")?)),
+        None => Ok(None),
+    }
+}
+
+/// Itérateur sur les enregistrements triés produit par ExternalSorter::finish
+pub struct SortedRecords {
+    inner: SortedInner,
+}
+
+/// Source des enregistrements triés
+enum SortedInner {
+    /// Tout le tri a tenu en mémoire
+    Memory(std::vec::IntoIter<Record>),
+    /// Fusion de séquences écrites sur disque
+    Merge {
+        order_by: Vec<OrderByItem>,
+        readers: Vec<Lines<BufReader<File>>>,
+        /// Prochain enregistrement de chaque séquence
+        heads: Vec<Option<Record>>,
+        _runs: Vec<RunFile>,
+    },
+}
+
+impl Iterator for SortedRecords {
+    type Item = Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            SortedInner::Memory(records) => records.next().map(Ok),
+            SortedInner::Merge { order_by, readers, heads, .. } => {
+                // Le nombre de séquences est faible : une recherche linéaire
+                // du plus petit en-tête suffit
+                let mut smallest: Option<usize> = None;
+                for (i, head) in heads.iter().enumerate() {
+                    if let Some(record) = head {
+                        let is_smaller = smallest.is_none_or(|s| {
+                            let current = heads[s].as_ref().expect("en-tête présent");
+                            compare_records(record, current, order_by) == Ordering::Less
+                        });
+                        if is_smaller {
+                            smallest = Some(i);
+                        }
+                    }
+                }
+
+                let index = smallest?;
+                let next_head = match read_run_record(&mut readers[index]) {
+                    Ok(record) => record,
+                    Err(e) => return Some(Err(e)),
+                };
+                std::mem::replace(&mut heads[index], next_head).map(Ok)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn record(id: u32, nom: Option<&str>, age: i64) -> Record {
+        let mut fields = HashMap::new();
+        fields.insert(
+            "NOM".to_string(),
+            nom.map(|n| FieldValue::string(n.to_string())).unwrap_or_else(FieldValue::null),
+        );
+        fields.insert("AGE".to_string(), FieldValue::integer(age));
+        Record { id, fields, memo_data: HashMap::new() }
+    }
+
+    fn order(column: &str, descending: bool, nulls_first: Option<bool>) -> OrderByItem {
+        OrderByItem { column: column.to_string(), descending, nulls_first }
+    }
+
+    #[test]
+    fn test_french_collation() {
+        let mut words = vec!["étude", "Zoé", "ecole", "École", "cote", "côte", "Cote", "œuvre", "odeur"];
+        words.sort_by(|a, b| collate(a, b));
+        assert_eq!(words, vec!["cote", "Cote", "côte", "ecole", "École", "étude", "odeur", "œuvre", "Zoé"]);
+        assert_eq!(collate("DUPONT   ", "DUPONT"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_typed_order_and_nulls() {
+        let records = vec![
+            record(0, Some("Martin"), 9),
+            record(1, None, 10),
+            record(2, Some("durand"), 100),
+            record(3, Some("Émile"), 10),
+        ];
+
+        let mut by_age = records.clone();
+        by_age.sort_by(|a, b| compare_records(a, b, &[order("age", false, None), order("NOM", true, None)]));
+        assert_eq!(by_age.iter().map(|r| r.id).collect::<Vec<_>>(), vec![0, 3, 1, 2]);
+
+        let mut by_name = records.clone();
+        by_name.sort_by(|a, b| compare_records(a, b, &[order("NOM", false, None)]));
+        assert_eq!(by_name.iter().map(|r| r.id).collect::<Vec<_>>(), vec![1, 2, 3, 0]);
+
+        by_name.sort_by(|a, b| compare_records(a, b, &[order("NOM", false, Some(false))]));
+        assert_eq!(by_name.iter().map(|r| r.id).collect::<Vec<_>>(), vec![2, 3, 0, 1]);
+
+        by_name.sort_by(|a, b| compare_records(a, b, &[order("NOM", true, None)]));
+        assert_eq!(by_name.iter().map(|r| r.id).collect::<Vec<_>>(), vec![0, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_external_sort_matches_in_memory_sort() -> Result<()> {
+        let order_by = vec![order("AGE", true, None)];
+        let records: Vec<Record> = (0..50u32)
+            .map(|i| record(i, Some("x"), ((i * 37) % 11) as i64))
+            .collect();
+
+        let mut expected = records.clone();
+        expected.sort_by(|a, b| compare_records(a, b, &order_by));
+
+        let mut sorter = ExternalSorter::new(order_by, 7);
+        for r in records {
+            sorter.push(r)?;
+        }
+        assert_eq!(sorter.len(), 50);
+        assert!(!sorter.runs.is_empty());
+        let run_paths: Vec<PathBuf> = sorter.runs.iter().map(|r| r.path.clone()).collect();
+
+        let sorted = sorter.finish()?.collect::<Result<Vec<_>>>()?;
+        assert_eq!(
+            sorted.iter().map(|r| r.id).collect::<Vec<_>>(),
+            expected.iter().map(|r| r.id).collect::<Vec<_>>()
+        );
+        assert!(run_paths.iter().all(|p| !p.exists()));
+        Ok(())
+    }
+}
@@ -0,0 +1,440 @@
+/**
+ * Dates, heures, dates-heures et durées HFSQL.
+ *
+ * Ce fichier contient le décodage des représentations HFSQL sur disque vers
+ * des valeurs ISO-8601, l'encodage inverse pour les écritures, et les clés
+ * numériques utilisées pour comparer ces valeurs dans l'ordre chronologique
+ * (WHERE, ORDER BY).
+ *
+ * Représentations sur disque :
+ * - Date : 8 caractères `AAAAMMJJ`
+ * - Heure : caractères `HHMMSS`, `HHMMSSCC` (centièmes) ou `HHMMSSmmm`
+ * - Date-heure : 8 bytes compactés (année u16, mois u8, jour u8, heure u8,
+ *   minute u8, millisecondes dans la minute u16, little-endian), ou
+ *   caractères `AAAAMMJJHHMMSS[CC|mmm]`
+ * - Durée : entier signé de millisecondes (4 ou 8 bytes little-endian)
+ *
+ * Valeurs ISO-8601 produites :
+ * - Date : `2024-01-15`
+ * - Heure : `14:30:05` ou `14:30:05.250`
+ * - Date-heure : `2024-01-15T14:30:05.250`
+ * - Durée : `P1DT2H30M`, `PT0.5S`, `-PT10M`
+ *
+ * Liens avec d'autres modules :
+ * - Utilisé par src/storage/engine.rs (décodage et encodage des champs)
+ * - Utilisé par src/storage/filter.rs et src/storage/sort.rs (comparaisons)
+ */
+
+use crate::core::FieldType;
+use crate::storage::engine::FieldValue;
+
+/// Nombre de millisecondes dans une journée
+const MS_PER_DAY: i64 = 86_400_000;
+
+/**
+ * Décode la zone d'un champ temporel.
+ *
+ * Une zone vide (zéros, espaces ou `0` uniquement) vaut NULL, sauf pour
+ * une durée où la valeur 0 est une durée nulle.
+ *
+ * @param field_type - Type du champ (Date, Time, DateTime ou Duration)
+ * @param data - Zone du champ dans l'enregistrement
+ * @returns Option<FieldValue> - Valeur décodée, None si la zone ne contient
+ *   pas une valeur valide (l'appelant la décode alors comme texte)
+ */
+pub fn decode(field_type: &FieldType, data: &[u8]) -> Option<FieldValue> {
+    if matches!(field_type, FieldType::Duration) {
+        let millis = match data.len() {
+            4 => i32::from_le_bytes(data.try_into().ok()?) as i64,
+            8 => i64::from_le_bytes(data.try_into().ok()?),
+            _ => ascii(data)?.parse::<i64>().ok()?,
+        };
+        return Some(FieldValue::Duration { value: format_duration(millis) });
+    }
+
+    if data.iter().all(|&b| b == 0 || b == b' ' || b == b'0') {
+        return Some(FieldValue::null());
+    }
+    match field_type {
+        FieldType::Date => {
+            let text = ascii(data)?;
+            if text.len() != 8 {
+                return None;
+            }
+            Some(FieldValue::Date { value: format_date(parse_date(text)?) })
+        }
+        FieldType::Time => {
+            let text = ascii(data)?;
+            if !text.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            Some(FieldValue::Time { value: format_time(parse_time(text)?) })
+        }
+        FieldType::DateTime => {
+            let key = if data.len() == 8 && !data.iter().all(|b| b.is_ascii_digit()) {
+                unpack_datetime(data)?
+            } else {
+                parse_datetime(ascii(data)?)?
+            };
+            Some(FieldValue::DateTime { value: format_datetime(key) })
+        }
+        _ => None,
+    }
+}
+
+/**
+ * Encode une valeur dans la représentation sur disque d'un champ temporel.
+ *
+ * Les chaînes sont acceptées au format ISO-8601 ou compact (`20240115`) ;
+ * un entier est lu comme `AAAAMMJJ` pour une date et comme un nombre de
+ * millisecondes pour une durée. Une chaîne vide donne une zone de zéros.
+ *
+ * @param field_type - Type du champ (Date, Time, DateTime ou Duration)
+ * @param value - Valeur à encoder
+ * @param length - Taille de la zone du champ
+ * @returns Option<Vec<u8>> - Exactement `length` bytes, None si la valeur
+ *   ou la taille ne conviennent pas
+ */
+pub fn encode(field_type: &FieldType, value: &FieldValue, length: usize) -> Option<Vec<u8>> {
+    let text = match value {
+        FieldValue::Integer { value } => value.to_string(),
+        _ => value_text(value)?.trim().to_string(),
+    };
+    if text.is_empty() {
+        return Some(vec![0u8; length]);
+    }
+
+    let mut bytes = match field_type {
+        FieldType::Date if length >= 8 => format!("{:08}", parse_date(&text)?).into_bytes(),
+        FieldType::Time => {
+            let ms = parse_time(&text)?;
+            let (h, m, s, milli) = (ms / 3_600_000, ms / 60_000 % 60, ms / 1000 % 60, ms % 1000);
+            match length {
+                4 => format!("{:02}{:02}", h, m),
+                6 => format!("{:02}{:02}{:02}", h, m, s),
+                8 => format!("{:02}{:02}{:02}{:02}", h, m, s, milli / 10),
+                9 => format!("{:02}{:02}{:02}{:03}", h, m, s, milli),
+                _ => return None,
+            }
+            .into_bytes()
+        }
+        FieldType::DateTime => {
+            let key = parse_datetime(&text)?;
+            let (date, ms) = (key.div_euclid(MS_PER_DAY), key.rem_euclid(MS_PER_DAY));
+            let (h, m, s, milli) = (ms / 3_600_000, ms / 60_000 % 60, ms / 1000 % 60, ms % 1000);
+            match length {
+                8 => {
+                    let mut packed = Vec::with_capacity(8);
+                    packed.extend_from_slice(&((date / 10_000) as u16).to_le_bytes());
+                    packed.push((date / 100 % 100) as u8);
+                    packed.push((date % 100) as u8);
+                    packed.push(h as u8);
+                    packed.push(m as u8);
+                    packed.extend_from_slice(&((s * 1000 + milli) as u16).to_le_bytes());
+                    packed
+                }
+                14 => format!("{:08}{:02}{:02}{:02}", date, h, m, s).into_bytes(),
+                16 => format!("{:08}{:02}{:02}{:02}{:02}", date, h, m, s, milli / 10).into_bytes(),
+                17 => format!("{:08}{:02}{:02}{:02}{:03}", date, h, m, s, milli).into_bytes(),
+                _ => return None,
+            }
+        }
+        FieldType::Duration => {
+            let ms = match value {
+                FieldValue::Integer { value } => *value,
+                _ => parse_duration(&text)?,
+            };
+            match length {
+                4 => i32::try_from(ms).ok()?.to_le_bytes().to_vec(),
+                8 => ms.to_le_bytes().to_vec(),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    bytes.resize(length, 0);
+    Some(bytes)
+}
+
+/**
+ * Clé numérique d'une valeur temporelle, croissante dans l'ordre
+ * chronologique (durées : millisecondes).
+ *
+ * @param value - Valeur décodée
+ * @returns Option<i64> - Clé, None si la valeur n'est pas temporelle
+ */
+pub fn key(value: &FieldValue) -> Option<i64> {
+    match value {
+        FieldValue::Date { value } => parse_date(value),
+        FieldValue::Time { value } => parse_time(value),
+        FieldValue::DateTime { value } => parse_datetime(value),
+        FieldValue::Duration { value } => parse_duration(value),
+        _ => None,
+    }
+}
+
+/**
+ * Interprète un texte (littéral SQL) comme une valeur du même type
+ * temporel que `like` et retourne sa clé.
+ *
+ * Une date comparée à une date-heure n'en garde que le jour ; une
+ * date-heure comparée à une date commence à minuit.
+ *
+ * @param like - Valeur donnant le type attendu
+ * @param text - Texte à interpréter
+ * @returns Option<i64> - Clé comparable à key(like)
+ */
+pub fn key_for_text(like: &FieldValue, text: &str) -> Option<i64> {
+    let text = text.trim();
+    match like {
+        FieldValue::Date { .. } => parse_date(text)
+            .or_else(|| parse_datetime(text).map(|key| key.div_euclid(MS_PER_DAY))),
+        FieldValue::Time { .. } => parse_time(text),
+        FieldValue::DateTime { .. } => parse_datetime(text),
+        FieldValue::Duration { .. } => parse_duration(text),
+        _ => None,
+    }
+}
+
+/// Texte ISO-8601 d'une valeur temporelle, ou texte d'une chaîne
+pub fn value_text(value: &FieldValue) -> Option<&str> {
+    match value {
+        FieldValue::String { value }
+        | FieldValue::Date { value }
+        | FieldValue::Time { value }
+        | FieldValue::DateTime { value }
+        | FieldValue::Duration { value } => Some(value),
+        _ => None,
+    }
+}
+
+/// Zone ASCII sans les zéros et espaces de fin
+fn ascii(data: &[u8]) -> Option<&str> {
+    let end = data.iter().rposition(|&b| b != 0 && b != b' ').map_or(0, |i| i + 1);
+    let text = std::str::from_utf8(&data[..end]).ok()?;
+    text.is_ascii().then_some(text)
+}
+
+/// Entier décimal composé uniquement de chiffres
+fn digits(text: &str) -> Option<i64> {
+    if text.is_empty() || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+/// Clé AAAAMMJJ d'une date valide
+fn date_key(year: i64, month: i64, day: i64) -> Option<i64> {
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        _ => return None,
+    };
+    (1..=days_in_month).contains(&day).then_some(year * 10_000 + month * 100 + day)
+}
+
+/// `AAAA-MM-JJ` ou `AAAAMMJJ` -> clé AAAAMMJJ
+fn parse_date(text: &str) -> Option<i64> {
+    let compact: String = match text.len() {
+        8 => text.to_string(),
+        10 if text.as_bytes()[4] == b'-' && text.as_bytes()[7] == b'-' => text.replace('-', ""),
+        _ => return None,
+    };
+    if compact.len() != 8 {
+        return None;
+    }
+    date_key(digits(&compact[..4])?, digits(&compact[4..6])?, digits(&compact[6..])?)
+}
+
+/// `HH:MM[:SS[.fff]]` ou `HHMM[SS[CC|mmm]]` -> millisecondes depuis minuit
+fn parse_time(text: &str) -> Option<i64> {
+    let (h, m, s, ms) = if text.contains(':') {
+        let (main, fraction) = match text.split_once('.') {
+            Some((main, fraction)) => (main, Some(fraction)),
+            None => (text, None),
+        };
+        let mut parts = main.split(':');
+        let h = digits(parts.next()?)?;
+        let m = digits(parts.next()?)?;
+        let s = parts.next().map(digits).unwrap_or(Some(0))?;
+        if parts.next().is_some() {
+            return None;
+        }
+        let ms = match fraction {
+            Some(fraction) => {
+                digits(fraction)?;
+                let padded = format!("{:0<3}", fraction);
+                padded[..3].parse::<i64>().ok()?
+            }
+            None => 0,
+        };
+        (h, m, s, ms)
+    } else {
+        let field = |range: std::ops::Range<usize>| text.get(range).and_then(digits);
+        match text.len() {
+            4 => (field(0..2)?, field(2..4)?, 0, 0),
+            6 => (field(0..2)?, field(2..4)?, field(4..6)?, 0),
+            8 => (field(0..2)?, field(2..4)?, field(4..6)?, field(6..8)? * 10),
+            9 => (field(0..2)?, field(2..4)?, field(4..6)?, field(6..9)?),
+            _ => return None,
+        }
+    };
+    if h >= 24 || m >= 60 || s >= 60 {
+        return None;
+    }
+    Some(((h * 60 + m) * 60 + s) * 1000 + ms)
+}
+
+/// Date seule, `date[T| ]heure` ou `AAAAMMJJ[HHMMSS[CC|mmm]]` -> clé
+fn parse_datetime(text: &str) -> Option<i64> {
+    let (date, time) = if let Some(split) = text.find(['T', ' ']) {
+        (&text[..split], Some(&text[split + 1..]))
+    } else if text.len() > 10 && !text.contains('-') {
+        (&text[..8], Some(&text[8..]))
+    } else {
+        (text, None)
+    };
+    let ms = match time {
+        Some(time) => parse_time(time)?,
+        None => 0,
+    };
+    Some(parse_date(date)? * MS_PER_DAY + ms)
+}
+
+/// Date-heure compactée sur 8 bytes -> clé
+fn unpack_datetime(data: &[u8]) -> Option<i64> {
+    let year = u16::from_le_bytes([data[0], data[1]]) as i64;
+    let (month, day, hour, minute) = (data[2] as i64, data[3] as i64, data[4] as i64, data[5] as i64);
+    let millis = u16::from_le_bytes([data[6], data[7]]) as i64;
+    if hour >= 24 || minute >= 60 || millis >= 60_000 {
+        return None;
+    }
+    Some(date_key(year, month, day)? * MS_PER_DAY + (hour * 60 + minute) * 60_000 + millis)
+}
+
+/// Durée ISO-8601 (`[-]P[nD][T[nH][nM][n[.f]S]]`) ou millisecondes -> millisecondes
+fn parse_duration(text: &str) -> Option<i64> {
+    let (negative, rest) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let Some(rest) = rest.strip_prefix('P') else {
+        return text.parse::<i64>().ok();
+    };
+
+    let mut total = 0i64;
+    let mut in_time = false;
+    let mut number = String::new();
+    for c in rest.chars() {
+        match c {
+            '0'..='9' | '.' => number.push(c),
+            'T' if number.is_empty() && !in_time => in_time = true,
+            _ => {
+                let unit = match (c, in_time) {
+                    ('W', false) => 7 * MS_PER_DAY,
+                    ('D', false) => MS_PER_DAY,
+                    ('H', true) => 3_600_000,
+                    ('M', true) => 60_000,
+                    ('S', true) => 1000,
+                    _ => return None,
+                };
+                let value = number.parse::<f64>().ok()?;
+                total = total.checked_add((value * unit as f64).round() as i64)?;
+                number.clear();
+            }
+        }
+    }
+    if !number.is_empty() {
+        return None;
+    }
+    Some(if negative { -total } else { total })
+}
+
+/// Clé AAAAMMJJ -> `AAAA-MM-JJ`
+fn format_date(key: i64) -> String {
+    format!("{:04}-{:02}-{:02}", key / 10_000, key / 100 % 100, key % 100)
+}
+
+/// Millisecondes depuis minuit -> `HH:MM:SS[.mmm]`
+fn format_time(ms: i64) -> String {
+    let base = format!("{:02}:{:02}:{:02}", ms / 3_600_000, ms / 60_000 % 60, ms / 1000 % 60);
+    match ms % 1000 {
+        0 => base,
+        milli => format!("{}.{:03}", base, milli),
+    }
+}
+
+/// Clé de date-heure -> `AAAA-MM-JJTHH:MM:SS[.mmm]`
+fn format_datetime(key: i64) -> String {
+    format!("{}T{}", format_date(key.div_euclid(MS_PER_DAY)), format_time(key.rem_euclid(MS_PER_DAY)))
+}
+
+/// Millisecondes -> durée ISO-8601
+fn format_duration(ms: i64) -> String {
+    let sign = if ms < 0 { "-" } else { "" };
+    let ms = ms.unsigned_abs();
+    let (days, hours, minutes, seconds, milli) = (
+        ms / MS_PER_DAY as u64,
+        ms / 3_600_000 % 24,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000,
+    );
+
+    let mut out = format!("{}P", sign);
+    if days > 0 {
+        out.push_str(&format!("{}D", days));
+    }
+    if hours > 0 || minutes > 0 || seconds > 0 || milli > 0 || days == 0 {
+        out.push('T');
+        if hours > 0 {
+            out.push_str(&format!("{}H", hours));
+        }
+        if minutes > 0 {
+            out.push_str(&format!("{}M", minutes));
+        }
+        if milli > 0 {
+            let fraction = format!("{:03}", milli);
+            out.push_str(&format!("{}.{}S", seconds, fraction.trim_end_matches('0')));
+        } else if seconds > 0 || (hours == 0 && minutes == 0 && days == 0) {
+            out.push_str(&format!("{}S", seconds));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_temporal_round_trip() {
+        let date = decode(&FieldType::Date, b"20240229").unwrap();
+        assert_eq!(date, FieldValue::Date { value: "2024-02-29".to_string() });
+        assert_eq!(decode(&FieldType::Date, b"00000000"), Some(FieldValue::null()));
+        assert_eq!(decode(&FieldType::Date, b"20230229"), None);
+        assert_eq!(encode(&FieldType::Date, &date, 8).unwrap(), b"20240229");
+
+        let time = decode(&FieldType::Time, b"14300525").unwrap();
+        assert_eq!(time, FieldValue::Time { value: "14:30:05.250".to_string() });
+        assert_eq!(encode(&FieldType::Time, &time, 6).unwrap(), b"143005");
+
+        let text = FieldValue::string("2024-01-15T08:05:00.5".to_string());
+        let packed = encode(&FieldType::DateTime, &text, 8).unwrap();
+        let datetime = decode(&FieldType::DateTime, &packed).unwrap();
+        assert_eq!(datetime, FieldValue::DateTime { value: "2024-01-15T08:05:00.500".to_string() });
+        assert_eq!(decode(&FieldType::DateTime, b"20240115080500500"), Some(datetime.clone()));
+
+        let duration = decode(&FieldType::Duration, &(-(90_061_500i64)).to_le_bytes()).unwrap();
+        assert_eq!(duration, FieldValue::Duration { value: "-P1DT1H1M1.5S".to_string() });
+        assert_eq!(key(&duration), Some(-90_061_500));
+        assert_eq!(format_duration(0), "PT0S");
+
+        // Les clés suivent l'ordre chronologique
+        assert!(key(&datetime) < key_for_text(&datetime, "2024-01-15 09:00"));
+        assert_eq!(key_for_text(&date, "2024-02-29T23:00:00"), key(&date));
+        assert!(key_for_text(&time, "9:00") < key(&time));
+    }
+}
@@ -0,0 +1,157 @@
+/**
+ * Transactions multi-instructions pour le moteur de stockage.
+ *
+ * Une transaction accumule les écritures préparées (PendingWrite) de
+ * plusieurs instructions, sur une ou plusieurs tables, sans toucher aux
+ * fichiers. Le COMMIT applique l'ensemble en un seul lot via le journal
+ * (src/storage/journal.rs) : tout ou rien, y compris après un crash.
+ *
+ * Isolation :
+ * - Les lectures faites dans la transaction voient ses propres
+ *   modifications (les emplacements modifiés remplacent ceux du disque)
+ * - Les autres sessions lisent les fichiers, donc l'état d'avant la
+ *   transaction, jusqu'au COMMIT
+ * - Une table modifiée par une transaction est verrouillée en écriture
+ *   jusqu'au COMMIT ou ROLLBACK ; une autre écriture sur cette table
+ *   échoue immédiatement (pas d'attente, donc pas d'interblocage)
+ *
+ * Les index .ndx de chaque table modifiée sont tenus à jour en mémoire à
+ * chaque écriture ; leurs pages modifiées sont ajoutées au lot du COMMIT.
+ *
+ * Limite : les mémos écrits dans la transaction ne sont lisibles qu'après
+ * le COMMIT (les blocs ne sont pas encore dans le .mmo).
+ *
+ * Liens avec d'autres modules :
+ * - Créée et validée par src/storage/engine.rs (begin, commit, rollback)
+ * - Conservée par session dans src/sql/server.rs
+ */
+
+use crate::core::{FicFile, MmoFile, NdxFile, RawRecord, TableFiles};
+use crate::storage::journal::WriteBatch;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+/// Verrous d'écriture par table (nom de table -> id de transaction)
+pub(crate) type TableLocks = Arc<Mutex<HashMap<String, u64>>>;
+
+/// Modifications en attente sur une table
+pub(crate) struct TableChanges {
+    /// Fichiers de la table
+    pub(crate) files: TableFiles,
+    /// Fichier .fic (header en mémoire tenant compte des ajouts/suppressions)
+    pub(crate) fic: FicFile,
+    /// Fichier .mmo (fin de fichier tenant compte des blocs ajoutés)
+    pub(crate) mmo: Option<MmoFile>,
+    /// Index .ndx de la table (pages modifiées gardées en mémoire)
+    pub(crate) indexes: Vec<NdxFile>,
+    /// Emplacements modifiés ou ajoutés (byte de flags inclus), par index
+    pub(crate) slots: BTreeMap<u32, Vec<u8>>,
+    /// Écritures préparées, dans l'ordre des instructions
+    pub(crate) batch: WriteBatch,
+}
+
+impl TableChanges {
+    /**
+     * Applique les modifications en attente à une lecture du disque.
+     *
+     * Les enregistrements modifiés sont remplacés, ceux supprimés dans la
+     * transaction sont retirés et ceux ajoutés sont placés à la suite. La
+     * fusion se fait au fil de la lecture, sans copier les enregistrements.
+     *
+     * @param records - Enregistrements actifs lus depuis le .fic, dans l'ordre des ids
+     * @returns impl Iterator<Item = RawRecord> - Enregistrements vus par la
+     *   transaction, dans l'ordre des ids
+     */
+    pub(crate) fn overlay<'a>(&'a self, records: impl Iterator<Item = RawRecord<'a>> + 'a) -> impl Iterator<Item = RawRecord<'a>> + 'a {
+        let mut disk = records
+            .filter(|r| !self.slots.contains_key(&r.id))
+            .peekable();
+        let mut pending = self.slots
+            .iter()
+            .filter(|(_, slot)| slot[0] & 0x01 == 0)
+            .map(|(id, slot)| RawRecord { id: *id, deleted: false, data: Cow::Borrowed(&slot[1..]) })
+            .peekable();
+        std::iter::from_fn(move || match (disk.peek(), pending.peek()) {
+            (Some(read), Some(changed)) if changed.id < read.id => pending.next(),
+            (Some(_), _) => disk.next(),
+            (None, _) => pending.next(),
+        })
+    }
+}
+
+/// Transaction ouverte par BEGIN (ou implicite pour une instruction seule)
+pub struct Transaction {
+    /// Identifiant interne (propriétaire des verrous), jamais exposé au client
+    id: u64,
+    /// Modifications par table, dans l'ordre de première modification
+    pub(crate) tables: Vec<(String, TableChanges)>,
+    /// Verrous partagés avec le moteur, libérés à la fin de la transaction
+    locks: TableLocks,
+}
+
+impl Transaction {
+    pub(crate) fn new(id: u64, locks: TableLocks) -> Self {
+        Self { id, tables: Vec::new(), locks }
+    }
+
+    /// Indique si la transaction contient des modifications
+    pub fn is_empty(&self) -> bool {
+        self.tables.iter().all(|(_, changes)| changes.batch.is_empty())
+    }
+
+    /// Indique si la transaction a modifié une table
+    pub fn touches(&self, table: &str) -> bool {
+        self.changes(table).is_some()
+    }
+
+    /// Modifications en attente sur une table
+    pub(crate) fn changes(&self, table: &str) -> Option<&TableChanges> {
+        self.tables.iter().find(|(name, _)| name == table).map(|(_, c)| c)
+    }
+
+    /// Modifications en attente sur une table (modifiables)
+    pub(crate) fn changes_mut(&mut self, table: &str) -> Option<&mut TableChanges> {
+        self.tables.iter_mut().find(|(name, _)| name == table).map(|(_, c)| c)
+    }
+
+    /**
+     * Réserve une table en écriture pour cette transaction.
+     *
+     * @returns anyhow::Result<()> - Erreur si une autre transaction modifie la table
+     */
+    pub(crate) fn lock_table(&self, table: &str) -> anyhow::Result<()> {
+        let mut locks = self.locks.lock().unwrap();
+        match locks.get(table) {
+            Some(owner) if *owner != self.id => anyhow::bail!(
+                "La table '{}' est en cours de modification par une autre transaction",
+                table
+            ),
+            _ => {
+                locks.insert(table.to_string(), self.id);
+                Ok(())
+            }
+        }
+    }
+
+    /// Regroupe toutes les écritures en un seul lot (ordre des tables conservé,
+    /// pages d'index à la suite des écritures de chaque table)
+    pub(crate) fn take_batch(&mut self) -> WriteBatch {
+        let mut batch = WriteBatch::new();
+        for (_, changes) in self.tables.drain(..) {
+            batch.append(changes.batch);
+            for ndx in &changes.indexes {
+                batch.push(ndx.path(), ndx.plan_writes());
+            }
+        }
+        batch
+    }
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        if let Ok(mut locks) = self.locks.lock() {
+            locks.retain(|_, owner| *owner != self.id);
+        }
+    }
+}